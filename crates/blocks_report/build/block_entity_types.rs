@@ -0,0 +1,42 @@
+use crate::blocks_report_loader::generated_data_dir;
+use blocks_report_data::block_entity_types::BlockEntityTypes;
+use minecraft_protocol::prelude::LengthPaddedVec;
+use protocol_version::protocol_version::ProtocolVersion;
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+
+pub struct BlockEntityTypesReport {
+    pub protocol_version: ProtocolVersion,
+    pub block_entity_types: BlockEntityTypes,
+}
+
+/// Loads the `block_entity_types.json` report of every version, mapping every type to its registry ID.
+pub fn load_block_entity_types() -> anyhow::Result<Vec<BlockEntityTypesReport>> {
+    let mut reports: Vec<BlockEntityTypesReport> = fs::read_dir(generated_data_dir()?)?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let protocol_version = ProtocolVersion::from_str(&name).ok()?;
+            let report_path = entry.path().join("reports").join("block_entity_types.json");
+            println!("cargo:rerun-if-changed={}", report_path.display());
+            let report = fs::read_to_string(&report_path).ok()?;
+            let ids: HashMap<String, usize> = serde_json::from_str(&report).ok()?;
+            Some(BlockEntityTypesReport {
+                protocol_version,
+                block_entity_types: build_block_entity_types(ids),
+            })
+        })
+        .collect();
+
+    reports.sort_by_key(|report| report.protocol_version);
+    Ok(reports)
+}
+
+fn build_block_entity_types(ids: HashMap<String, usize>) -> BlockEntityTypes {
+    let mut names = vec![String::new(); ids.values().max().map_or(0, |max| max + 1)];
+    for (name, id) in ids {
+        names[id] = name;
+    }
+    LengthPaddedVec::new(names)
+}
@@ -0,0 +1,31 @@
+use blocks_report_data::block_properties::{BlockPropertiesTable, BlockStateProperties};
+use blocks_report_data::internal_mapping::{InternalId, InternalMapping};
+use minecraft_protocol::prelude::LengthPaddedVec;
+
+/// Computes the properties of every internal state, indexed by internal ID.
+pub fn build_block_properties(internal_mapping: &InternalMapping) -> BlockPropertiesTable {
+    let state_count = internal_mapping
+        .mapping
+        .inner()
+        .iter()
+        .flat_map(|block| block.states.inner())
+        .map(|state| state.internal_id as usize + 1)
+        .max()
+        .unwrap_or_default();
+
+    let mut table: Vec<BlockStateProperties> = (0..state_count)
+        .map(|internal_id| BlockStateProperties::unknown(internal_id as InternalId))
+        .collect();
+
+    for block in internal_mapping.mapping.inner() {
+        for state in block.states.inner() {
+            table[state.internal_id as usize] = BlockStateProperties::new(
+                &block.name,
+                state.properties.inner(),
+                block.default_internal_id,
+            );
+        }
+    }
+
+    LengthPaddedVec::new(table)
+}
@@ -43,14 +43,19 @@ pub struct BlockState {
     pub default: bool,
 }
 
-pub fn load_block_data() -> anyhow::Result<Vec<BlocksReport>> {
-    let data_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR")?)
+/// Directory holding the data generated by every supported version of the server.
+pub fn generated_data_dir() -> anyhow::Result<PathBuf> {
+    Ok(PathBuf::from(env::var("CARGO_MANIFEST_DIR")?)
         .parent()
         .unwrap()
         .parent()
         .unwrap()
         .join("data")
-        .join("generated");
+        .join("generated"))
+}
+
+pub fn load_block_data() -> anyhow::Result<Vec<BlocksReport>> {
+    let data_dir = generated_data_dir()?;
 
     let mut block_data_list: Vec<BlocksReport> = fs::read_dir(data_dir)?
         .filter_map(|result| result.ok())
@@ -59,7 +64,8 @@ pub fn load_block_data() -> anyhow::Result<Vec<BlocksReport>> {
             ProtocolVersion::from_str(&name)
                 .ok()
                 .and_then(|protocol_version| {
-                    if protocol_version.is_after_inclusive(ProtocolVersion::V1_19) {
+                    // Block states were flattened in 1.13
+                    if protocol_version.is_after_inclusive(ProtocolVersion::V1_13) {
                         let version_path = entry.path();
                         let blocks_report_path = version_path.join("reports").join("blocks.json");
                         println!("cargo:rerun-if-changed={}", blocks_report_path.display());
@@ -80,6 +86,6 @@ pub fn load_block_data() -> anyhow::Result<Vec<BlocksReport>> {
         })
         .collect();
 
-    block_data_list.sort_by(|a, b| a.protocol_version.cmp(&b.protocol_version));
+    block_data_list.sort_by_key(|a| a.protocol_version);
     Ok(block_data_list)
 }
@@ -1,9 +1,11 @@
 use crate::blocks_report_loader::BlocksReport;
 use crate::internal_mapping::sort_internal_properties;
 use blocks_report_data::internal_mapping::{InternalId, InternalMapping, InternalProperties};
+use blocks_report_data::legacy_mapping::legacy_block_id;
 use blocks_report_data::report_mapping::{BlocksReportId, ReportMapping};
 use minecraft_protocol::prelude::LengthPaddedVec;
-use std::collections::HashMap;
+use protocol_version::protocol_version::ProtocolVersion;
+use std::collections::{BTreeSet, HashMap};
 
 pub fn build_report_mappings(
     blocks_reports: &[BlocksReport],
@@ -48,3 +50,39 @@ pub fn build_report_mappings(
 
     all_mappings
 }
+
+/// Versions prior to the flattening do not have blocks reports,
+/// their block states are derived from the flattened block states instead.
+pub fn build_legacy_report_mappings(internal_mapping: &InternalMapping) -> Vec<ReportMapping> {
+    let legacy_versions: BTreeSet<ProtocolVersion> = ProtocolVersion::ALL_VERSION
+        .iter()
+        .filter(|version| !version.is_modern() && !version.is_any())
+        .map(|version| version.reports())
+        .collect();
+
+    let num_internal_states = internal_mapping
+        .mapping
+        .inner()
+        .iter()
+        .map(|mapping| mapping.states.inner().len())
+        .sum();
+
+    legacy_versions
+        .into_iter()
+        .map(|protocol_version| {
+            let mut report_vec: Vec<BlocksReportId> = vec![0; num_internal_states];
+
+            for mapping in internal_mapping.mapping.inner() {
+                for state in mapping.states.inner() {
+                    report_vec[state.internal_id as usize] =
+                        legacy_block_id(&mapping.name, state.properties.inner(), protocol_version);
+                }
+            }
+
+            ReportMapping {
+                protocol_version,
+                mapping: LengthPaddedVec::new(report_vec),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,91 @@
+use blocks_report_data::internal_mapping::{InternalBlockMapping, InternalMapping, InternalState};
+use blocks_report_data::legacy_mapping::{LEGACY_BLOCK_COUNT, LegacyBlockNames, flattened_from};
+use minecraft_protocol::prelude::LengthPaddedVec;
+
+/// Inverts the flattening: every legacy block state gets the flattened state created from it.
+/// When several flattened states come from the same legacy state, the closest one to its block's
+/// default state is kept, for instance a straight stair rather than a corner.
+pub fn build_legacy_block_names(internal_mapping: &InternalMapping) -> LegacyBlockNames {
+    let mut candidates: Vec<Option<(Candidate, String)>> = vec![None; LEGACY_BLOCK_COUNT];
+
+    for block in internal_mapping.mapping.inner() {
+        for state in block.states.inner() {
+            let Some(legacy_id) = flattened_from(&block.name, state.properties.inner()) else {
+                continue;
+            };
+            let Some(entry) = candidates.get_mut(legacy_id as usize) else {
+                continue;
+            };
+            let candidate = Candidate::new(block, state);
+            if entry
+                .as_ref()
+                .is_none_or(|(best, _)| candidate.is_better_than(best))
+            {
+                *entry = Some((candidate, state_string(block, state)));
+            }
+        }
+    }
+
+    LengthPaddedVec::new(
+        candidates
+            .into_iter()
+            .map(|candidate| candidate.map(|(_, name)| name).unwrap_or_default())
+            .collect(),
+    )
+}
+
+#[derive(Clone)]
+struct Candidate {
+    properties_like_default: usize,
+    name_length: usize,
+    internal_id: u16,
+}
+
+impl Candidate {
+    fn new(block: &InternalBlockMapping, state: &InternalState) -> Self {
+        let default_state = block
+            .states
+            .inner()
+            .iter()
+            .find(|default_state| default_state.internal_id == block.default_internal_id);
+        let properties_like_default = default_state.map_or(0, |default_state| {
+            state
+                .properties
+                .inner()
+                .iter()
+                .filter(|property| default_state.properties.inner().contains(property))
+                .count()
+        });
+
+        Self {
+            properties_like_default,
+            name_length: block.name.len(),
+            internal_id: state.internal_id,
+        }
+    }
+
+    fn is_better_than(&self, other: &Self) -> bool {
+        (
+            std::cmp::Reverse(self.properties_like_default),
+            self.name_length,
+            self.internal_id,
+        ) < (
+            std::cmp::Reverse(other.properties_like_default),
+            other.name_length,
+            other.internal_id,
+        )
+    }
+}
+
+/// Formats a state the way `BlockStateLookup` parses it, `name[key=value,...]`.
+fn state_string(block: &InternalBlockMapping, state: &InternalState) -> String {
+    let properties = state.properties.inner();
+    if properties.is_empty() {
+        return block.name.clone();
+    }
+    let properties: Vec<String> = properties
+        .iter()
+        .map(|property| format!("{}={}", property.name, property.value))
+        .collect();
+    format!("{}[{}]", block.name, properties.join(","))
+}
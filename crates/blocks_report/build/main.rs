@@ -1,10 +1,16 @@
+pub mod block_entity_types;
+pub mod block_properties;
 pub mod blocks_report_loader;
 pub mod build_report_mappings;
 pub mod internal_mapping;
+pub mod legacy_block_names;
 
+use crate::block_entity_types::load_block_entity_types;
+use crate::block_properties::build_block_properties;
 use crate::blocks_report_loader::{BlocksReport, load_block_data};
-use crate::build_report_mappings::build_report_mappings;
+use crate::build_report_mappings::{build_legacy_report_mappings, build_report_mappings};
 use crate::internal_mapping::build_internal_id_mapping;
+use crate::legacy_block_names::build_legacy_block_names;
 use minecraft_protocol::prelude::{BinaryWriter, EncodePacket};
 use proc_macro2::{Ident, Span};
 use protocol_version::protocol_version::ProtocolVersion;
@@ -26,9 +32,18 @@ fn main() -> anyhow::Result<()> {
     let save_path = out_path.join("internal_mapping");
     write(&internal_mapping, &save_path)?;
 
-    // 4. Create report mappings
+    // 4. Compute and serialize the properties of every internal state
+    let block_properties = build_block_properties(&internal_mapping);
+    write(&block_properties, &out_path.join("block_properties"))?;
+
+    // 5. Invert the flattening to load pre-1.13 schematics
+    let legacy_block_names = build_legacy_block_names(&internal_mapping);
+    write(&legacy_block_names, &out_path.join("legacy_block_names"))?;
+
+    // 6. Create report mappings
     let mut mappings_arms = Vec::new();
-    let report_mappings = build_report_mappings(&blocks_reports, &internal_mapping);
+    let mut report_mappings = build_report_mappings(&blocks_reports, &internal_mapping);
+    report_mappings.extend(build_legacy_report_mappings(&internal_mapping));
     for mapping in report_mappings {
         let file_name = format!("version_mapping_{}", mapping.protocol_version);
         let save_path = out_path.join(file_name);
@@ -47,6 +62,26 @@ fn main() -> anyhow::Result<()> {
         mappings_arms.push(arm);
     }
 
+    // 7. Serialize the block entity types of every version
+    let mut block_entity_types_arms = Vec::new();
+    for report in load_block_entity_types()? {
+        let file_name = format!("block_entity_types_{}", report.protocol_version);
+        let save_path = out_path.join(file_name);
+        write(&report.block_entity_types, &save_path)?;
+
+        let version_ident = Ident::new(&report.protocol_version.to_string(), Span::call_site());
+        let file_path_str = save_path.to_str().unwrap().to_string();
+        let arm = quote! {
+            ProtocolVersion::#version_ident => {
+                let bytes = include_bytes!(#file_path_str);
+                let mut reader = minecraft_protocol::prelude::BinaryReader::new(bytes);
+                Ok(BlockEntityTypes::decode(&mut reader, minecraft_protocol::prelude::ProtocolVersion::latest())?)
+            },
+        };
+
+        block_entity_types_arms.push(arm);
+    }
+
     let generated_code = quote! {
 
         #[allow(clippy::match_same_arms)]
@@ -56,6 +91,14 @@ fn main() -> anyhow::Result<()> {
                 _ => Err(BlockReportIdMappingError::UnsupportedVersion(protocol_version)),
             }
         }
+
+        #[allow(clippy::match_same_arms, clippy::match_single_binding)]
+        pub fn get_block_entity_types_report(protocol_version: minecraft_protocol::prelude::ProtocolVersion) -> Result<BlockEntityTypes, BlockReportIdMappingError> {
+            match protocol_version {
+                #(#block_entity_types_arms)*
+                _ => Err(BlockReportIdMappingError::UnsupportedVersion(protocol_version)),
+            }
+        }
     };
 
     let dest_path = out_path.join("get_blocks_reports.rs");
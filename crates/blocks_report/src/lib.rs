@@ -2,14 +2,20 @@ use minecraft_protocol::prelude::{BinaryReader, BinaryReaderError, DecodePacket,
 use thiserror::Error;
 
 pub use blocks_report_data::{
+    block_entity_types::{BlockEntityTypes, block_entity_type_id},
+    block_properties::{BlockPropertiesTable, BlockStateProperties},
     block_state_builder::BlockStateLookup,
     internal_mapping::{InternalId, InternalMapping},
+    legacy_mapping::{LegacyBlockId, LegacyBlockNames},
     report_mapping::{BlocksReportId, ReportIdMapping},
 };
 
 include!(concat!(env!("OUT_DIR"), "/get_blocks_reports.rs"));
 
 static INTERNAL_MAPPING_DATA: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/internal_mapping"));
+static LEGACY_BLOCK_NAMES_DATA: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/legacy_block_names"));
+static BLOCK_PROPERTIES_DATA: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/block_properties"));
 
 #[derive(Debug, Error)]
 pub enum BlockReportIdMappingError {
@@ -24,10 +30,47 @@ pub fn load_internal_mapping() -> Result<InternalMapping, BinaryReaderError> {
     InternalMapping::decode(&mut reader, ProtocolVersion::latest())
 }
 
+/// Loads the luminance, light opacity, collision and default state of every internal state.
+pub fn load_block_properties() -> Result<BlockPropertiesTable, BinaryReaderError> {
+    let mut reader = BinaryReader::new(BLOCK_PROPERTIES_DATA);
+    BlockPropertiesTable::decode(&mut reader, ProtocolVersion::latest())
+}
+
+/// Loads the flattened block state string of every pre-1.13 block state.
+pub fn load_legacy_block_names() -> Result<LegacyBlockNames, BinaryReaderError> {
+    let mut reader = BinaryReader::new(LEGACY_BLOCK_NAMES_DATA);
+    LegacyBlockNames::decode(&mut reader, ProtocolVersion::latest())
+}
+
+/// Hash of the bundled internal mapping, block properties and legacy block names.
+/// It changes whenever the internal IDs, or anything derived from them when loading a world, do.
+pub fn block_data_version() -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+    [
+        INTERNAL_MAPPING_DATA,
+        BLOCK_PROPERTIES_DATA,
+        LEGACY_BLOCK_NAMES_DATA,
+    ]
+    .iter()
+    .flat_map(|data| data.iter())
+    .fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
 pub fn get_block_report_id_mapping(
     protocol_version: ProtocolVersion,
 ) -> Result<ReportIdMapping, BlockReportIdMappingError> {
-    get_blocks_reports(protocol_version)
+    // Patch releases share the blocks report of the version they get their reports from
+    get_blocks_reports(protocol_version.reports())
+}
+
+/// Loads the block entity types of a version, whose IDs are sent in chunks since 1.18.
+pub fn get_block_entity_types(
+    protocol_version: ProtocolVersion,
+) -> Result<BlockEntityTypes, BlockReportIdMappingError> {
+    get_block_entity_types_report(protocol_version.reports())
 }
 
 pub fn get_block_id(
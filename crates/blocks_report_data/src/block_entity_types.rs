@@ -0,0 +1,37 @@
+use minecraft_protocol::prelude::LengthPaddedVec;
+
+/// Names of the block entity types of a version, indexed by their registry ID.
+pub type BlockEntityTypes = LengthPaddedVec<String>;
+
+/// Returns the registry ID of a block entity type, such as `minecraft:sign`.
+pub fn block_entity_type_id(block_entity_types: &BlockEntityTypes, name: &str) -> Option<i32> {
+    block_entity_types
+        .inner()
+        .iter()
+        .position(|type_name| type_name == name)
+        .map(|id| id as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_entity_type_id() {
+        // Given
+        let block_entity_types = LengthPaddedVec::new(vec![
+            "minecraft:furnace".to_string(),
+            "minecraft:chest".to_string(),
+        ]);
+
+        // Then
+        assert_eq!(
+            block_entity_type_id(&block_entity_types, "minecraft:chest"),
+            Some(1)
+        );
+        assert_eq!(
+            block_entity_type_id(&block_entity_types, "minecraft:sign"),
+            None
+        );
+    }
+}
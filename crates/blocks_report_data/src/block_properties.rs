@@ -0,0 +1,165 @@
+use crate::internal_mapping::{InternalId, InternalProperties};
+use crate::light_properties::{MAX_LIGHT_LEVEL, light_opacity, luminance};
+use minecraft_protocol::prelude::*;
+
+/// Properties of every internal block state, indexed by internal ID.
+pub type BlockPropertiesTable = LengthPaddedVec<BlockStateProperties>;
+
+/// Server side properties of a block state, packed in 4 bytes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PacketOut, PacketIn)]
+pub struct BlockStateProperties {
+    /// Luminance in the high nibble, light opacity in the low nibble.
+    light: u8,
+    flags: u8,
+    /// Default state of the block this state belongs to.
+    default_state: InternalId,
+}
+
+impl BlockStateProperties {
+    const FULL_CUBE: u8 = 0b001;
+    const AIR: u8 = 0b010;
+    const LIQUID: u8 = 0b100;
+
+    /// Properties of a state missing from the table, an opaque block without collision.
+    pub const fn unknown(internal_id: InternalId) -> Self {
+        Self {
+            light: MAX_LIGHT_LEVEL,
+            flags: 0,
+            default_state: internal_id,
+        }
+    }
+
+    pub fn new(name: &str, properties: &[InternalProperties], default_state: InternalId) -> Self {
+        let luminance = luminance(name, properties);
+        let opacity = light_opacity(name, properties);
+        let mut flags = 0;
+        if has_full_cube_collision(name, properties) {
+            flags |= Self::FULL_CUBE;
+        }
+        if is_air(name) {
+            flags |= Self::AIR;
+        }
+        if is_liquid(name) {
+            flags |= Self::LIQUID;
+        }
+
+        Self {
+            light: (luminance.min(MAX_LIGHT_LEVEL) << 4) | opacity.min(MAX_LIGHT_LEVEL),
+            flags,
+            default_state,
+        }
+    }
+
+    /// Light emitted by the block, from 0 to 15.
+    pub const fn luminance(&self) -> u8 {
+        self.light >> 4
+    }
+
+    /// Light absorbed by the block, from 0 to 15.
+    pub const fn light_opacity(&self) -> u8 {
+        self.light & 0x0F
+    }
+
+    /// Whether players collide with the whole 1×1×1 block.
+    pub const fn has_full_cube_collision(&self) -> bool {
+        self.flags & Self::FULL_CUBE != 0
+    }
+
+    pub const fn is_air(&self) -> bool {
+        self.flags & Self::AIR != 0
+    }
+
+    pub const fn is_liquid(&self) -> bool {
+        self.flags & Self::LIQUID != 0
+    }
+
+    pub const fn default_state(&self) -> InternalId {
+        self.default_state
+    }
+}
+
+fn is_air(name: &str) -> bool {
+    matches!(
+        name.strip_prefix("minecraft:").unwrap_or(name),
+        "air" | "cave_air" | "void_air"
+    )
+}
+
+fn is_liquid(name: &str) -> bool {
+    matches!(
+        name.strip_prefix("minecraft:").unwrap_or(name),
+        "water" | "lava" | "bubble_column"
+    )
+}
+
+/// Opaque blocks are full cubes, apart from the few ones players sink into.
+/// Some blocks letting light through still are full cubes.
+fn has_full_cube_collision(name: &str, properties: &[InternalProperties]) -> bool {
+    const SHORTER_BLOCKS: &[&str] = &["soul_sand", "farmland", "dirt_path", "mud", "honey_block"];
+    const SEE_THROUGH_CUBES: &[&str] = &[
+        "glass",
+        "tinted_glass",
+        "barrier",
+        "ice",
+        "slime_block",
+        "spawner",
+        "beacon",
+    ];
+
+    if is_air(name) || is_liquid(name) {
+        return false;
+    }
+    let short_name = name.strip_prefix("minecraft:").unwrap_or(name);
+    if SHORTER_BLOCKS.contains(&short_name) {
+        return false;
+    }
+
+    light_opacity(name, properties) == MAX_LIGHT_LEVEL
+        || SEE_THROUGH_CUBES.contains(&short_name)
+        || short_name.ends_with("_stained_glass")
+        || short_name.ends_with("_leaves")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_properties_are_packed() {
+        // Given
+        let properties = BlockStateProperties::new("minecraft:glowstone", &[], 42);
+
+        // When
+        let mut writer = BinaryWriter::default();
+        properties
+            .encode(&mut writer, ProtocolVersion::latest())
+            .unwrap();
+        let bytes = writer.into_inner();
+
+        // Then
+        assert_eq!(bytes, vec![0xFF, 0b001, 0, 42]);
+        assert_eq!(properties.luminance(), 15);
+        assert_eq!(properties.light_opacity(), 15);
+        assert!(properties.has_full_cube_collision());
+        assert_eq!(properties.default_state(), 42);
+    }
+
+    #[test]
+    fn test_block_flags() {
+        // Given
+        let air = BlockStateProperties::new("minecraft:air", &[], 0);
+        let water = BlockStateProperties::new("minecraft:water", &[], 0);
+        let glass = BlockStateProperties::new("minecraft:glass", &[], 0);
+        let soul_sand = BlockStateProperties::new("minecraft:soul_sand", &[], 0);
+        let torch = BlockStateProperties::new("minecraft:torch", &[], 0);
+
+        // Then
+        assert!(air.is_air() && !air.has_full_cube_collision());
+        assert!(water.is_liquid() && !water.has_full_cube_collision());
+        assert_eq!(water.light_opacity(), 1);
+        assert!(glass.has_full_cube_collision());
+        assert!(!soul_sand.has_full_cube_collision());
+        assert!(!torch.has_full_cube_collision());
+        assert_eq!(torch.luminance(), 14);
+    }
+}
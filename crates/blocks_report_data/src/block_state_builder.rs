@@ -1,4 +1,6 @@
+use crate::block_properties::{BlockPropertiesTable, BlockStateProperties};
 use crate::internal_mapping::{InternalBlockMapping, InternalId, InternalMapping};
+use crate::legacy_mapping::{LegacyBlockId, LegacyBlockNames};
 use std::collections::HashMap;
 use thiserror::Error;
 
@@ -16,11 +18,63 @@ pub enum BlockStateLookupError {
 
 pub struct BlockStateLookup<'a> {
     mapping: &'a InternalMapping,
+    properties: &'a BlockPropertiesTable,
+    legacy_block_names: &'a LegacyBlockNames,
 }
 
 impl<'a> BlockStateLookup<'a> {
-    pub fn new(mapping: &'a InternalMapping) -> Self {
-        Self { mapping }
+    pub fn new(
+        mapping: &'a InternalMapping,
+        properties: &'a BlockPropertiesTable,
+        legacy_block_names: &'a LegacyBlockNames,
+    ) -> Self {
+        Self {
+            mapping,
+            properties,
+            legacy_block_names,
+        }
+    }
+
+    /// Returns the flattened block state string of a pre-1.13 block state.
+    /// Unknown metadata values fall back to the first state of the block.
+    pub fn legacy_state_string(&self, legacy_id: LegacyBlockId) -> Option<&'a str> {
+        let names = self.legacy_block_names.inner();
+        let name = |legacy_id: LegacyBlockId| {
+            names
+                .get(legacy_id as usize)
+                .map(String::as_str)
+                .filter(|name| !name.is_empty())
+        };
+        name(legacy_id).or_else(|| name(legacy_id & !0x0F))
+    }
+
+    /// Returns the luminance, light opacity, collision and default state of a block state.
+    pub fn properties(&self, internal_id: InternalId) -> BlockStateProperties {
+        self.properties
+            .inner()
+            .get(internal_id as usize)
+            .copied()
+            .unwrap_or(BlockStateProperties::unknown(internal_id))
+    }
+
+    /// Returns the block state string of an internal ID, such as "minecraft:chest[facing=north,type=single]".
+    pub fn state_string(&self, internal_id: InternalId) -> Option<String> {
+        self.mapping.mapping.inner().iter().find_map(|block| {
+            let state = block
+                .states
+                .inner()
+                .iter()
+                .find(|state| state.internal_id == internal_id)?;
+            let properties = state.properties.inner();
+            if properties.is_empty() {
+                return Some(block.name.clone());
+            }
+            let properties: Vec<String> = properties
+                .iter()
+                .map(|property| format!("{}={}", property.name, property.value))
+                .collect();
+            Some(format!("{}[{}]", block.name, properties.join(",")))
+        })
     }
 
     /// Parses a block state string like "minecraft:chest[facing=north,type=single]"
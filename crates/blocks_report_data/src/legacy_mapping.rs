@@ -0,0 +1,755 @@
+use crate::internal_mapping::InternalProperties;
+use minecraft_protocol::prelude::LengthPaddedVec;
+use protocol_version::protocol_version::ProtocolVersion;
+
+/// Block state of versions prior to the flattening of 1.13.
+/// The block ID is shifted left by 4 bits and ORed with the metadata of the block.
+pub type LegacyBlockId = u16;
+
+/// Flattened block state string of every legacy block state, indexed by legacy block ID.
+/// Legacy block states without any flattened equivalent are empty strings.
+pub type LegacyBlockNames = LengthPaddedVec<String>;
+
+/// Number of legacy block states, 256 block IDs with 16 metadata values each.
+pub const LEGACY_BLOCK_COUNT: usize = 256 * 16;
+
+const AIR: (u16, u8) = (0, 0);
+const STONE: (u16, u8) = (1, 0);
+
+const COLORS: [&str; 16] = [
+    "white",
+    "orange",
+    "magenta",
+    "light_blue",
+    "yellow",
+    "lime",
+    "pink",
+    "gray",
+    "light_gray",
+    "cyan",
+    "purple",
+    "blue",
+    "brown",
+    "green",
+    "red",
+    "black",
+];
+
+const WOODS: [&str; 6] = ["oak", "spruce", "birch", "jungle", "acacia", "dark_oak"];
+
+/// Maps a flattened block state to the closest block state known by a legacy client.
+/// Blocks that did not exist yet are replaced with a block that looks alike.
+pub fn legacy_block_id(
+    name: &str,
+    properties: &[InternalProperties],
+    protocol_version: ProtocolVersion,
+) -> LegacyBlockId {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    let state = BlockState { name, properties };
+
+    let (mut id, mut meta) = state.resolve();
+    while protocol_version < introduced_in(id, meta) {
+        (id, meta) = older_alternative(id, meta);
+    }
+
+    (id << 4) | u16::from(meta & 0x0F)
+}
+
+/// Legacy block state a flattened block state was created from during the flattening of 1.13.
+/// Returns None for blocks added after the flattening, which only have a look-alike.
+pub fn flattened_from(name: &str, properties: &[InternalProperties]) -> Option<LegacyBlockId> {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    let state = BlockState { name, properties };
+
+    state
+        .resolve_colored()
+        .or_else(|| state.resolve_wooden())
+        .or_else(|| state.resolve_named())
+        .map(|(id, meta)| (id << 4) | u16::from(meta & 0x0F))
+}
+
+struct BlockState<'a> {
+    name: &'a str,
+    properties: &'a [InternalProperties],
+}
+
+impl BlockState<'_> {
+    fn property(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name)
+            .map(|property| property.value.as_str())
+    }
+
+    fn is(&self, name: &str, value: &str) -> bool {
+        self.property(name) == Some(value)
+    }
+
+    fn number(&self, name: &str) -> u8 {
+        self.property(name)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Prefix of the block name, if it is one of the 16 dye colors.
+    fn color(&self, suffix: &str) -> Option<u8> {
+        let prefix = self.name.strip_suffix(suffix)?;
+        COLORS
+            .iter()
+            .position(|color| *color == prefix)
+            .map(|index| index as u8)
+    }
+
+    /// Prefix of the block name, if it is one of the 6 legacy wood types.
+    fn wood(&self, suffix: &str) -> Option<u8> {
+        let prefix = self.name.strip_suffix(suffix)?;
+        WOODS
+            .iter()
+            .position(|wood| *wood == prefix)
+            .map(|index| index as u8)
+    }
+
+    fn top_half(&self) -> u8 {
+        if self.is("half", "top") || self.is("half", "upper") {
+            8
+        } else {
+            0
+        }
+    }
+
+    /// Logs, pillars, hay and bone blocks.
+    fn axis(&self) -> u8 {
+        match self.property("axis") {
+            Some("x") => 4,
+            Some("z") => 8,
+            _ => 0,
+        }
+    }
+
+    /// Ladders, wall signs, chests, furnaces and wall banners.
+    fn horizontal_facing(&self) -> u8 {
+        match self.property("facing") {
+            Some("south") => 3,
+            Some("west") => 4,
+            Some("east") => 5,
+            _ => 2,
+        }
+    }
+
+    /// Dispensers, droppers and observers.
+    fn facing(&self) -> u8 {
+        match self.property("facing") {
+            Some("down") => 0,
+            Some("up") => 1,
+            _ => self.horizontal_facing(),
+        }
+    }
+
+    /// Pumpkins, fence gates and beds.
+    fn rotation_facing(&self) -> u8 {
+        match self.property("facing") {
+            Some("west") => 1,
+            Some("north") => 2,
+            Some("east") => 3,
+            _ => 0,
+        }
+    }
+
+    fn stairs(&self, id: u16) -> (u16, u8) {
+        let facing = match self.property("facing") {
+            Some("west") => 1,
+            Some("south") => 2,
+            Some("north") => 3,
+            _ => 0,
+        };
+        let half = if self.is("half", "top") { 4 } else { 0 };
+        (id, facing | half)
+    }
+
+    fn slab(&self, id: u16, double_id: u16, variant: u8) -> (u16, u8) {
+        match self.property("type") {
+            Some("double") => (double_id, variant),
+            Some("top") => (id, variant | 8),
+            _ => (id, variant),
+        }
+    }
+
+    fn door(&self, id: u16) -> (u16, u8) {
+        if self.is("half", "upper") {
+            let hinge = u8::from(self.is("hinge", "right"));
+            return (id, 8 | hinge);
+        }
+        let facing = match self.property("facing") {
+            Some("south") => 1,
+            Some("west") => 2,
+            Some("north") => 3,
+            _ => 0,
+        };
+        let open = if self.is("open", "true") { 4 } else { 0 };
+        (id, facing | open)
+    }
+
+    fn fence_gate(&self, id: u16) -> (u16, u8) {
+        let open = if self.is("open", "true") { 4 } else { 0 };
+        (id, self.rotation_facing() | open)
+    }
+
+    fn trapdoor(&self, id: u16) -> (u16, u8) {
+        let facing = match self.property("facing") {
+            Some("south") => 1,
+            Some("west") => 2,
+            Some("east") => 3,
+            _ => 0,
+        };
+        let open = if self.is("open", "true") { 4 } else { 0 };
+        (id, facing | open | self.top_half())
+    }
+
+    fn wall_torch(&self, id: u16) -> (u16, u8) {
+        let facing = match self.property("facing") {
+            Some("east") => 1,
+            Some("west") => 2,
+            Some("south") => 3,
+            _ => 4,
+        };
+        (id, facing)
+    }
+
+    fn resolve(&self) -> (u16, u8) {
+        self.resolve_colored()
+            .or_else(|| self.resolve_wooden())
+            .or_else(|| self.resolve_named())
+            .unwrap_or_else(|| self.resolve_fallback())
+    }
+
+    fn resolve_colored(&self) -> Option<(u16, u8)> {
+        if let Some(color) = self.color("_wool") {
+            Some((35, color))
+        } else if let Some(color) = self.color("_stained_glass") {
+            Some((95, color))
+        } else if let Some(color) = self.color("_stained_glass_pane") {
+            Some((160, color))
+        } else if let Some(color) = self.color("_terracotta") {
+            Some((159, color))
+        } else if let Some(color) = self.color("_carpet") {
+            Some((171, color))
+        } else if let Some(color) = self.color("_concrete") {
+            Some((251, color))
+        } else if let Some(color) = self.color("_concrete_powder") {
+            Some((252, color))
+        } else if let Some(color) = self.color("_glazed_terracotta") {
+            Some((235 + u16::from(color), 0))
+        } else if let Some(color) = self.color("_shulker_box") {
+            Some((219 + u16::from(color), 1))
+        } else if self.color("_bed").is_some() {
+            let head = if self.is("part", "head") { 8 } else { 0 };
+            Some((26, self.rotation_facing() | head))
+        } else if self.color("_banner").is_some() {
+            Some((176, self.number("rotation")))
+        } else if self.color("_wall_banner").is_some() {
+            Some((177, self.horizontal_facing()))
+        } else {
+            None
+        }
+    }
+
+    fn log(wood: u8, axis: u8) -> (u16, u8) {
+        if wood < 4 {
+            (17, wood | axis)
+        } else {
+            (162, (wood - 4) | axis)
+        }
+    }
+
+    fn resolve_wooden(&self) -> Option<(u16, u8)> {
+        const STAIRS: [u16; 6] = [53, 134, 135, 136, 163, 164];
+        const FENCES: [u16; 6] = [85, 188, 189, 190, 192, 191];
+        const FENCE_GATES: [u16; 6] = [107, 183, 184, 185, 187, 186];
+        const DOORS: [u16; 6] = [64, 193, 194, 195, 196, 197];
+
+        if let Some(wood) = self.wood("_planks") {
+            Some((5, wood))
+        } else if let Some(wood) = self.wood("_sapling") {
+            Some((6, wood))
+        } else if let Some(wood) = self.wood("_log") {
+            Some(Self::log(wood, self.axis()))
+        } else if let Some(wood) = self.wood("_wood") {
+            // Bark on all sides is stored as a fourth axis
+            Some(Self::log(wood, 12))
+        } else if let Some(wood) = self.wood("_leaves") {
+            Some(if wood < 4 {
+                (18, wood)
+            } else {
+                (161, wood - 4)
+            })
+        } else if let Some(wood) = self.wood("_slab") {
+            Some(self.slab(126, 125, wood))
+        } else if let Some(wood) = self.wood("_stairs") {
+            Some(self.stairs(STAIRS[usize::from(wood)]))
+        } else if let Some(wood) = self.wood("_fence") {
+            Some((FENCES[usize::from(wood)], 0))
+        } else if let Some(wood) = self.wood("_fence_gate") {
+            Some(self.fence_gate(FENCE_GATES[usize::from(wood)]))
+        } else {
+            self.wood("_door")
+                .map(|wood| self.door(DOORS[usize::from(wood)]))
+        }
+    }
+
+    fn resolve_named(&self) -> Option<(u16, u8)> {
+        let block = match self.name {
+            "air" | "cave_air" | "void_air" => AIR,
+            "stone" => STONE,
+            "granite" => (1, 1),
+            "polished_granite" => (1, 2),
+            "diorite" => (1, 3),
+            "polished_diorite" => (1, 4),
+            "andesite" => (1, 5),
+            "polished_andesite" => (1, 6),
+            "grass_block" => (2, 0),
+            "dirt" => (3, 0),
+            "coarse_dirt" => (3, 1),
+            "podzol" => (3, 2),
+            "cobblestone" => (4, 0),
+            "bedrock" => (7, 0),
+            "water" => match self.number("level") {
+                0 => (9, 0),
+                level => (8, level),
+            },
+            "lava" => match self.number("level") {
+                0 => (11, 0),
+                level => (10, level),
+            },
+            "sand" => (12, 0),
+            "red_sand" => (12, 1),
+            "gravel" => (13, 0),
+            "gold_ore" => (14, 0),
+            "iron_ore" => (15, 0),
+            "coal_ore" => (16, 0),
+            "sponge" => (19, 0),
+            "wet_sponge" => (19, 1),
+            "glass" => (20, 0),
+            "lapis_ore" => (21, 0),
+            "lapis_block" => (22, 0),
+            "dispenser" => (23, self.facing()),
+            "sandstone" => (24, 0),
+            "chiseled_sandstone" => (24, 1),
+            "cut_sandstone" | "smooth_sandstone" => (24, 2),
+            "note_block" => (25, 0),
+            "powered_rail" => (27, 0),
+            "detector_rail" => (28, 0),
+            "sticky_piston" => (29, self.facing()),
+            "cobweb" => (30, 0),
+            "grass" | "short_grass" => (31, 1),
+            "fern" => (31, 2),
+            "dead_bush" => (32, 0),
+            "piston" => (33, self.facing()),
+            "dandelion" => (37, 0),
+            "poppy" => (38, 0),
+            "blue_orchid" => (38, 1),
+            "allium" => (38, 2),
+            "azure_bluet" => (38, 3),
+            "red_tulip" => (38, 4),
+            "orange_tulip" => (38, 5),
+            "white_tulip" => (38, 6),
+            "pink_tulip" => (38, 7),
+            "oxeye_daisy" => (38, 8),
+            "brown_mushroom" => (39, 0),
+            "red_mushroom" => (40, 0),
+            "gold_block" => (41, 0),
+            "iron_block" => (42, 0),
+            "smooth_stone" => (43, 8),
+            "stone_slab" | "smooth_stone_slab" => self.slab(44, 43, 0),
+            "sandstone_slab" => self.slab(44, 43, 1),
+            "cobblestone_slab" => self.slab(44, 43, 3),
+            "brick_slab" => self.slab(44, 43, 4),
+            "stone_brick_slab" => self.slab(44, 43, 5),
+            "nether_brick_slab" => self.slab(44, 43, 6),
+            "quartz_slab" => self.slab(44, 43, 7),
+            "red_sandstone_slab" => self.slab(182, 181, 0),
+            "purpur_slab" => self.slab(205, 204, 0),
+            "bricks" => (45, 0),
+            "tnt" => (46, 0),
+            "bookshelf" => (47, 0),
+            "mossy_cobblestone" => (48, 0),
+            "obsidian" => (49, 0),
+            "torch" => (50, 5),
+            "wall_torch" => self.wall_torch(50),
+            "fire" => (51, 0),
+            "spawner" => (52, 0),
+            "chest" => (54, self.horizontal_facing()),
+            "redstone_wire" => (55, self.number("power")),
+            "diamond_ore" => (56, 0),
+            "diamond_block" => (57, 0),
+            "crafting_table" => (58, 0),
+            "wheat" => (59, self.number("age")),
+            "farmland" => (60, self.number("moisture")),
+            "furnace" if self.is("lit", "true") => (62, self.horizontal_facing()),
+            "furnace" => (61, self.horizontal_facing()),
+            "ladder" => (65, self.horizontal_facing()),
+            "rail" => (66, 0),
+            "cobblestone_stairs" => self.stairs(67),
+            "oak_sign" => (63, self.number("rotation")),
+            "oak_wall_sign" => (68, self.horizontal_facing()),
+            "lever" => (69, 0),
+            "stone_pressure_plate" => (70, 0),
+            "iron_door" => self.door(71),
+            "oak_pressure_plate" => (72, 0),
+            "redstone_ore" => (73, 0),
+            "redstone_torch" => (76, 5),
+            "redstone_wall_torch" => self.wall_torch(76),
+            "stone_button" => (77, 0),
+            "snow" => (78, self.number("layers").saturating_sub(1)),
+            "ice" => (79, 0),
+            "snow_block" => (80, 0),
+            "cactus" => (81, 0),
+            "clay" => (82, 0),
+            "sugar_cane" => (83, 0),
+            "jukebox" => (84, 0),
+            "pumpkin" => (86, 0),
+            "carved_pumpkin" => (86, self.rotation_facing()),
+            "netherrack" => (87, 0),
+            "soul_sand" => (88, 0),
+            "glowstone" => (89, 0),
+            "nether_portal" => (90, 0),
+            "jack_o_lantern" => (91, self.rotation_facing()),
+            "cake" => (92, self.number("bites")),
+            "repeater" => (93, 0),
+            "oak_trapdoor" => self.trapdoor(96),
+            "infested_stone" => (97, 0),
+            "infested_cobblestone" => (97, 1),
+            "infested_stone_bricks" => (97, 2),
+            "stone_bricks" => (98, 0),
+            "mossy_stone_bricks" => (98, 1),
+            "cracked_stone_bricks" => (98, 2),
+            "chiseled_stone_bricks" => (98, 3),
+            "brown_mushroom_block" => (99, 14),
+            "red_mushroom_block" => (100, 14),
+            "mushroom_stem" => (100, 15),
+            "iron_bars" => (101, 0),
+            "glass_pane" => (102, 0),
+            "melon" => (103, 0),
+            "pumpkin_stem" => (104, self.number("age")),
+            "melon_stem" => (105, self.number("age")),
+            "vine" => (106, 0),
+            "brick_stairs" => self.stairs(108),
+            "stone_brick_stairs" => self.stairs(109),
+            "mycelium" => (110, 0),
+            "lily_pad" => (111, 0),
+            "nether_bricks" => (112, 0),
+            "nether_brick_fence" => (113, 0),
+            "nether_brick_stairs" => self.stairs(114),
+            "nether_wart" => (115, self.number("age")),
+            "enchanting_table" => (116, 0),
+            "brewing_stand" => (117, 0),
+            "cauldron" => (118, 0),
+            "end_portal" => (119, 0),
+            "end_portal_frame" => (120, 0),
+            "end_stone" => (121, 0),
+            "dragon_egg" => (122, 0),
+            "redstone_lamp" if self.is("lit", "true") => (124, 0),
+            "redstone_lamp" => (123, 0),
+            "cocoa" => (127, 0),
+            "sandstone_stairs" => self.stairs(128),
+            "emerald_ore" => (129, 0),
+            "ender_chest" => (130, self.horizontal_facing()),
+            "tripwire_hook" => (131, 0),
+            "tripwire" => (132, 0),
+            "emerald_block" => (133, 0),
+            "command_block" => (137, 0),
+            "beacon" => (138, 0),
+            "cobblestone_wall" => (139, 0),
+            "mossy_cobblestone_wall" => (139, 1),
+            "flower_pot" => (140, 0),
+            "carrots" => (141, self.number("age")),
+            "potatoes" => (142, self.number("age")),
+            "oak_button" => (143, 0),
+            "skeleton_skull"
+            | "wither_skeleton_skull"
+            | "zombie_head"
+            | "player_head"
+            | "creeper_head" => (144, 1),
+            "anvil" => (145, 0),
+            "trapped_chest" => (146, self.horizontal_facing()),
+            "light_weighted_pressure_plate" => (147, 0),
+            "heavy_weighted_pressure_plate" => (148, 0),
+            "comparator" => (149, 0),
+            "daylight_detector" if self.is("inverted", "true") => (178, 0),
+            "daylight_detector" => (151, 0),
+            "redstone_block" => (152, 0),
+            "nether_quartz_ore" => (153, 0),
+            "hopper" => (154, 0),
+            "quartz_block" | "smooth_quartz" => (155, 0),
+            "chiseled_quartz_block" => (155, 1),
+            "quartz_pillar" => match self.property("axis") {
+                Some("x") => (155, 3),
+                Some("z") => (155, 4),
+                _ => (155, 2),
+            },
+            "quartz_stairs" => self.stairs(156),
+            "activator_rail" => (157, 0),
+            "dropper" => (158, self.facing()),
+            "slime_block" => (165, 0),
+            "barrier" => (166, 0),
+            "iron_trapdoor" => self.trapdoor(167),
+            "prismarine" => (168, 0),
+            "prismarine_bricks" => (168, 1),
+            "dark_prismarine" => (168, 2),
+            "sea_lantern" => (169, 0),
+            "hay_block" => (170, self.axis()),
+            "terracotta" => (172, 0),
+            "coal_block" => (173, 0),
+            "packed_ice" => (174, 0),
+            "sunflower" => (175, self.top_half()),
+            "lilac" => (175, 1 | self.top_half()),
+            "tall_grass" => (175, 2 | self.top_half()),
+            "large_fern" => (175, 3 | self.top_half()),
+            "rose_bush" => (175, 4 | self.top_half()),
+            "peony" => (175, 5 | self.top_half()),
+            "red_sandstone" => (179, 0),
+            "chiseled_red_sandstone" => (179, 1),
+            "cut_red_sandstone" | "smooth_red_sandstone" => (179, 2),
+            "red_sandstone_stairs" => self.stairs(180),
+            "end_rod" => (198, self.facing()),
+            "chorus_plant" => (199, 0),
+            "chorus_flower" => (200, 0),
+            "purpur_block" => (201, 0),
+            "purpur_pillar" => (202, self.axis()),
+            "purpur_stairs" => self.stairs(203),
+            "end_stone_bricks" => (206, 0),
+            "beetroots" => (207, self.number("age")),
+            "grass_path" | "dirt_path" => (208, 0),
+            "end_gateway" => (209, 0),
+            "repeating_command_block" => (210, 0),
+            "chain_command_block" => (211, 0),
+            "frosted_ice" => (212, 0),
+            "magma_block" => (213, 0),
+            "nether_wart_block" => (214, 0),
+            "red_nether_bricks" => (215, 0),
+            "bone_block" => (216, self.axis()),
+            "structure_void" => (217, 0),
+            "observer" => (218, self.facing()),
+            "shulker_box" => (229, 1),
+            "structure_block" => (255, 0),
+            _ => return None,
+        };
+        Some(block)
+    }
+
+    /// Blocks added after the flattening, replaced by the legacy block of the same shape.
+    fn resolve_fallback(&self) -> (u16, u8) {
+        let name = self.name;
+        if name.starts_with("stripped_") {
+            let log = BlockState {
+                name: name.trim_start_matches("stripped_"),
+                properties: self.properties,
+            };
+            return log.resolve();
+        }
+        if name.starts_with("potted_") {
+            return (140, 0);
+        }
+
+        match name.rsplit('_').next() {
+            Some("stairs") => self.stairs(67),
+            Some("slab") => self.slab(44, 43, 0),
+            Some("wall") => (139, 0),
+            Some("fence") => (85, 0),
+            Some("gate") => self.fence_gate(107),
+            Some("trapdoor") => self.trapdoor(96),
+            Some("door") => self.door(64),
+            Some("planks") => (5, 0),
+            Some("log" | "stem" | "wood" | "hyphae") => (17, self.axis()),
+            Some("leaves") => (18, 0),
+            Some("sapling" | "propagule") => (6, 0),
+            Some("glass") => (20, 0),
+            Some("pane") => (102, 0),
+            Some("carpet") => (171, 0),
+            Some("button") => (77, 0),
+            Some("plate") => (70, 0),
+            Some("sign") if name.ends_with("_wall_sign") => (68, self.horizontal_facing()),
+            Some("sign") => (63, self.number("rotation")),
+            Some("torch") => (50, 5),
+            Some("lantern") => (89, 0),
+            Some("bricks") => (98, 0),
+            _ => STONE,
+        }
+    }
+}
+
+/// Version in which a legacy block was added.
+fn introduced_in(id: u16, meta: u8) -> ProtocolVersion {
+    match (id, meta) {
+        (1, 1..) | (3, 1) | (19, 1) | (165..=197, _) => ProtocolVersion::V1_8,
+        (198..=212 | 255, _) => ProtocolVersion::V1_9,
+        (213..=217, _) => ProtocolVersion::V1_10,
+        (218..=234, _) => ProtocolVersion::V1_11,
+        (235..=252, _) => ProtocolVersion::V1_12,
+        _ => ProtocolVersion::V1_7_2,
+    }
+}
+
+/// Block that looks alike, from an older version.
+fn older_alternative(id: u16, meta: u8) -> (u16, u8) {
+    let top_half = meta & 8;
+    match id {
+        1 | 3 | 19 => (id, 0),
+        165 => (35, 5),
+        166 | 176 | 177 | 217 => AIR,
+        167 => (96, meta),
+        168 => (98, 0),
+        169 => (89, 0),
+        178 => (151, meta),
+        179 => (24, meta),
+        180 => (128, meta),
+        181 => (43, 1),
+        182 => (44, 1 | top_half),
+        183..=187 => (107, meta),
+        188..=192 => (85, 0),
+        193..=197 => (64, meta),
+        198 => (50, 5),
+        199 | 200 => (85, 0),
+        201 | 202 => (155, 0),
+        203 => (156, meta),
+        204 => (43, 7),
+        205 => (44, 7 | top_half),
+        206 => (121, 0),
+        207 => (59, meta),
+        208 => (60, 0),
+        209 => (119, 0),
+        210 | 211 => (137, 0),
+        212 => (79, 0),
+        213 => (87, 0),
+        214 => (35, 14),
+        215 => (112, 0),
+        216 => (155, 0),
+        218 => (23, meta),
+        219..=234 => (35, (id - 219) as u8),
+        235..=250 => (159, (id - 235) as u8),
+        251 | 252 => (35, meta),
+        _ => STONE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn properties(properties: &[(&str, &str)]) -> Vec<InternalProperties> {
+        properties
+            .iter()
+            .map(|(name, value)| InternalProperties {
+                name: name.to_string(),
+                value: value.to_string(),
+            })
+            .collect()
+    }
+
+    fn legacy(name: &str, props: &[(&str, &str)], protocol_version: ProtocolVersion) -> (u16, u8) {
+        let id = legacy_block_id(name, &properties(props), protocol_version);
+        (id >> 4, (id & 0x0F) as u8)
+    }
+
+    #[test]
+    fn test_simple_blocks() {
+        assert_eq!(legacy("minecraft:air", &[], ProtocolVersion::V1_8), (0, 0));
+        assert_eq!(
+            legacy("minecraft:stone", &[], ProtocolVersion::V1_8),
+            (1, 0)
+        );
+        assert_eq!(
+            legacy("minecraft:andesite", &[], ProtocolVersion::V1_8),
+            (1, 5)
+        );
+        assert_eq!(
+            legacy("minecraft:red_wool", &[], ProtocolVersion::V1_8),
+            (35, 14)
+        );
+        assert_eq!(
+            legacy("minecraft:birch_planks", &[], ProtocolVersion::V1_8),
+            (5, 2)
+        );
+    }
+
+    #[test]
+    fn test_block_properties() {
+        assert_eq!(
+            legacy(
+                "minecraft:oak_stairs",
+                &[("facing", "north"), ("half", "top"), ("shape", "straight")],
+                ProtocolVersion::V1_12_2
+            ),
+            (53, 7)
+        );
+        assert_eq!(
+            legacy(
+                "minecraft:dark_oak_log",
+                &[("axis", "z")],
+                ProtocolVersion::V1_12_2
+            ),
+            (162, 9)
+        );
+        assert_eq!(
+            legacy(
+                "minecraft:stone_brick_slab",
+                &[("type", "top"), ("waterlogged", "false")],
+                ProtocolVersion::V1_12_2
+            ),
+            (44, 13)
+        );
+    }
+
+    #[test]
+    fn test_blocks_missing_from_older_versions() {
+        assert_eq!(
+            legacy("minecraft:lime_concrete", &[], ProtocolVersion::V1_12_2),
+            (251, 5)
+        );
+        assert_eq!(
+            legacy("minecraft:lime_concrete", &[], ProtocolVersion::V1_8),
+            (35, 5)
+        );
+        assert_eq!(
+            legacy("minecraft:granite", &[], ProtocolVersion::V1_7_2),
+            (1, 0)
+        );
+        assert_eq!(
+            legacy("minecraft:purpur_block", &[], ProtocolVersion::V1_8),
+            (155, 0)
+        );
+    }
+
+    #[test]
+    fn test_flattened_blocks_use_fallbacks() {
+        assert_eq!(
+            legacy(
+                "minecraft:mangrove_stairs",
+                &[("facing", "east"), ("half", "bottom")],
+                ProtocolVersion::V1_8
+            ),
+            (67, 0)
+        );
+        assert_eq!(
+            legacy(
+                "minecraft:stripped_spruce_log",
+                &[("axis", "x")],
+                ProtocolVersion::V1_8
+            ),
+            (17, 5)
+        );
+        assert_eq!(
+            legacy("minecraft:deepslate", &[], ProtocolVersion::V1_8),
+            (1, 0)
+        );
+    }
+
+    #[test]
+    fn test_only_flattened_blocks_have_an_origin() {
+        assert_eq!(
+            flattened_from("minecraft:birch_log", &properties(&[("axis", "x")])),
+            Some((17 << 4) | 6)
+        );
+        assert_eq!(flattened_from("minecraft:stripped_birch_log", &[]), None);
+        assert_eq!(flattened_from("minecraft:deepslate", &[]), None);
+    }
+}
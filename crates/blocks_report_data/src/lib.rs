@@ -1,3 +1,7 @@
+pub mod block_entity_types;
+pub mod block_properties;
 pub mod block_state_builder;
 pub mod internal_mapping;
+pub mod legacy_mapping;
+pub mod light_properties;
 pub mod report_mapping;
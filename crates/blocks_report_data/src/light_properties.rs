@@ -0,0 +1,323 @@
+use crate::internal_mapping::InternalProperties;
+
+/// Highest light level, also the opacity of blocks that do not let any light through.
+pub const MAX_LIGHT_LEVEL: u8 = 15;
+
+/// Light emitted by a block state, from 0 to 15.
+pub fn luminance(name: &str, properties: &[InternalProperties]) -> u8 {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    let property = |key: &str| {
+        properties
+            .iter()
+            .find(|property| property.name == key)
+            .map(|property| property.value.as_str())
+    };
+    let is_lit = property("lit") != Some("false");
+    let number = |key: &str| -> u8 { property(key).and_then(|v| v.parse().ok()).unwrap_or(0) };
+
+    match name {
+        "beacon"
+        | "conduit"
+        | "end_gateway"
+        | "end_portal"
+        | "fire"
+        | "glowstone"
+        | "jack_o_lantern"
+        | "lava"
+        | "lava_cauldron"
+        | "sea_lantern"
+        | "shroomlight"
+        | "lantern"
+        | "ochre_froglight"
+        | "verdant_froglight"
+        | "pearlescent_froglight" => 15,
+        "campfire" | "redstone_lamp" if is_lit => 15,
+        "copper_bulb" | "waxed_copper_bulb" if is_lit => 15,
+        "exposed_copper_bulb" | "waxed_exposed_copper_bulb" if is_lit => 12,
+        "weathered_copper_bulb" | "waxed_weathered_copper_bulb" if is_lit => 8,
+        "oxidized_copper_bulb" | "waxed_oxidized_copper_bulb" if is_lit => 4,
+        "torch" | "wall_torch" | "end_rod" => 14,
+        "cave_vines" | "cave_vines_plant" if property("berries") == Some("true") => 14,
+        "furnace" | "blast_furnace" | "smoker" if is_lit => 13,
+        "nether_portal" => 11,
+        "soul_fire" | "soul_lantern" | "soul_torch" | "soul_wall_torch" | "crying_obsidian" => 10,
+        "soul_campfire" if is_lit => 10,
+        "redstone_ore" | "deepslate_redstone_ore" if property("lit") == Some("true") => 9,
+        "redstone_torch" | "redstone_wall_torch" if is_lit => 7,
+        "enchanting_table" | "ender_chest" | "glow_lichen" => 7,
+        "sculk_catalyst" => 6,
+        "amethyst_cluster" => 5,
+        "large_amethyst_bud" => 4,
+        "magma_block" => 3,
+        "medium_amethyst_bud" => 2,
+        "brewing_stand"
+        | "brown_mushroom"
+        | "dragon_egg"
+        | "end_portal_frame"
+        | "sculk_sensor"
+        | "calibrated_sculk_sensor"
+        | "small_amethyst_bud" => 1,
+        "light" => number("level").min(MAX_LIGHT_LEVEL),
+        "respawn_anchor" => match number("charges") {
+            0 => 0,
+            charges => (charges * 4 - 1).min(MAX_LIGHT_LEVEL),
+        },
+        "sea_pickle" if property("waterlogged") == Some("true") => {
+            ((number("pickles") + 1) * 3).min(MAX_LIGHT_LEVEL)
+        }
+        _ if name.ends_with("candle") && property("lit") == Some("true") => {
+            (number("candles") * 3).min(MAX_LIGHT_LEVEL)
+        }
+        _ => 0,
+    }
+}
+
+/// Light absorbed by a block state, from 0 to 15.
+/// Light still decreases by 1 for every block it travels through, even when the opacity is 0.
+pub fn light_opacity(name: &str, properties: &[InternalProperties]) -> u8 {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    let is_waterlogged = properties
+        .iter()
+        .any(|property| property.name == "waterlogged" && property.value == "true");
+
+    if is_transparent(name, properties) {
+        u8::from(is_waterlogged)
+    } else if is_translucent(name) {
+        1
+    } else {
+        MAX_LIGHT_LEVEL
+    }
+}
+
+/// Blocks letting light through without absorbing any.
+fn is_transparent(name: &str, properties: &[InternalProperties]) -> bool {
+    const NAMES: &[&str] = &[
+        "air",
+        "cave_air",
+        "void_air",
+        "glass",
+        "barrier",
+        "light",
+        "structure_void",
+        "iron_bars",
+        "chain",
+        "ladder",
+        "vine",
+        "lever",
+        "redstone_wire",
+        "tripwire",
+        "tripwire_hook",
+        "repeater",
+        "comparator",
+        "torch",
+        "wall_torch",
+        "soul_torch",
+        "soul_wall_torch",
+        "redstone_torch",
+        "redstone_wall_torch",
+        "end_rod",
+        "lantern",
+        "soul_lantern",
+        "flower_pot",
+        "scaffolding",
+        "sugar_cane",
+        "bamboo",
+        "cactus",
+        "cake",
+        "bell",
+        "lightning_rod",
+        "pointed_dripstone",
+        "brewing_stand",
+        "hopper",
+        "conduit",
+        "beacon",
+        "cobweb",
+        "nether_portal",
+        "end_portal",
+        "end_gateway",
+        "chest",
+        "trapped_chest",
+        "ender_chest",
+        "enchanting_table",
+        "daylight_detector",
+        "stonecutter",
+        "lectern",
+        "cauldron",
+        "water_cauldron",
+        "lava_cauldron",
+        "powder_snow_cauldron",
+        "composter",
+        "anvil",
+        "chipped_anvil",
+        "damaged_anvil",
+        "grindstone",
+        "campfire",
+        "soul_campfire",
+        "sea_pickle",
+        "turtle_egg",
+        "cocoa",
+        "dragon_egg",
+        "decorated_pot",
+    ];
+    const SUFFIXES: &[&str] = &[
+        "_glass",
+        "_glass_pane",
+        "glass_pane",
+        "_rail",
+        "rail",
+        "_button",
+        "_pressure_plate",
+        "_sign",
+        "_banner",
+        "_door",
+        "_trapdoor",
+        "_fence",
+        "_fence_gate",
+        "_wall",
+        "_carpet",
+        "carpet",
+        "_candle",
+        "candle",
+        "_head",
+        "_skull",
+        "_sapling",
+        "_flower",
+        "_tulip",
+        "_mushroom",
+        "_coral",
+        "_coral_fan",
+        "_slab",
+        "_stairs",
+        "_bed",
+        "_amethyst_bud",
+        "_vines",
+        "_roots",
+    ];
+    const PLANTS: &[&str] = &[
+        "short_grass",
+        "grass",
+        "tall_grass",
+        "fern",
+        "large_fern",
+        "dead_bush",
+        "dandelion",
+        "poppy",
+        "blue_orchid",
+        "allium",
+        "azure_bluet",
+        "oxeye_daisy",
+        "cornflower",
+        "lily_of_the_valley",
+        "wither_rose",
+        "sunflower",
+        "lilac",
+        "rose_bush",
+        "peony",
+        "wheat",
+        "carrots",
+        "potatoes",
+        "beetroots",
+        "nether_wart",
+        "sweet_berry_bush",
+        "lily_pad",
+        "fire",
+        "soul_fire",
+    ];
+
+    if name.ends_with("_slab") {
+        // Double slabs are full blocks
+        return !properties
+            .iter()
+            .any(|property| property.name == "type" && property.value == "double");
+    }
+    if name == "tinted_glass" {
+        // Tinted glass is see-through but blocks light entirely
+        return false;
+    }
+    if name == "snow" {
+        return !properties
+            .iter()
+            .any(|property| property.name == "layers" && property.value == "8");
+    }
+
+    NAMES.contains(&name)
+        || PLANTS.contains(&name)
+        || name.starts_with("potted_")
+        || name.ends_with("amethyst_cluster")
+        || SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+}
+
+/// Blocks letting light through while absorbing some of it.
+fn is_translucent(name: &str) -> bool {
+    matches!(
+        name,
+        "water"
+            | "bubble_column"
+            | "ice"
+            | "frosted_ice"
+            | "slime_block"
+            | "honey_block"
+            | "kelp"
+            | "kelp_plant"
+            | "seagrass"
+            | "tall_seagrass"
+    ) || name.ends_with("_leaves")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn properties(properties: &[(&str, &str)]) -> Vec<InternalProperties> {
+        properties
+            .iter()
+            .map(|(name, value)| InternalProperties {
+                name: (*name).to_string(),
+                value: (*value).to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_luminance() {
+        assert_eq!(luminance("minecraft:glowstone", &[]), 15);
+        assert_eq!(luminance("minecraft:torch", &[]), 14);
+        assert_eq!(luminance("minecraft:stone", &[]), 0);
+        assert_eq!(
+            luminance("minecraft:redstone_lamp", &properties(&[("lit", "false")])),
+            0
+        );
+        assert_eq!(
+            luminance(
+                "minecraft:red_candle",
+                &properties(&[("candles", "3"), ("lit", "true")])
+            ),
+            9
+        );
+        assert_eq!(
+            luminance("minecraft:light", &properties(&[("level", "4")])),
+            4
+        );
+    }
+
+    #[test]
+    fn test_light_opacity() {
+        assert_eq!(light_opacity("minecraft:air", &[]), 0);
+        assert_eq!(light_opacity("minecraft:glass", &[]), 0);
+        assert_eq!(light_opacity("minecraft:tinted_glass", &[]), 15);
+        assert_eq!(light_opacity("minecraft:oak_leaves", &[]), 1);
+        assert_eq!(light_opacity("minecraft:water", &[]), 1);
+        assert_eq!(light_opacity("minecraft:stone", &[]), 15);
+        assert_eq!(
+            light_opacity("minecraft:oak_slab", &properties(&[("type", "double")])),
+            15
+        );
+        assert_eq!(
+            light_opacity(
+                "minecraft:oak_stairs",
+                &properties(&[("waterlogged", "true")])
+            ),
+            1
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use minecraft_protocol::prelude::*;
 
-/// This packet is currently only used to communicate with the Velocity proxy.
+/// This packet is used to communicate with the Velocity proxy and with Forge clients.
 #[derive(PacketOut)]
 pub struct CustomQueryPacket {
     pub message_id: VarInt,
@@ -16,4 +16,65 @@ impl CustomQueryPacket {
             data: Vec::new(),
         }
     }
+
+    /// Forge login wrapper containing an empty mod list, sent to FML2 and FML3 clients.
+    /// FML3 clients also expect an empty list of data pack registries.
+    pub fn forge_empty_mod_list(
+        message_id: i32,
+        with_data_pack_registries: bool,
+    ) -> Result<Self, BinaryWriterError> {
+        // Packet ID of the mod list, followed by the mod, channel and registry counts
+        let list_counts = if with_data_pack_registries { 4 } else { 3 };
+        let mut mod_list = BinaryWriter::new();
+        mod_list.write(&VarInt::new(1))?;
+        for _ in 0..list_counts {
+            mod_list.write(&VarInt::new(0))?;
+        }
+
+        let mut data = BinaryWriter::new();
+        data.write(&VarIntPrefixedString::string("fml:handshake"))?;
+        data.write(&VarIntPrefixed::new(mod_list.into_inner()))?;
+
+        Ok(Self {
+            message_id: VarInt::new(message_id),
+            channel: Identifier::new("fml", "loginwrapper"),
+            data: data.into_inner(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::login::custom_query_packet::CustomQueryPacket;
+    use minecraft_protocol::prelude::{
+        BinaryReader, BinaryWriter, EncodePacket, ProtocolVersion, VarInt, VarIntPrefixedString,
+    };
+
+    #[test]
+    fn test_forge_empty_mod_list_encode() {
+        let packet = CustomQueryPacket::forge_empty_mod_list(42, true).unwrap();
+        let mut writer = BinaryWriter::new();
+
+        packet
+            .encode(&mut writer, ProtocolVersion::V1_18_2)
+            .unwrap();
+        let bytes = writer.into_inner();
+        let mut reader = BinaryReader::new(&bytes);
+
+        assert_eq!(42, reader.read::<VarInt>().unwrap().inner());
+        assert_eq!(
+            "fml:loginwrapper",
+            reader.read::<VarIntPrefixedString>().unwrap().into_inner()
+        );
+        assert_eq!(
+            "fml:handshake",
+            reader.read::<VarIntPrefixedString>().unwrap().into_inner()
+        );
+        assert_eq!(5, reader.read::<VarInt>().unwrap().inner());
+        assert_eq!(1, reader.read::<VarInt>().unwrap().inner());
+        for _ in 0..4 {
+            assert_eq!(0, reader.read::<VarInt>().unwrap().inner());
+        }
+        assert_eq!(0, reader.remaining());
+    }
 }
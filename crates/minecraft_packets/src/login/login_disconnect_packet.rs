@@ -18,6 +18,10 @@ impl Default for LoginDisconnectPacket {
 impl LoginDisconnectPacket {
     pub fn text(text: impl Into<String>) -> LoginDisconnectPacket {
         let component = Component::new(text);
+        Self::component(&component)
+    }
+
+    pub fn component(component: &Component) -> LoginDisconnectPacket {
         Self {
             reason: component.to_json(),
         }
@@ -0,0 +1,17 @@
+use minecraft_protocol::prelude::*;
+
+/// Marks the end of a chunk batch, since 1.20.2 included.
+/// The client answers with the Chunk Batch Received packet.
+#[derive(PacketOut)]
+pub struct ChunkBatchFinishedPacket {
+    /// Number of chunks sent in the batch
+    batch_size: VarInt,
+}
+
+impl ChunkBatchFinishedPacket {
+    pub fn new(batch_size: i32) -> Self {
+        Self {
+            batch_size: VarInt::new(batch_size),
+        }
+    }
+}
@@ -0,0 +1,8 @@
+use minecraft_protocol::prelude::*;
+
+/// Sent by the client once a chunk batch is processed, since 1.20.2 included.
+#[derive(PacketIn)]
+pub struct ChunkBatchReceivedPacket {
+    /// Number of chunks per tick the client is able to process
+    pub chunks_per_tick: f32,
+}
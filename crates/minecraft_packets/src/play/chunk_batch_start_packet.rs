@@ -0,0 +1,6 @@
+use minecraft_protocol::prelude::*;
+
+/// Marks the start of a chunk batch, since 1.20.2 included.
+/// The client measures the time until the end of the batch to compute its desired rate.
+#[derive(PacketOut)]
+pub struct ChunkBatchStartPacket {}
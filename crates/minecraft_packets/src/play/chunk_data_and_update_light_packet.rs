@@ -3,17 +3,35 @@ use crate::play::data::chunk_data::ChunkData;
 use crate::play::data::light_data::LightData;
 use minecraft_protocol::prelude::*;
 
-/// This packet is only mandatory for versions above 1.20.3,
-/// thus the packet is only implemented to work on versions after 1.20.3.
-/// The GameEventPacket must be sent before sending this one.
+/// Named Chunk Data prior to 1.18, the light being sent using the Update Light packet from 1.14.
+/// Prior to 1.9, a chunk without any section is unloaded by the client.
+/// The GameEventPacket must be sent before sending this one since 1.20.3.
 #[derive(PacketOut)]
 pub struct ChunkDataAndUpdateLightPacket {
     chunk_x: i32,
     chunk_z: i32,
 
+    /// Always true, the whole chunk column is sent.
+    /// Up until 1.16.5 included
+    #[pvn(..755)]
+    v1_7_full_chunk: bool,
+    /// Since 1.16 included, up until 1.16.1 included
+    #[pvn(735..751)]
+    v1_16_ignore_old_data: bool,
+    /// Bits set to 1 for every 16×16×16 chunk section whose data is included in Data.
+    /// Up until 1.8.9 included
+    #[pvn(..107)]
+    v1_7_primary_bit_mask: u16,
+    /// Bits set to 1 for every section with block IDs above 255, always empty.
+    /// Up until 1.7.10 included
+    #[pvn(..47)]
+    v1_7_add_bit_mask: u16,
+    /// Since 1.9 included, up until 1.16.5 included
+    #[pvn(107..755)]
+    v1_9_primary_bit_mask: VarInt,
     /// BitSet with bits (world height in blocks / 16) set to 1 for every 16×16×16 chunk section whose data is included in Data. The least significant bit represents the chunk section at the bottom of the chunk column (from the lowest y to 15 blocks above).
-    /// Up until 1.17.1 included
-    #[pvn(..757)]
+    /// Since 1.17 included, up until 1.17.1 included
+    #[pvn(755..757)]
     primary_bit_mask: LengthPaddedVec<i64>,
 
     chunk_data: ChunkData,
@@ -23,36 +41,64 @@ pub struct ChunkDataAndUpdateLightPacket {
     #[pvn(757..763)]
     trust_edges: bool,
 
-    // TODO: Implement Update Light packet for versions prior to 1.18
+    /// Prior to 1.18, the light is sent using the UpdateLightPacket
     #[pvn(757..)]
     v1_18_light_data: LightData,
 }
 
 impl ChunkDataAndUpdateLightPacket {
     pub fn void(context: VoidChunkContext) -> Self {
-        Self {
-            chunk_x: context.chunk_x,
-            chunk_z: context.chunk_z,
-            primary_bit_mask: LengthPaddedVec::default(),
-            chunk_data: ChunkData::void(context),
-            trust_edges: true,
-            v1_18_light_data: LightData::default(),
-        }
+        let chunk_x = context.chunk_x;
+        let chunk_z = context.chunk_z;
+        Self::new(
+            chunk_x,
+            chunk_z,
+            ChunkData::void(context),
+            LightData::default(),
+        )
     }
 
     pub fn from_structure(
         chunk_context: VoidChunkContext,
         schematic_context: &WorldContext,
     ) -> Self {
+        let chunk_x = chunk_context.chunk_x;
+        let chunk_z = chunk_context.chunk_z;
+        let chunk_data = ChunkData::from_schematic(chunk_context, schematic_context);
+        let light_data = chunk_data.light_data();
+        Self::new(chunk_x, chunk_z, chunk_data, light_data)
+    }
+
+    fn new(chunk_x: i32, chunk_z: i32, chunk_data: ChunkData, light_data: LightData) -> Self {
+        let primary_bit_mask = chunk_data.primary_bit_mask();
         Self {
-            chunk_x: chunk_context.chunk_x,
-            chunk_z: chunk_context.chunk_z,
-            primary_bit_mask: LengthPaddedVec::default(),
-            chunk_data: ChunkData::from_schematic(chunk_context, schematic_context),
+            chunk_x,
+            chunk_z,
+            v1_7_full_chunk: true,
+            v1_16_ignore_old_data: true,
+            v1_7_primary_bit_mask: primary_bit_mask as u16,
+            v1_7_add_bit_mask: 0,
+            v1_9_primary_bit_mask: VarInt::new(primary_bit_mask as i32),
+            primary_bit_mask: LengthPaddedVec::new(if primary_bit_mask == 0 {
+                Vec::new()
+            } else {
+                vec![primary_bit_mask]
+            }),
+            chunk_data,
             trust_edges: true,
-            v1_18_light_data: LightData::new_with_level(15),
+            v1_18_light_data: light_data,
         }
     }
+
+    /// Whether the chunk contains at least one block.
+    pub fn has_blocks(&self) -> bool {
+        self.chunk_data.primary_bit_mask() != 0
+    }
+
+    /// The light of the chunk, also sent using the UpdateLightPacket prior to 1.18.
+    pub const fn light_data(&self) -> &LightData {
+        &self.v1_18_light_data
+    }
 }
 
 #[cfg(test)]
@@ -187,4 +233,81 @@ mod tests {
             assert_eq!(expected_bytes, bytes, "Mismatch for version {version}");
         }
     }
+
+    fn encode(protocol_version: ProtocolVersion) -> Vec<u8> {
+        let mut writer = BinaryWriter::default();
+        create_packet(protocol_version)
+            .encode(&mut writer, protocol_version)
+            .unwrap();
+        writer.into_inner()
+    }
+
+    #[test]
+    fn legacy_void_chunk_only_contains_biomes() {
+        // 1.9: full chunk, empty bit mask, 256 biome bytes and no block entities
+        let bytes = encode(ProtocolVersion::V1_9);
+        assert_eq!(&bytes[8..12], &[1, 0, 0x80, 0x02]);
+        assert_eq!(bytes.len(), 12 + 256);
+
+        // 1.12.2: the block entities are appended
+        let bytes = encode(ProtocolVersion::V1_12_2);
+        assert_eq!(bytes.len(), 12 + 256 + 1);
+
+        // 1.13: biomes are sent as integers
+        let bytes = encode(ProtocolVersion::V1_13);
+        assert_eq!(&bytes[8..12], &[1, 0, 0x80, 0x08]);
+        assert_eq!(&bytes[12..16], &[0, 0, 0, 1]);
+        assert_eq!(bytes.len(), 12 + 1024 + 1);
+    }
+
+    #[test]
+    fn legacy_void_chunk_contains_height_maps() {
+        // 1.15: height maps then 1024 biomes as integers, outside of the data
+        let bytes = encode(ProtocolVersion::V1_15);
+        assert_eq!(&bytes[8..13], &[1, 0, 10, 0, 0]);
+        let data_start = bytes.len() - 2 - 4096;
+        assert_eq!(&bytes[data_start..data_start + 4], &[0, 0, 0, 1]);
+        assert_eq!(&bytes[bytes.len() - 2..], &[0, 0]);
+
+        // 1.16: ignore old data flag
+        let bytes = encode(ProtocolVersion::V1_16);
+        assert_eq!(&bytes[8..11], &[1, 1, 0]);
+
+        // 1.16.2: biomes are prefixed with their length
+        let bytes = encode(ProtocolVersion::V1_16_2);
+        assert_eq!(&bytes[8..10], &[1, 0]);
+        assert_eq!(
+            &bytes[bytes.len() - 1024 - 4..bytes.len() - 1024 - 2],
+            &[0x80, 0x08]
+        );
+
+        // 1.17: the bit mask is a bit set, the full chunk flag is removed
+        let bytes = encode(ProtocolVersion::V1_17);
+        assert_eq!(&bytes[8..11], &[0, 10, 0]);
+    }
+
+    #[test]
+    fn pre_1_9_void_chunk_groups_arrays_by_type() {
+        // 1.8: full chunk, empty u16 bit mask, then 256 biome bytes
+        let bytes = encode(ProtocolVersion::V1_8);
+        assert_eq!(&bytes[8..13], &[1, 0, 0, 0x80, 0x02]);
+        assert_eq!(bytes.len(), 13 + 256);
+    }
+
+    #[test]
+    fn v1_7_chunk_data_is_compressed() {
+        // Given
+        let bytes = encode(ProtocolVersion::V1_7_2);
+
+        // When
+        let compressed_length = i32::from_be_bytes(bytes[13..17].try_into().unwrap());
+        let mut decoder = flate2::read::ZlibDecoder::new(&bytes[17..]);
+        let mut data = Vec::new();
+        std::io::Read::read_to_end(&mut decoder, &mut data).unwrap();
+
+        // Then
+        assert_eq!(&bytes[8..13], &[1, 0, 0, 0, 0]);
+        assert_eq!(compressed_length as usize, bytes.len() - 17);
+        assert_eq!(data, vec![1; 256]);
+    }
 }
@@ -0,0 +1,101 @@
+use minecraft_protocol::prelude::*;
+
+/// Sent by the client when joining, and every time its settings change.
+/// Since 1.20.2, it is also sent during the configuration state.
+#[derive(Default, PacketIn)]
+#[allow(dead_code)]
+pub struct ClientInformationPacket {
+    /// e.g. `en_us`
+    locale: String,
+    /// Client-side render distance, in chunks.
+    view_distance: i8,
+    #[pvn(..107)]
+    v1_7_chat_flags: i8,
+    #[pvn(107..)]
+    chat_mode: VarInt,
+    chat_colors: bool,
+    #[pvn(..47)]
+    v1_7_difficulty: u8,
+    #[pvn(..47)]
+    v1_7_show_cape: bool,
+    /// Bit mask of the skin parts displayed by the client.
+    #[pvn(47..)]
+    displayed_skin_parts: u8,
+    #[pvn(107..)]
+    main_hand: VarInt,
+    #[pvn(755..)]
+    enable_text_filtering: bool,
+    #[pvn(757..)]
+    allow_server_listings: bool,
+    #[pvn(768..)]
+    particle_status: VarInt,
+}
+
+impl ClientInformationPacket {
+    pub fn new(locale: impl ToString, view_distance: i8, displayed_skin_parts: u8) -> Self {
+        Self {
+            locale: locale.to_string(),
+            view_distance,
+            displayed_skin_parts,
+            ..Self::default()
+        }
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    pub fn view_distance(&self) -> i32 {
+        i32::from(self.view_distance)
+    }
+
+    /// Always 0 prior to 1.8, skin parts did not exist yet.
+    pub const fn displayed_skin_parts(&self) -> u8 {
+        self.displayed_skin_parts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::play::client_information_packet::ClientInformationPacket;
+    use minecraft_protocol::prelude::{BinaryReader, DecodePacket, ProtocolVersion};
+
+    #[test]
+    fn test_client_information_packet_decode_v1_8() {
+        let snapshot = [5, 102, 114, 95, 102, 114, 6, 0, 1, 127];
+        let mut reader = BinaryReader::new(&snapshot);
+
+        let packet = ClientInformationPacket::decode(&mut reader, ProtocolVersion::V1_8).unwrap();
+
+        assert_eq!("fr_fr", packet.locale());
+        assert_eq!(6, packet.view_distance());
+        assert_eq!(127, packet.displayed_skin_parts());
+        assert_eq!(0, reader.remaining());
+    }
+
+    #[test]
+    fn test_client_information_packet_decode_v1_7() {
+        let snapshot = [5, 101, 110, 95, 117, 115, 12, 0, 1, 2, 1];
+        let mut reader = BinaryReader::new(&snapshot);
+
+        let packet = ClientInformationPacket::decode(&mut reader, ProtocolVersion::V1_7_2).unwrap();
+
+        assert_eq!("en_us", packet.locale());
+        assert_eq!(12, packet.view_distance());
+        assert_eq!(0, reader.remaining());
+    }
+
+    #[test]
+    fn test_client_information_packet_decode_v1_21_4() {
+        let snapshot = [5, 101, 110, 95, 117, 115, 12, 0, 1, 125, 1, 0, 1, 0];
+        let mut reader = BinaryReader::new(&snapshot);
+
+        let packet =
+            ClientInformationPacket::decode(&mut reader, ProtocolVersion::V1_21_4).unwrap();
+
+        assert_eq!("en_us", packet.locale());
+        assert_eq!(12, packet.view_distance());
+        assert_eq!(125, packet.displayed_skin_parts());
+        assert_eq!(0, reader.remaining());
+    }
+}
@@ -0,0 +1,673 @@
+use minecraft_protocol::prelude::*;
+use serde_json::{Map, Value};
+
+/// A block entity sent along with the chunk it belongs to.
+pub struct BlockEntity {
+    x: i32,
+    y: i32,
+    z: i32,
+    /// ID of the block entity type in the registry, since 1.18 included
+    type_id: i32,
+    /// Type of the block entity, such as `minecraft:sign`
+    id: String,
+    /// Tags of the block entity, as stored in the schematic
+    data: Vec<Nbt>,
+}
+
+impl BlockEntity {
+    const SIGN_LINE_COUNT: usize = 4;
+
+    pub fn new(position: Coordinates, type_id: i32, id: String, data: Vec<Nbt>) -> Self {
+        Self {
+            x: position.x(),
+            y: position.y(),
+            z: position.z(),
+            type_id,
+            id,
+            data,
+        }
+    }
+
+    /// Converts the tags to the layout known by the client.
+    /// The content of containers is left out, it is never displayed.
+    fn network_data(&self, protocol_version: ProtocolVersion) -> Vec<Nbt> {
+        let data = self
+            .data
+            .iter()
+            .filter(|tag| tag.get_name().as_deref() != Some("Items"))
+            .cloned();
+        match self.id.as_str() {
+            "minecraft:sign" | "minecraft:hanging_sign" => {
+                convert_sign(&self.data, protocol_version)
+            }
+            "minecraft:skull" => convert_skull(data.collect(), protocol_version),
+            _ => data.collect(),
+        }
+    }
+
+    /// Prior to 1.11, block entity types have a capitalized name without namespace.
+    fn legacy_id(&self) -> &str {
+        match self.id.as_str() {
+            "minecraft:sign" => "Sign",
+            "minecraft:banner" => "Banner",
+            "minecraft:skull" => "Skull",
+            "minecraft:chest" | "minecraft:trapped_chest" => "Chest",
+            "minecraft:ender_chest" => "EnderChest",
+            "minecraft:furnace" => "Furnace",
+            "minecraft:flower_pot" => "FlowerPot",
+            "minecraft:mob_spawner" => "MobSpawner",
+            "minecraft:enchanting_table" => "EnchantTable",
+            "minecraft:beacon" => "Beacon",
+            id => id,
+        }
+    }
+}
+
+impl EncodePacket for BlockEntity {
+    fn encode(
+        &self,
+        writer: &mut BinaryWriter,
+        protocol_version: ProtocolVersion,
+    ) -> Result<(), BinaryWriterError> {
+        let data = self.network_data(protocol_version);
+        if protocol_version.is_after_inclusive(ProtocolVersion::V1_18) {
+            // Position relative to the chunk, X in the upper 4 bits
+            let packed_xz = (((self.x & 15) << 4) | (self.z & 15)) as u8;
+            packed_xz.encode(writer, protocol_version)?;
+            (self.y as i16).encode(writer, protocol_version)?;
+            VarInt::new(self.type_id).encode(writer, protocol_version)?;
+            return Nbt::nameless_compound(data).encode(writer, protocol_version);
+        }
+
+        // Up until 1.17.1 included, the position and type are tags of the compound
+        let id = if protocol_version.is_after_inclusive(ProtocolVersion::V1_11) {
+            self.id.as_str()
+        } else {
+            self.legacy_id()
+        };
+        let mut compound = vec![
+            Nbt::int("x", self.x),
+            Nbt::int("y", self.y),
+            Nbt::int("z", self.z),
+            Nbt::string("id", id),
+        ];
+        compound.extend(data);
+        Nbt::nameless_compound(compound).encode(writer, protocol_version)
+    }
+}
+
+/// One side of a sign, read from the layout of any version.
+struct SignSide {
+    messages: Vec<Value>,
+    color: String,
+    has_glowing_text: bool,
+}
+
+impl SignSide {
+    fn empty() -> Self {
+        Self {
+            messages: vec![Value::String(String::new()); BlockEntity::SIGN_LINE_COUNT],
+            color: "black".to_string(),
+            has_glowing_text: false,
+        }
+    }
+
+    /// Since 1.20, each side is a compound holding a list of messages.
+    fn from_compound(side: &Nbt) -> Self {
+        let mut messages: Vec<Value> = side
+            .find_tag("messages")
+            .and_then(|t| t.get_nbt_vec())
+            .unwrap_or_default()
+            .iter()
+            .map(text_component)
+            .collect();
+        messages.resize(BlockEntity::SIGN_LINE_COUNT, Value::String(String::new()));
+        Self {
+            messages,
+            color: side
+                .find_tag("color")
+                .and_then(|t| t.get_string())
+                .unwrap_or_else(|| "black".to_string()),
+            has_glowing_text: side.find_tag("has_glowing_text").and_then(|t| t.get_byte())
+                == Some(1),
+        }
+    }
+
+    /// Up until 1.19.4 included, there is a single side with a tag per line.
+    fn from_legacy_tags(data: &[Nbt]) -> Self {
+        let find = |name: &str| {
+            data.iter()
+                .find(|tag| tag.get_name().as_deref() == Some(name))
+        };
+        Self {
+            messages: (1..=BlockEntity::SIGN_LINE_COUNT)
+                .map(|line| {
+                    find(&format!("Text{line}"))
+                        .map(text_component)
+                        .unwrap_or(Value::String(String::new()))
+                })
+                .collect(),
+            color: find("Color")
+                .and_then(Nbt::get_string)
+                .unwrap_or_else(|| "black".to_string()),
+            has_glowing_text: find("GlowingText").and_then(Nbt::get_byte) == Some(1),
+        }
+    }
+
+    /// Since 1.21.5 included, messages are NBT text components, JSON strings before.
+    fn message_tags(&self, protocol_version: ProtocolVersion) -> Vec<Nbt> {
+        self.messages
+            .iter()
+            .map(|message| {
+                if protocol_version.is_after_inclusive(ProtocolVersion::V1_21_5) {
+                    component_to_nbt("", message)
+                } else {
+                    Nbt::String {
+                        name: None,
+                        value: message.to_string(),
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn to_compound(&self, name: &str, protocol_version: ProtocolVersion) -> Nbt {
+        let messages = self.message_tags(protocol_version);
+        let tag_type = if protocol_version.is_after_inclusive(ProtocolVersion::V1_21_5) {
+            10
+        } else {
+            8
+        };
+        Nbt::compound(
+            name,
+            vec![
+                Nbt::List {
+                    name: Some("messages".to_string()),
+                    value: messages,
+                    tag_type,
+                },
+                Nbt::string("color", &self.color),
+                Nbt::byte("has_glowing_text", i8::from(self.has_glowing_text)),
+            ],
+        )
+    }
+}
+
+/// Since 1.20 included, signs have a front and a back text.
+fn convert_sign(data: &[Nbt], protocol_version: ProtocolVersion) -> Vec<Nbt> {
+    let find = |name: &str| {
+        data.iter()
+            .find(|tag| tag.get_name().as_deref() == Some(name))
+    };
+    let (front, back) = match find("front_text") {
+        Some(front) => (
+            SignSide::from_compound(front),
+            find("back_text").map_or_else(SignSide::empty, SignSide::from_compound),
+        ),
+        None => (SignSide::from_legacy_tags(data), SignSide::empty()),
+    };
+
+    if protocol_version.is_after_inclusive(ProtocolVersion::V1_20) {
+        let is_waxed = find("is_waxed").and_then(Nbt::get_byte).unwrap_or_default();
+        return vec![
+            front.to_compound("front_text", protocol_version),
+            back.to_compound("back_text", protocol_version),
+            Nbt::byte("is_waxed", is_waxed),
+        ];
+    }
+
+    let mut tags: Vec<Nbt> = front
+        .messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| Nbt::string(format!("Text{}", index + 1), message))
+        .collect();
+    tags.push(Nbt::string("Color", &front.color));
+    tags.push(Nbt::byte("GlowingText", i8::from(front.has_glowing_text)));
+    tags
+}
+
+/// Owner of a player head, read from the layout of any version.
+#[derive(Default)]
+struct SkullProfile {
+    name: Option<String>,
+    id: Option<Vec<i32>>,
+    /// Value and signature of the textures property
+    textures: Option<(String, Option<String>)>,
+}
+
+impl SkullProfile {
+    /// Since 1.20.5, the owner is a `profile` whose properties are a list.
+    fn from_profile(profile: &Nbt) -> Self {
+        if let Some(name) = profile.get_string() {
+            return Self {
+                name: Some(name),
+                ..Self::default()
+            };
+        }
+        let textures = profile
+            .find_tag("properties")
+            .and_then(|t| t.get_nbt_vec())
+            .unwrap_or_default()
+            .into_iter()
+            .find(|property| {
+                property
+                    .find_tag("name")
+                    .and_then(|t| t.get_string())
+                    .as_deref()
+                    == Some("textures")
+            })
+            .and_then(|property| {
+                let value = property.find_tag("value")?.get_string()?;
+                Some((
+                    value,
+                    property.find_tag("signature").and_then(|t| t.get_string()),
+                ))
+            });
+        Self {
+            name: profile.find_tag("name").and_then(|t| t.get_string()),
+            id: profile.find_tag("id").and_then(|t| t.get_int_array()),
+            textures,
+        }
+    }
+
+    /// Up until 1.20.4 included, the owner is a `SkullOwner` whose properties are a compound.
+    fn from_skull_owner(owner: &Nbt) -> Self {
+        if let Some(name) = owner.get_string() {
+            return Self {
+                name: Some(name),
+                ..Self::default()
+            };
+        }
+        let textures = owner
+            .find_tag("Properties")
+            .and_then(|t| t.find_tag("textures"))
+            .and_then(|t| t.get_nbt_vec())
+            .and_then(|textures| {
+                let texture = textures.first()?;
+                let value = texture.find_tag("Value")?.get_string()?;
+                Some((
+                    value,
+                    texture.find_tag("Signature").and_then(|t| t.get_string()),
+                ))
+            });
+        Self {
+            name: owner.find_tag("Name").and_then(|t| t.get_string()),
+            id: owner.find_tag("Id").and_then(|t| t.get_int_array()),
+            textures,
+        }
+    }
+
+    fn to_profile(&self) -> Nbt {
+        let mut tags = Vec::new();
+        if let Some(name) = &self.name {
+            tags.push(Nbt::string("name", name));
+        }
+        if let Some(id) = &self.id {
+            tags.push(Nbt::IntArray {
+                name: Some("id".to_string()),
+                value: id.clone(),
+            });
+        }
+        if let Some((value, signature)) = &self.textures {
+            let mut property = vec![Nbt::string("name", "textures"), Nbt::string("value", value)];
+            if let Some(signature) = signature {
+                property.push(Nbt::string("signature", signature));
+            }
+            tags.push(Nbt::compound_list(
+                "properties",
+                vec![Nbt::nameless_compound(property)],
+            ));
+        }
+        Nbt::compound("profile", tags)
+    }
+
+    /// Prior to 1.16, the UUID is a string.
+    fn to_skull_owner(&self, name: &str, protocol_version: ProtocolVersion) -> Nbt {
+        let mut tags = Vec::new();
+        if let Some(owner_name) = &self.name {
+            tags.push(Nbt::string("Name", owner_name));
+        }
+        if let Some(id) = &self.id {
+            if protocol_version.is_after_inclusive(ProtocolVersion::V1_16) {
+                tags.push(Nbt::IntArray {
+                    name: Some("Id".to_string()),
+                    value: id.clone(),
+                });
+            } else {
+                tags.push(Nbt::string("Id", uuid_string(id)));
+            }
+        }
+        if let Some((value, signature)) = &self.textures {
+            let mut texture = vec![Nbt::string("Value", value)];
+            if let Some(signature) = signature {
+                texture.push(Nbt::string("Signature", signature));
+            }
+            tags.push(Nbt::compound(
+                "Properties",
+                vec![Nbt::compound_list(
+                    "textures",
+                    vec![Nbt::nameless_compound(texture)],
+                )],
+            ));
+        }
+        Nbt::compound(name, tags)
+    }
+}
+
+/// Since 1.20.5 included, the owner of a player head is a `profile`, a `SkullOwner` before.
+/// Prior to 1.13, it is named `Owner`.
+fn convert_skull(data: Vec<Nbt>, protocol_version: ProtocolVersion) -> Vec<Nbt> {
+    let is_owner_tag = |tag: &Nbt| {
+        matches!(
+            tag.get_name().as_deref(),
+            Some("profile" | "SkullOwner" | "Owner")
+        )
+    };
+    let profile = data.iter().find(|tag| is_owner_tag(tag)).map(|tag| {
+        if tag.get_name().as_deref() == Some("profile") {
+            SkullProfile::from_profile(tag)
+        } else {
+            SkullProfile::from_skull_owner(tag)
+        }
+    });
+    let mut tags: Vec<Nbt> = data.into_iter().filter(|tag| !is_owner_tag(tag)).collect();
+    if let Some(profile) = profile {
+        tags.push(
+            if protocol_version.is_after_inclusive(ProtocolVersion::V1_20_5) {
+                profile.to_profile()
+            } else if protocol_version.is_after_inclusive(ProtocolVersion::V1_13) {
+                profile.to_skull_owner("SkullOwner", protocol_version)
+            } else {
+                profile.to_skull_owner("Owner", protocol_version)
+            },
+        );
+    }
+    tags
+}
+
+fn uuid_string(id: &[i32]) -> String {
+    let hex: String = id
+        .iter()
+        .map(|part| format!("{:08x}", *part as u32))
+        .collect();
+    if hex.len() != 32 {
+        return hex;
+    }
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// Reads a text component saved either as NBT, as a JSON string or as plain text.
+fn text_component(tag: &Nbt) -> Value {
+    match tag {
+        Nbt::String { value, .. } => match serde_json::from_str::<Value>(value) {
+            Ok(json @ (Value::String(_) | Value::Object(_) | Value::Array(_))) => json,
+            _ => Value::String(value.clone()),
+        },
+        tag => nbt_to_json(tag),
+    }
+}
+
+/// Bytes become booleans, as for the style of text components.
+fn nbt_to_json(tag: &Nbt) -> Value {
+    match tag {
+        Nbt::Byte { value, .. } => Value::Bool(*value != 0),
+        Nbt::Short { value, .. } => Value::from(*value),
+        Nbt::Int { value, .. } => Value::from(*value),
+        Nbt::Long { value, .. } => Value::from(*value),
+        Nbt::Float { value, .. } => Value::from(*value),
+        Nbt::Double { value, .. } => Value::from(*value),
+        Nbt::String { value, .. } => Value::String(value.clone()),
+        Nbt::ByteArray { value, .. } => value.iter().copied().map(Value::from).collect(),
+        Nbt::IntArray { value, .. } => value.iter().copied().map(Value::from).collect(),
+        Nbt::LongArray { value, .. } => value.iter().copied().map(Value::from).collect(),
+        Nbt::List { value, .. } => value.iter().map(nbt_to_json).collect(),
+        Nbt::Compound { value, .. } => Value::Object(
+            value
+                .iter()
+                .filter_map(|tag| Some((tag.get_name()?, nbt_to_json(tag))))
+                .collect::<Map<_, _>>(),
+        ),
+        Nbt::End => Value::Null,
+    }
+}
+
+/// Every component becomes a compound, so that lists of components have a single tag type.
+fn component_to_nbt(name: &str, component: &Value) -> Nbt {
+    match component {
+        Value::Object(object) => Nbt::compound(
+            name,
+            object
+                .iter()
+                .filter_map(|(key, value)| match (key.as_str(), value) {
+                    ("extra" | "with", Value::Array(components)) => Some(Nbt::compound_list(
+                        key,
+                        components.iter().map(|c| component_to_nbt("", c)).collect(),
+                    )),
+                    _ => json_to_nbt(key, value),
+                })
+                .collect(),
+        ),
+        // Components of an array are children of an empty parent
+        Value::Array(components) => Nbt::compound(
+            name,
+            vec![
+                Nbt::string("text", ""),
+                Nbt::compound_list(
+                    "extra",
+                    components.iter().map(|c| component_to_nbt("", c)).collect(),
+                ),
+            ],
+        ),
+        Value::String(text) => Nbt::compound(name, vec![Nbt::string("text", text)]),
+        other => Nbt::compound(name, vec![Nbt::string("text", other.to_string())]),
+    }
+}
+
+fn json_to_nbt(name: &str, value: &Value) -> Option<Nbt> {
+    let name = Some(name.to_string());
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(value) => Nbt::Byte {
+            name,
+            value: i8::from(*value),
+        },
+        Value::Number(number) => match number.as_i64().and_then(|n| i32::try_from(n).ok()) {
+            Some(value) => Nbt::Int { name, value },
+            None => Nbt::Double {
+                name,
+                value: number.as_f64().unwrap_or_default(),
+            },
+        },
+        Value::String(value) => Nbt::String {
+            name,
+            value: value.clone(),
+        },
+        Value::Object(object) => Nbt::Compound {
+            name,
+            value: object
+                .iter()
+                .filter_map(|(key, value)| json_to_nbt(key, value))
+                .collect(),
+        },
+        Value::Array(components) => Nbt::List {
+            name,
+            value: components.iter().map(|c| component_to_nbt("", c)).collect(),
+            tag_type: 10,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(data: &'a [Nbt], name: &str) -> &'a Nbt {
+        data.iter()
+            .find(|tag| tag.get_name().as_deref() == Some(name))
+            .unwrap()
+    }
+
+    fn legacy_sign() -> Vec<Nbt> {
+        vec![
+            Nbt::string("Text1", r#"{"text":"Hello"}"#),
+            Nbt::string("Text2", "Plain"),
+            Nbt::string("Color", "red"),
+        ]
+    }
+
+    fn player_head() -> Vec<Nbt> {
+        vec![Nbt::compound(
+            "profile",
+            vec![
+                Nbt::string("name", "Notch"),
+                Nbt::IntArray {
+                    name: Some("id".to_string()),
+                    value: vec![1, 2, 3, 4],
+                },
+                Nbt::compound_list(
+                    "properties",
+                    vec![Nbt::nameless_compound(vec![
+                        Nbt::string("name", "textures"),
+                        Nbt::string("value", "skin"),
+                    ])],
+                ),
+            ],
+        )]
+    }
+
+    #[test]
+    fn test_block_entity_layout_since_1_18() {
+        // Given
+        let block_entity = BlockEntity::new(
+            Coordinates::new(17, -3, 34),
+            7,
+            "minecraft:banner".to_string(),
+            Vec::new(),
+        );
+        let mut writer = BinaryWriter::default();
+
+        // When
+        block_entity
+            .encode(&mut writer, ProtocolVersion::V1_20_2)
+            .unwrap();
+
+        // Then
+        let bytes = writer.into_inner();
+        assert_eq!(bytes[..4], [0x12, 0xFF, 0xFD, 7]);
+        // Empty nameless compound
+        assert_eq!(bytes[4..], [10, 0]);
+    }
+
+    #[test]
+    fn test_sign_text_before_1_20() {
+        // When
+        let data = convert_sign(&legacy_sign(), ProtocolVersion::V1_19_4);
+
+        // Then
+        assert_eq!(
+            find(&data, "Text1").get_string().unwrap(),
+            r#"{"text":"Hello"}"#
+        );
+        assert_eq!(find(&data, "Text2").get_string().unwrap(), r#""Plain""#);
+        assert_eq!(find(&data, "Text4").get_string().unwrap(), r#""""#);
+        assert_eq!(find(&data, "Color").get_string().unwrap(), "red");
+    }
+
+    #[test]
+    fn test_sign_text_since_1_20() {
+        // When
+        let data = convert_sign(&legacy_sign(), ProtocolVersion::V1_20);
+
+        // Then
+        let front_text = find(&data, "front_text");
+        let messages = front_text
+            .find_tag("messages")
+            .unwrap()
+            .get_nbt_vec()
+            .unwrap();
+        assert_eq!(messages[0].get_string().unwrap(), r#"{"text":"Hello"}"#);
+        assert_eq!(
+            front_text.find_tag("color").unwrap().get_string().unwrap(),
+            "red"
+        );
+        assert!(
+            data.iter()
+                .any(|tag| tag.get_name().as_deref() == Some("back_text"))
+        );
+    }
+
+    #[test]
+    fn test_sign_text_components_since_1_21_5() {
+        // When
+        let data = convert_sign(&legacy_sign(), ProtocolVersion::V1_21_5);
+
+        // Then
+        let messages = find(&data, "front_text")
+            .find_tag("messages")
+            .unwrap()
+            .get_nbt_vec()
+            .unwrap();
+        let text = |index: usize| messages[index].find_tag("text").unwrap().get_string();
+        assert_eq!(text(0).unwrap(), "Hello");
+        assert_eq!(text(1).unwrap(), "Plain");
+    }
+
+    #[test]
+    fn test_player_head_profile_before_1_20_5() {
+        // When
+        let data = convert_skull(player_head(), ProtocolVersion::V1_20_3);
+
+        // Then
+        let owner = find(&data, "SkullOwner");
+        assert_eq!(
+            owner.find_tag("Name").unwrap().get_string().unwrap(),
+            "Notch"
+        );
+        let textures = owner
+            .find_tag("Properties")
+            .and_then(|t| t.find_tag("textures"))
+            .and_then(|t| t.get_nbt_vec())
+            .unwrap();
+        assert_eq!(
+            textures[0].find_tag("Value").unwrap().get_string().unwrap(),
+            "skin"
+        );
+    }
+
+    #[test]
+    fn test_player_head_uuid_is_a_string_before_1_16() {
+        // When
+        let data = convert_skull(player_head(), ProtocolVersion::V1_15_2);
+
+        // Then
+        let owner = find(&data, "SkullOwner");
+        assert_eq!(
+            owner.find_tag("Id").unwrap().get_string().unwrap(),
+            "00000001-0000-0002-0000-000300000004"
+        );
+    }
+
+    #[test]
+    fn test_player_head_profile_since_1_20_5() {
+        // Given
+        let skull_owner = vec![
+            SkullProfile::from_profile(&player_head()[0])
+                .to_skull_owner("SkullOwner", ProtocolVersion::V1_20_3),
+        ];
+
+        // When
+        let data = convert_skull(skull_owner, ProtocolVersion::V1_21);
+
+        // Then
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0], player_head()[0]);
+    }
+}
@@ -1,6 +1,7 @@
-use blocks_report::ReportIdMapping;
+use blocks_report::{BlockEntityTypes, ReportIdMapping};
 use minecraft_protocol::prelude::{Coordinates, Dimension};
 use pico_structures::prelude::World;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct VoidChunkContext {
@@ -14,4 +15,8 @@ pub struct WorldContext {
     pub world: Arc<World>,
     pub paste_origin: Coordinates,
     pub report_id_mapping: Arc<ReportIdMapping>,
+    /// Registry of block entity types, since 1.18 included
+    pub block_entity_types: Option<Arc<BlockEntityTypes>>,
+    /// Registry IDs of the biomes of the world, since 1.18 included
+    pub biome_ids: Option<Arc<HashMap<String, i32>>>,
 }
@@ -1,68 +1,52 @@
+use crate::play::data::block_entity::BlockEntity;
 use crate::play::data::chunk_context::{VoidChunkContext, WorldContext};
 use crate::play::data::chunk_section::ChunkSection;
 use crate::play::data::encode_as_bytes::EncodeAsBytes;
+use crate::play::data::light_data::LightData;
+use blocks_report::block_entity_type_id;
 use minecraft_protocol::prelude::*;
 
 #[derive(PacketOut)]
 pub struct ChunkData {
-    #[pvn(..770)]
+    /// Since 1.14 included, up until 1.15.2 included
+    /// Entries could span across two longs
+    #[pvn(477..735)]
+    v1_14_height_maps: Nbt,
+    #[pvn(735..770)]
     height_maps: Nbt,
     #[pvn(770..)]
     v1_21_5_height_maps: LengthPaddedVec<HeightMap>,
 
     /// Biome IDs, ordered by x then z then y, in 4×4×4 blocks.
-    /// Up until 1.17.1 included
-    #[pvn(..757)]
+    /// Since 1.15 included, up until 1.16.1 included
+    #[pvn(573..751)]
+    v1_15_biomes: Vec<i32>,
+    /// Since 1.16.2 included, up until 1.17.1 included
+    #[pvn(751..757)]
     biomes: LengthPaddedVec<VarInt>,
 
     /// Size of Data in bytes!
     /// LengthPaddedVec prefixes with the number of elements!
-    data: EncodeAsBytes<Vec<ChunkSection>>,
+    data: EncodeAsBytes<ChunkColumn>,
+    /// Since 1.9.4 included
+    #[pvn(110..)]
     block_entities: LengthPaddedVec<BlockEntity>,
 }
 
 impl ChunkData {
-    pub fn void(context: VoidChunkContext) -> Self {
-        let long_array_tag = Nbt::LongArray {
-            name: Some("MOTION_BLOCKING".to_string()),
-            value: vec![0; 37],
-        };
-        let root_tag = Nbt::Compound {
-            name: None,
-            value: vec![long_array_tag],
-        };
+    const BIOME_COUNT: usize = 1024;
 
+    pub fn void(context: VoidChunkContext) -> Self {
         let section_count = context.dimension.height() / ChunkSection::SECTION_SIZE;
-
-        Self {
-            height_maps: root_tag,
-            v1_21_5_height_maps: LengthPaddedVec::new(vec![HeightMap {
-                height_map_type: VarInt::new(4), // Motionblock type
-                data: LengthPaddedVec::new(vec![0; 37]),
-            }]),
-            biomes: LengthPaddedVec::new(vec![VarInt::new(127); 1024]),
-            data: EncodeAsBytes::new(vec![
-                ChunkSection::void(context.biome_index);
-                section_count as usize
-            ]),
-            block_entities: LengthPaddedVec::default(),
-        }
+        let sections = vec![ChunkSection::void(context.biome_index); section_count as usize];
+        Self::new(sections, context.biome_index, Vec::new())
     }
 
     pub fn from_schematic(
         chunk_context: VoidChunkContext,
         schematic_context: &WorldContext,
     ) -> Self {
-        let long_array_tag = Nbt::LongArray {
-            name: Some("MOTION_BLOCKING".to_string()),
-            value: vec![0; 37],
-        };
-        let root_tag = Nbt::Compound {
-            name: None,
-            value: vec![long_array_tag],
-        };
-
-        let mut data = Vec::new();
+        let mut sections = Vec::new();
         let negative_section_count =
             chunk_context.dimension.min_y().abs() / ChunkSection::SECTION_SIZE;
         let positive_section_count =
@@ -75,20 +59,180 @@ impl ChunkData {
                 schematic_context,
                 coordinates,
                 chunk_context.biome_index,
+                chunk_context.dimension.has_sky_light(),
             );
-            data.push(section);
+            sections.push(section);
         }
 
+        let block_entities = Self::block_entities(&chunk_context, schematic_context);
+        Self::new(sections, chunk_context.biome_index, block_entities)
+    }
+
+    /// Since 1.18 included, block entities whose type is missing from the registry are left out.
+    fn block_entities(
+        chunk_context: &VoidChunkContext,
+        schematic_context: &WorldContext,
+    ) -> Vec<BlockEntity> {
+        let min_y = chunk_context.dimension.min_y();
+        let max_y = min_y + chunk_context.dimension.height();
+        schematic_context
+            .world
+            .get_block_entities(chunk_context.chunk_x, chunk_context.chunk_z)
+            .iter()
+            .filter_map(|block_entity| {
+                let position = schematic_context.paste_origin + block_entity.position();
+                if !(min_y..max_y).contains(&position.y()) {
+                    return None;
+                }
+                let type_id = match &schematic_context.block_entity_types {
+                    Some(types) => block_entity_type_id(types, block_entity.id())?,
+                    None => 0,
+                };
+                Some(BlockEntity::new(
+                    position,
+                    type_id,
+                    block_entity.id().to_string(),
+                    block_entity.data().to_vec(),
+                ))
+            })
+            .collect()
+    }
+
+    fn new(sections: Vec<ChunkSection>, biome_id: i32, block_entities: Vec<BlockEntity>) -> Self {
         Self {
-            height_maps: root_tag,
+            v1_14_height_maps: Self::motion_blocking_height_map(36),
+            height_maps: Self::motion_blocking_height_map(37),
             v1_21_5_height_maps: LengthPaddedVec::new(vec![HeightMap {
                 height_map_type: VarInt::new(4), // Motionblock type
                 data: LengthPaddedVec::new(vec![0; 37]),
             }]),
-            data: EncodeAsBytes::new(data),
-            biomes: LengthPaddedVec::default(),
-            block_entities: LengthPaddedVec::default(),
+            v1_15_biomes: vec![biome_id; Self::BIOME_COUNT],
+            biomes: LengthPaddedVec::new(vec![VarInt::new(biome_id); Self::BIOME_COUNT]),
+            data: EncodeAsBytes::new(ChunkColumn { sections, biome_id }),
+            block_entities: LengthPaddedVec::new(block_entities),
+        }
+    }
+
+    /// 256 entries of 9 bits each.
+    fn motion_blocking_height_map(long_count: usize) -> Nbt {
+        let long_array_tag = Nbt::LongArray {
+            name: Some("MOTION_BLOCKING".to_string()),
+            value: vec![0; long_count],
+        };
+        Nbt::Compound {
+            name: None,
+            value: vec![long_array_tag],
+        }
+    }
+
+    /// Bit mask with bits set to 1 for every section sent prior to 1.18,
+    /// the least significant bit being the lowest section.
+    pub fn primary_bit_mask(&self) -> i64 {
+        self.data
+            .inner()
+            .sections
+            .iter()
+            .enumerate()
+            .filter(|(_, section)| !section.is_empty())
+            .fold(0, |mask, (index, _)| mask | (1 << index))
+    }
+
+    pub fn light_data(&self) -> LightData {
+        LightData::from_sections(&self.data.inner().sections)
+    }
+}
+
+/// Every section of the chunk column.
+/// Up until 1.14.4 included, the biomes are sent after the sections.
+struct ChunkColumn {
+    sections: Vec<ChunkSection>,
+    biome_id: i32,
+}
+
+impl ChunkColumn {
+    const LEGACY_BIOME_COUNT: usize = 256;
+
+    /// Up until 1.8.9 included, the arrays of every section are grouped by type:
+    /// blocks first, then block light and sky light.
+    fn encode_legacy(
+        &self,
+        writer: &mut BinaryWriter,
+        protocol_version: ProtocolVersion,
+    ) -> Result<(), BinaryWriterError> {
+        let sections: Vec<&ChunkSection> = self
+            .sections
+            .iter()
+            .filter(|section| !section.is_empty())
+            .collect();
+
+        if protocol_version.is_after_inclusive(ProtocolVersion::V1_8) {
+            // Block ID and metadata as little endian shorts
+            for section in &sections {
+                let blocks: Vec<u8> = section
+                    .block_states
+                    .entries()
+                    .into_iter()
+                    .flat_map(|block| (block as u16).to_le_bytes())
+                    .collect();
+                writer.write_bytes(&blocks)?;
+            }
+        } else {
+            let entries: Vec<Vec<u32>> = sections
+                .iter()
+                .map(|section| section.block_states.entries())
+                .collect();
+            // Block IDs as bytes
+            for blocks in &entries {
+                let ids: Vec<u8> = blocks.iter().map(|block| (block >> 4) as u8).collect();
+                writer.write_bytes(&ids)?;
+            }
+            // Metadata as half bytes, the first block being the lowest 4 bits
+            for blocks in &entries {
+                let metadata: Vec<u8> = blocks
+                    .chunks_exact(2)
+                    .map(|pair| ((pair[0] & 0x0F) | ((pair[1] & 0x0F) << 4)) as u8)
+                    .collect();
+                writer.write_bytes(&metadata)?;
+            }
+        }
+
+        for section in &sections {
+            writer.write_bytes(&section.v1_9_block_light)?;
+        }
+        for section in &sections {
+            writer.write_bytes(&section.v1_9_sky_light)?;
+        }
+
+        writer.write_bytes(&vec![self.biome_id as u8; Self::LEGACY_BIOME_COUNT])?;
+        Ok(())
+    }
+}
+
+impl EncodePacket for ChunkColumn {
+    fn encode(
+        &self,
+        writer: &mut BinaryWriter,
+        protocol_version: ProtocolVersion,
+    ) -> Result<(), BinaryWriterError> {
+        if protocol_version.is_before_inclusive(ProtocolVersion::V1_8) {
+            return self.encode_legacy(writer, protocol_version);
+        }
+
+        let sends_every_section = protocol_version.is_after_inclusive(ProtocolVersion::V1_18);
+        for section in &self.sections {
+            // Prior to 1.18, empty sections are omitted from the primary bit mask
+            if sends_every_section || !section.is_empty() {
+                section.encode(writer, protocol_version)?;
+            }
+        }
+
+        if protocol_version.is_before_inclusive(ProtocolVersion::V1_12_2) {
+            vec![self.biome_id as u8; Self::LEGACY_BIOME_COUNT].encode(writer, protocol_version)?;
+        } else if protocol_version.is_before_inclusive(ProtocolVersion::V1_14_4) {
+            vec![self.biome_id; Self::LEGACY_BIOME_COUNT].encode(writer, protocol_version)?;
         }
+
+        Ok(())
     }
 }
 
@@ -104,7 +248,70 @@ struct HeightMap {
     data: LengthPaddedVec<i64>,
 }
 
-#[derive(PacketOut)]
-pub struct BlockEntity {
-    // TODO: Implement BlockEntity
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blocks_report::{
+        BlockPropertiesTable, BlockStateLookup, InternalMapping, ReportIdMapping,
+        get_block_entity_types,
+    };
+    use blocks_report_data::internal_mapping::{InternalBlockMapping, InternalState};
+    use pico_structures::prelude::{Schematic, World};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    /// Blocks other than air are unknown, only the block entities of the schematic are kept.
+    fn sign_world() -> World {
+        let internal_mapping = InternalMapping {
+            mapping: LengthPaddedVec::new(vec![InternalBlockMapping {
+                name: "minecraft:air".to_string(),
+                states: LengthPaddedVec::new(vec![InternalState {
+                    internal_id: 0,
+                    properties: LengthPaddedVec::default(),
+                }]),
+                default_internal_id: 0,
+            }]),
+        };
+        let block_properties = BlockPropertiesTable::default();
+        let legacy_block_names = LengthPaddedVec::default();
+        let lookup =
+            BlockStateLookup::new(&internal_mapping, &block_properties, &legacy_block_names);
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../pico_structures/test_data")
+            .join("sponge_v3.schem");
+        let schematic = Schematic::load_schematic_file(&path, 0, &lookup).unwrap();
+        World::from_schematic(&schematic, &lookup).unwrap()
+    }
+
+    #[test]
+    fn test_sign_is_sent_in_a_1_20_chunk() {
+        // Given
+        let protocol_version = ProtocolVersion::V1_20;
+        let context = WorldContext {
+            world: Arc::new(sign_world()),
+            paste_origin: Coordinates::new_uniform(0),
+            report_id_mapping: Arc::new(ReportIdMapping::default()),
+            block_entity_types: Some(Arc::new(get_block_entity_types(protocol_version).unwrap())),
+            biome_ids: None,
+        };
+        let chunk_context = VoidChunkContext {
+            chunk_x: 0,
+            chunk_z: 0,
+            biome_index: 0,
+            dimension: Dimension::Overworld,
+        };
+
+        // When
+        let chunk_data = ChunkData::from_schematic(chunk_context, &context);
+        let [sign] = chunk_data.block_entities.inner().as_slice() else {
+            panic!("expected the sign to be sent");
+        };
+        let mut writer = BinaryWriter::default();
+        sign.encode(&mut writer, protocol_version).unwrap();
+        let bytes = writer.into_inner();
+
+        // Then
+        // The sign is at x 2, y 0 and z 1, and signs have the ID 7 in the registry of 1.20
+        assert_eq!(bytes[..4], [0x21, 0, 0, 7]);
+    }
 }
@@ -5,22 +5,36 @@ use minecraft_protocol::prelude::*;
 #[derive(Clone, PacketOut)]
 pub struct ChunkSection {
     /// Number of non-air blocks present in the chunk section.
+    /// Since 1.14 included
+    #[pvn(477..)]
     pub block_count: i16,
     /// Consists of 4096 entries, representing all the blocks in the chunk section.
     pub block_states: PaletteContainer,
     /// Consists of 64 entries, representing 4×4×4 biome regions in the chunk section.
     #[pvn(757..)]
     pub biomes: PaletteContainer,
+    /// Half a byte per block, sent with the section up until 1.13.2 included.
+    /// Prior to 1.9, the light arrays of every section are sent after the blocks.
+    /// Since 1.14, the same arrays are sent in the light data instead.
+    #[pvn(..477)]
+    pub v1_9_block_light: Vec<u8>,
+    /// Only present in dimensions with sky light.
+    #[pvn(..477)]
+    pub v1_9_sky_light: Vec<u8>,
 }
 
 impl ChunkSection {
     pub const SECTION_SIZE: i32 = 16;
+    const LIGHT_ARRAY_SIZE: usize = 2048;
+    const FULL_BRIGHT: u8 = 0xFF;
 
     pub fn void(biome_id: i32) -> Self {
         Self {
             block_count: 0,
             block_states: PaletteContainer::blocks_void(),
             biomes: PaletteContainer::single_valued(biome_id),
+            v1_9_block_light: Vec::new(),
+            v1_9_sky_light: Vec::new(),
         }
     }
 
@@ -28,21 +42,72 @@ impl ChunkSection {
         context: &WorldContext,
         section_position: Coordinates,
         biome_id: i32,
+        has_sky_light: bool,
     ) -> ChunkSection {
         if let Some(palette) = context.world.get_section(&section_position) {
             let block_states =
                 PaletteContainer::from_palette(palette, context.report_id_mapping.as_ref());
-            let biomes = PaletteContainer::single_valued(biome_id);
+            let biomes = Self::biomes(context, section_position, biome_id);
+            if block_states.is_void() {
+                return Self {
+                    biomes,
+                    ..Self::void(biome_id)
+                };
+            }
+            let (v1_9_block_light, v1_9_sky_light) =
+                match context.world.get_section_light(&section_position) {
+                    Some(light) => (light.block_light().to_vec(), light.sky_light().to_vec()),
+                    None => (
+                        vec![Self::FULL_BRIGHT; Self::LIGHT_ARRAY_SIZE],
+                        vec![Self::FULL_BRIGHT; Self::LIGHT_ARRAY_SIZE],
+                    ),
+                };
 
             ChunkSection {
                 block_count: 4096,
                 block_states,
                 biomes,
+                v1_9_block_light,
+                v1_9_sky_light: if has_sky_light {
+                    v1_9_sky_light
+                } else {
+                    Vec::new()
+                },
             }
         } else {
             Self::void(biome_id)
         }
     }
+
+    /// Biomes stored in the world, the cells without biome use the biome of the world.
+    fn biomes(
+        context: &WorldContext,
+        section_position: Coordinates,
+        biome_id: i32,
+    ) -> PaletteContainer {
+        let section_biomes = context.world.get_section_biomes(&section_position);
+        match (section_biomes, &context.biome_ids) {
+            (Some(section_biomes), Some(biome_ids)) => {
+                let palette: Vec<i32> = section_biomes
+                    .palette()
+                    .iter()
+                    .map(|name| biome_ids.get(name).copied().unwrap_or(biome_id))
+                    .collect();
+                PaletteContainer::biomes(
+                    section_biomes
+                        .cells()
+                        .iter()
+                        .map(|index| palette[*index as usize]),
+                )
+            }
+            _ => PaletteContainer::single_valued(biome_id),
+        }
+    }
+
+    /// Sections without any block are not sent prior to 1.18.
+    pub const fn is_empty(&self) -> bool {
+        self.block_count == 0
+    }
 }
 
 #[cfg(test)]
@@ -106,4 +171,93 @@ mod tests {
             assert_eq!(expected_bytes, bytes, "Mismatch for version {version}");
         }
     }
+
+    #[test]
+    fn legacy_single_valued_section_uses_smallest_palette() {
+        // Given
+        let section = ChunkSection {
+            block_count: 4096,
+            block_states: PaletteContainer::single_valued(16),
+            biomes: PaletteContainer::single_valued(1),
+            v1_9_block_light: vec![0xFF; 2048],
+            v1_9_sky_light: Vec::new(),
+        };
+
+        for (version, header) in [
+            (ProtocolVersion::V1_12_2, vec![4, 1, 16, 0x80, 0x02]),
+            (
+                ProtocolVersion::V1_16,
+                vec![0x10, 0x00, 4, 1, 16, 0x80, 0x02],
+            ),
+        ] {
+            // When
+            let mut writer = BinaryWriter::default();
+            section.encode(&mut writer, version).unwrap();
+            let bytes = writer.into_inner();
+
+            // Then
+            assert_eq!(&bytes[..header.len()], header.as_slice());
+            let light_size = if version.is_before_inclusive(ProtocolVersion::V1_13_2) {
+                2048
+            } else {
+                0
+            };
+            assert_eq!(bytes.len(), header.len() + 256 * 8 + light_size);
+        }
+    }
+
+    #[test]
+    fn biomes_of_the_cells_use_an_indirect_palette() {
+        // Given
+        let cells = (0..64).map(|cell| if cell < 32 { 1 } else { 40 });
+        let section = ChunkSection {
+            biomes: PaletteContainer::biomes(cells),
+            ..ChunkSection::void(1)
+        };
+
+        // When
+        let mut writer = BinaryWriter::default();
+        section
+            .encode(&mut writer, ProtocolVersion::V1_21_6)
+            .unwrap();
+        let bytes = writer.into_inner();
+
+        // Then
+        let biomes = &bytes[4..];
+        assert_eq!(&biomes[..4], [1, 2, 1, 40]);
+        // 64 entries of 1 bit fit in a single long
+        assert_eq!(&biomes[4..], 0xFFFF_FFFF_0000_0000_u64.to_be_bytes());
+        assert!(matches!(
+            PaletteContainer::biomes([7; 64]),
+            PaletteContainer::SingleValued { value, .. } if value.inner() == 7
+        ));
+    }
+
+    #[test]
+    fn legacy_direct_section_omits_palette_after_1_13() {
+        // Given
+        let section = ChunkSection {
+            block_count: 4096,
+            block_states: PaletteContainer::Direct {
+                data: vec![1; 4096],
+            },
+            biomes: PaletteContainer::single_valued(1),
+            v1_9_block_light: Vec::new(),
+            v1_9_sky_light: Vec::new(),
+        };
+
+        for (version, header) in [
+            (ProtocolVersion::V1_12_2, vec![13, 0, 0xC0, 0x06]),
+            (ProtocolVersion::V1_13, vec![14, 0x80, 0x07]),
+            (ProtocolVersion::V1_17, vec![0x10, 0x00, 15, 0x80, 0x08]),
+        ] {
+            // When
+            let mut writer = BinaryWriter::default();
+            section.encode(&mut writer, version).unwrap();
+            let bytes = writer.into_inner();
+
+            // Then
+            assert_eq!(&bytes[..header.len()], header.as_slice(), "{version:?}");
+        }
+    }
 }
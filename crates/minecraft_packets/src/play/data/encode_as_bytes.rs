@@ -1,13 +1,22 @@
+use flate2::Compression;
+use flate2::write::ZlibEncoder;
 use minecraft_protocol::prelude::{
     BinaryWriter, BinaryWriterError, EncodePacket, ProtocolVersion, VarInt,
 };
+use std::io::Write;
 
+/// Encodes the inner value prefixed with its size in bytes.
+/// Prior to 1.8, the bytes are compressed using zlib and prefixed with their compressed size as an int.
 pub struct EncodeAsBytes<T>(T);
 
 impl<T> EncodeAsBytes<T> {
     pub fn new(data: T) -> Self {
         Self(data)
     }
+
+    pub fn inner(&self) -> &T {
+        &self.0
+    }
 }
 
 impl<T> EncodePacket for EncodeAsBytes<T>
@@ -22,6 +31,15 @@ where
         let mut payload_writer = BinaryWriter::default();
         self.0.encode(&mut payload_writer, protocol_version)?;
 
+        if protocol_version.is_before_inclusive(ProtocolVersion::V1_7_6) {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(payload_writer.as_slice())?;
+            let compressed = encoder.finish()?;
+            (compressed.len() as i32).encode(writer, protocol_version)?;
+            writer.write_bytes(&compressed)?;
+            return Ok(());
+        }
+
         let payload_size = VarInt::new(payload_writer.len() as i32);
         payload_size.encode(writer, protocol_version)?;
 
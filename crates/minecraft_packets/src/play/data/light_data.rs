@@ -1,13 +1,31 @@
+use crate::play::data::chunk_section::ChunkSection;
 use minecraft_protocol::prelude::*;
 
-#[derive(PacketOut, Default)]
+/// Sent in the Chunk Data and Update Light packet since 1.18,
+/// and in the standalone Update Light packet from 1.14 up until 1.17.1.
+#[derive(PacketOut, Default, Clone)]
 pub struct LightData {
+    #[pvn(755..)]
     sky_light_mask: BitSet,
+    #[pvn(755..)]
     block_light_mask: BitSet,
+    #[pvn(755..)]
     empty_sky_light_mask: BitSet,
+    #[pvn(755..)]
     empty_block_light_mask: BitSet,
-    sky_light_arrays: LengthPaddedVec<Light>,
-    block_light_arrays: LengthPaddedVec<Light>,
+
+    /// Up until 1.16.5 included, the masks only had 18 bits
+    #[pvn(..755)]
+    v1_14_sky_light_mask: VarInt,
+    #[pvn(..755)]
+    v1_14_block_light_mask: VarInt,
+    #[pvn(..755)]
+    v1_14_empty_sky_light_mask: VarInt,
+    #[pvn(..755)]
+    v1_14_empty_block_light_mask: VarInt,
+
+    sky_light_arrays: LightArrays,
+    block_light_arrays: LightArrays,
 }
 
 #[derive(PacketOut, Default, Clone)]
@@ -17,18 +35,37 @@ pub struct Light {
     block_light_array: LengthPaddedVec<i8>,
 }
 
+/// Up until 1.16.5 included, the number of arrays is given by the mask only.
+#[derive(Default, Clone)]
+struct LightArrays(Vec<Light>);
+
+impl EncodePacket for LightArrays {
+    fn encode(
+        &self,
+        writer: &mut BinaryWriter,
+        protocol_version: ProtocolVersion,
+    ) -> Result<(), BinaryWriterError> {
+        if protocol_version.is_after_inclusive(ProtocolVersion::V1_17) {
+            VarInt::new(self.0.len() as i32).encode(writer, protocol_version)?;
+        }
+        self.0.encode(writer, protocol_version)
+    }
+}
+
 impl LightData {
-    pub fn new_with_level(light_level: u8) -> Self {
-        const NUM_SECTIONS_IN_WORLD: u32 = 24;
+    /// Fills every section of the world with the same light level.
+    /// The light sections include one section below and one above the world.
+    pub fn new_with_level(light_level: u8, section_count: u32) -> Self {
         let light_level = light_level.clamp(0, 15);
 
         if light_level == 0 {
             return Self::default();
         }
 
-        let world_sections_mask_val = ((1u64 << NUM_SECTIONS_IN_WORLD) - 1) << 1;
+        let world_sections_mask_val = ((1u64 << section_count) - 1) << 1;
 
         let world_sections_mask = BitSet::new(vec![world_sections_mask_val as i64]);
+        let v1_14_world_sections_mask = VarInt::new(world_sections_mask_val as i32);
 
         let packed_byte = ((light_level << 4) | light_level) as i8;
 
@@ -36,8 +73,7 @@ impl LightData {
             block_light_array: LengthPaddedVec::new(vec![packed_byte; 2048]),
         };
 
-        let all_light_arrays =
-            LengthPaddedVec::new(vec![light_section_array; NUM_SECTIONS_IN_WORLD as usize]);
+        let all_light_arrays = vec![light_section_array; section_count as usize];
 
         Self {
             sky_light_mask: world_sections_mask.clone(),
@@ -46,8 +82,116 @@ impl LightData {
             empty_sky_light_mask: BitSet::default(),
             empty_block_light_mask: BitSet::default(),
 
-            sky_light_arrays: all_light_arrays.clone(),
-            block_light_arrays: all_light_arrays,
+            v1_14_sky_light_mask: v1_14_world_sections_mask.clone(),
+            v1_14_block_light_mask: v1_14_world_sections_mask,
+            v1_14_empty_sky_light_mask: VarInt::default(),
+            v1_14_empty_block_light_mask: VarInt::default(),
+
+            sky_light_arrays: LightArrays(all_light_arrays.clone()),
+            block_light_arrays: LightArrays(all_light_arrays),
+        }
+    }
+
+    /// Sends the light arrays of the sections containing blocks, the client computes the others.
+    /// The light sections include one section below and one above the world.
+    pub fn from_sections(sections: &[ChunkSection]) -> Self {
+        let mut sky_light_mask = Vec::new();
+        let mut block_light_mask = Vec::new();
+        let mut sky_light_arrays = Vec::new();
+        let mut block_light_arrays = Vec::new();
+
+        for (index, section) in sections.iter().enumerate() {
+            if section.is_empty() {
+                continue;
+            }
+            if !section.v1_9_sky_light.is_empty() {
+                Self::set_bit(&mut sky_light_mask, index + 1);
+                sky_light_arrays.push(Light::from_nibbles(&section.v1_9_sky_light));
+            }
+            if !section.v1_9_block_light.is_empty() {
+                Self::set_bit(&mut block_light_mask, index + 1);
+                block_light_arrays.push(Light::from_nibbles(&section.v1_9_block_light));
+            }
+        }
+
+        Self {
+            v1_14_sky_light_mask: Self::legacy_mask(&sky_light_mask),
+            v1_14_block_light_mask: Self::legacy_mask(&block_light_mask),
+            v1_14_empty_sky_light_mask: VarInt::default(),
+            v1_14_empty_block_light_mask: VarInt::default(),
+
+            sky_light_mask: BitSet::new(sky_light_mask),
+            block_light_mask: BitSet::new(block_light_mask),
+            empty_sky_light_mask: BitSet::default(),
+            empty_block_light_mask: BitSet::default(),
+
+            sky_light_arrays: LightArrays(sky_light_arrays),
+            block_light_arrays: LightArrays(block_light_arrays),
         }
     }
+
+    fn set_bit(mask: &mut Vec<i64>, bit: usize) {
+        let long_index = bit / 64;
+        if mask.len() <= long_index {
+            mask.resize(long_index + 1, 0);
+        }
+        mask[long_index] |= 1 << (bit % 64);
+    }
+
+    /// Up until 1.16.5 included, the masks only had 18 bits.
+    fn legacy_mask(mask: &[i64]) -> VarInt {
+        VarInt::new(mask.first().copied().unwrap_or_default() as i32)
+    }
+}
+
+impl Light {
+    fn from_nibbles(nibbles: &[u8]) -> Self {
+        Self {
+            block_light_array: LengthPaddedVec::new(
+                nibbles.iter().map(|&nibble| nibble as i8).collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::play::data::palette_container::PaletteContainer;
+
+    fn section_with_light(sky_light: u8, block_light: u8) -> ChunkSection {
+        ChunkSection {
+            block_count: 4096,
+            block_states: PaletteContainer::single_valued(1),
+            biomes: PaletteContainer::single_valued(0),
+            v1_9_block_light: vec![block_light; 2048],
+            v1_9_sky_light: vec![sky_light; 2048],
+        }
+    }
+
+    #[test]
+    fn test_only_sections_with_blocks_carry_light() {
+        // Given
+        let sections = vec![
+            ChunkSection::void(0),
+            section_with_light(0xFF, 0x00),
+            ChunkSection::void(0),
+            section_with_light(0x77, 0x11),
+        ];
+
+        // When
+        let light_data = LightData::from_sections(&sections);
+        let mut writer = BinaryWriter::default();
+        light_data
+            .encode(&mut writer, ProtocolVersion::V1_16)
+            .unwrap();
+        let bytes = writer.into_inner();
+
+        // Then
+        assert_eq!(&bytes[..4], &[0b10100, 0b10100, 0, 0]);
+        assert_eq!(&bytes[4..7], &[0x80, 0x10, 0xFF]);
+        assert_eq!(&bytes[2054..2057], &[0x80, 0x10, 0x77]);
+        assert_eq!(&bytes[4104..4107], &[0x80, 0x10, 0x00]);
+        assert_eq!(bytes.len(), 4 + 4 * (2 + 2048));
+    }
 }
@@ -1,6 +1,6 @@
 use blocks_report::{BlocksReportId, InternalId, ReportIdMapping, get_block_id};
 use minecraft_protocol::prelude::*;
-use pico_structures::prelude::{Palette, pack_direct};
+use pico_structures::prelude::{Palette, pack_compact, pack_direct, unpack_direct};
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -18,18 +18,22 @@ pub enum PaletteContainer {
         data: Vec<u64>,
     },
     /// Registry IDs are stored directly as entries in the Data Array.
-    Direct {
-        /// Should be 15 for blocks or 6 for biomes
-        bits_per_entry: u8,
-        data: Vec<u64>,
-    },
+    /// The entries are packed when encoding, as the size of the global palette depends on the version.
+    Direct { data: Vec<u32> },
 }
 
 impl PaletteContainer {
+    const SECTION_VOLUME: usize = 4096;
+
     pub fn blocks_void() -> Self {
         Self::single_valued(0)
     }
 
+    /// Whether the container only holds air.
+    pub fn is_void(&self) -> bool {
+        matches!(self, Self::SingleValued { value, .. } if value.inner() == 0)
+    }
+
     pub fn single_valued(value: impl Into<VarInt>) -> Self {
         Self::SingleValued {
             bits_per_entry: 0,
@@ -65,18 +69,138 @@ impl PaletteContainer {
                     data: packed_data.clone(),
                 }
             }
-            Palette::Direct { internal_data } => {
-                const BITS_PER_ENTRY: u8 = 15;
+            Palette::Direct { internal_data } => Self::Direct {
+                data: internal_data.iter().map(|id| map_id(id) as u32).collect(),
+            },
+        }
+    }
 
-                let global_data_iter = internal_data.iter().map(|id| map_id(id) as u32);
+    /// Biomes of the 64 cells of a section, given as registry IDs.
+    /// A section holds at most 8 different biomes, cells of further biomes use the first one.
+    pub fn biomes(cells: impl IntoIterator<Item = i32>) -> Self {
+        const MAX_BITS_PER_ENTRY: u8 = 3;
 
-                Self::Direct {
-                    bits_per_entry: BITS_PER_ENTRY,
-                    data: pack_direct(global_data_iter, BITS_PER_ENTRY),
+        let mut palette: Vec<i32> = Vec::new();
+        let indices: Vec<u32> = cells
+            .into_iter()
+            .map(
+                |biome_id| match palette.iter().position(|id| *id == biome_id) {
+                    Some(index) => index as u32,
+                    None if palette.len() < 1 << MAX_BITS_PER_ENTRY => {
+                        palette.push(biome_id);
+                        (palette.len() - 1) as u32
+                    }
+                    None => 0,
+                },
+            )
+            .collect();
+
+        match palette[..] {
+            [] => Self::single_valued(0),
+            [biome_id] => Self::single_valued(biome_id),
+            _ => {
+                let bits_per_entry = (usize::BITS - (palette.len() - 1).leading_zeros()) as u8;
+                Self::Indirect {
+                    bits_per_entry,
+                    palette: LengthPaddedVec::new(palette.into_iter().map(VarInt::new).collect()),
+                    data: pack_direct(indices.into_iter(), bits_per_entry),
                 }
             }
         }
     }
+
+    /// Global palette IDs of the 4096 blocks of the section.
+    pub fn entries(&self) -> Vec<u32> {
+        match self {
+            PaletteContainer::SingleValued { value, .. } => {
+                vec![value.inner() as u32; Self::SECTION_VOLUME]
+            }
+            PaletteContainer::Indirect {
+                bits_per_entry,
+                palette,
+                data,
+            } => unpack_direct(data, *bits_per_entry, Self::SECTION_VOLUME)
+                .into_iter()
+                .map(|index| {
+                    palette
+                        .inner()
+                        .get(index as usize)
+                        .map_or(0, |id| id.inner() as u32)
+                })
+                .collect(),
+            PaletteContainer::Direct { data } => data.clone(),
+        }
+    }
+
+    /// Number of bits used by the global palette of blocks.
+    fn direct_bits_per_entry(protocol_version: ProtocolVersion) -> u8 {
+        if protocol_version.is_before_inclusive(ProtocolVersion::V1_12_2) {
+            13
+        } else if protocol_version.is_before_inclusive(ProtocolVersion::V1_15_2) {
+            14
+        } else {
+            15
+        }
+    }
+
+    /// Prior to 1.16, entries could span across two longs.
+    fn pack(
+        entries: impl Iterator<Item = u32>,
+        bits_per_entry: u8,
+        protocol_version: ProtocolVersion,
+    ) -> Vec<u64> {
+        if protocol_version.is_after_inclusive(ProtocolVersion::V1_16) {
+            pack_direct(entries, bits_per_entry)
+        } else {
+            pack_compact(entries, bits_per_entry)
+        }
+    }
+
+    /// Up until 1.17.1 included, single valued palettes did not exist
+    /// and the palette of a global container was not omitted before 1.13.
+    fn encode_legacy(
+        &self,
+        writer: &mut BinaryWriter,
+        protocol_version: ProtocolVersion,
+    ) -> Result<(), BinaryWriterError> {
+        const MIN_BITS_PER_ENTRY: u8 = 4;
+
+        let (bits_per_entry, palette, data) = match self {
+            PaletteContainer::SingleValued { value, .. } => {
+                let data = vec![0; Self::SECTION_VOLUME * MIN_BITS_PER_ENTRY as usize / 64];
+                (MIN_BITS_PER_ENTRY, Some(vec![value.clone()]), data)
+            }
+            PaletteContainer::Indirect {
+                bits_per_entry,
+                palette,
+                data,
+            } => {
+                let data = if 64 % bits_per_entry == 0 {
+                    data.clone()
+                } else {
+                    let entries = unpack_direct(data, *bits_per_entry, Self::SECTION_VOLUME);
+                    Self::pack(entries.into_iter(), *bits_per_entry, protocol_version)
+                };
+                (*bits_per_entry, Some(palette.inner().clone()), data)
+            }
+            PaletteContainer::Direct { data } => {
+                let bits_per_entry = Self::direct_bits_per_entry(protocol_version);
+                let data = Self::pack(data.iter().copied(), bits_per_entry, protocol_version);
+                (bits_per_entry, None, data)
+            }
+        };
+
+        bits_per_entry.encode(writer, protocol_version)?;
+        match palette {
+            Some(palette) => LengthPaddedVec::new(palette).encode(writer, protocol_version)?,
+            None if protocol_version.is_before_inclusive(ProtocolVersion::V1_12_2) => {
+                VarInt::new(0).encode(writer, protocol_version)?;
+            }
+            None => {}
+        }
+        VarInt::new(data.len() as i32).encode(writer, protocol_version)?;
+        data.encode(writer, protocol_version)
+    }
 }
 
 impl EncodePacket for PaletteContainer {
@@ -85,6 +209,10 @@ impl EncodePacket for PaletteContainer {
         writer: &mut BinaryWriter,
         protocol_version: ProtocolVersion,
     ) -> Result<(), BinaryWriterError> {
+        if protocol_version.is_before_inclusive(ProtocolVersion::V1_17_1) {
+            return self.encode_legacy(writer, protocol_version);
+        }
+
         match self {
             PaletteContainer::SingleValued {
                 bits_per_entry,
@@ -108,15 +236,14 @@ impl EncodePacket for PaletteContainer {
                 }
                 data.encode(writer, protocol_version)?;
             }
-            PaletteContainer::Direct {
-                bits_per_entry,
-                data,
-            } => {
+            PaletteContainer::Direct { data } => {
+                let bits_per_entry = Self::direct_bits_per_entry(protocol_version);
+                let data = pack_direct(data.iter().copied(), bits_per_entry);
                 bits_per_entry.encode(writer, protocol_version)?;
                 if protocol_version.is_before_inclusive(ProtocolVersion::V1_21_4) {
                     VarInt::new(data.len() as i32).encode(writer, protocol_version)?;
                 }
-                for &long_value in data {
+                for long_value in data {
                     long_value.encode(writer, protocol_version)?;
                 }
             }
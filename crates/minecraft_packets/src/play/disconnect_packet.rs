@@ -13,6 +13,10 @@ pub struct DisconnectPacket {
 impl DisconnectPacket {
     pub fn text(text: impl Into<String>) -> DisconnectPacket {
         let component = Component::new(text);
+        Self::component(&component)
+    }
+
+    pub fn component(component: &Component) -> DisconnectPacket {
         Self {
             reason: component.to_json(),
             v1_20_3_reason: component.to_nbt(),
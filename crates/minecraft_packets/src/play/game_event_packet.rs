@@ -1,6 +1,6 @@
 use minecraft_protocol::prelude::*;
 
-/// This packet was introduced in Minecraft version 1.20.3.
+/// Also known as Change Game State before 1.20.3, this packet exists since 1.7.2.
 /// Used for a wide variety of game events, from weather to bed use to game mode to demo messages.
 #[derive(PacketOut)]
 pub struct GameEventPacket {
@@ -11,22 +11,73 @@ pub struct GameEventPacket {
 }
 
 impl GameEventPacket {
+    /// This event was introduced in Minecraft version 1.20.3.
     pub fn start_waiting_for_chunks(value: f32) -> Self {
+        Self::new(GameEvent::StartWaitingForChunks, value)
+    }
+
+    pub fn begin_raining() -> Self {
+        Self::new(GameEvent::BeginRaining, 0.0)
+    }
+
+    pub fn end_raining() -> Self {
+        Self::new(GameEvent::EndRaining, 0.0)
+    }
+
+    /// The level goes from 0 (no rain) to 1 (full rain).
+    pub fn rain_level(level: f32) -> Self {
+        Self::new(GameEvent::RainLevelChange, level)
+    }
+
+    /// The level goes from 0 (no thunder) to 1 (full thunder), thunder is only visible while raining.
+    pub fn thunder_level(level: f32) -> Self {
+        Self::new(GameEvent::ThunderLevelChange, level)
+    }
+
+    fn new(event: GameEvent, value: f32) -> Self {
         Self {
-            event: GameEvent::StartWaitingForChunks.get_event_id(),
+            event: event.get_event_id(),
             value,
         }
     }
 }
 
 enum GameEvent {
+    BeginRaining,
+    EndRaining,
+    RainLevelChange,
+    ThunderLevelChange,
     StartWaitingForChunks,
 }
 
 impl GameEvent {
     fn get_event_id(&self) -> u8 {
         match self {
+            GameEvent::BeginRaining => 1,
+            GameEvent::EndRaining => 2,
+            GameEvent::RainLevelChange => 7,
+            GameEvent::ThunderLevelChange => 8,
             GameEvent::StartWaitingForChunks => 13,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weather_events_should_be_encoded_the_same_way_in_every_version() {
+        // Given
+        let packet = GameEventPacket::rain_level(1.0);
+
+        for version in [ProtocolVersion::V1_7_2, ProtocolVersion::latest()] {
+            // When
+            let mut writer = BinaryWriter::default();
+            packet.encode(&mut writer, version).unwrap();
+
+            // Then
+            assert_eq!(writer.into_inner(), vec![7, 0x3F, 0x80, 0x00, 0x00]);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use minecraft_protocol::prelude::*;
+
+/// Sets the center, size and warnings of the world border.
+/// Prior to 1.17, this is the initialize action of the World Border packet.
+/// The packet does not exist prior to 1.8.
+#[derive(PacketOut)]
+pub struct InitializeWorldBorderPacket {
+    /// Always 3 to initialize the border, up until 1.16.5 included.
+    #[pvn(..755)]
+    action: VarInt,
+    x: f64,
+    z: f64,
+    old_diameter: f64,
+    new_diameter: f64,
+    /// Number of real-time milliseconds until the new diameter is reached.
+    speed: VarLong,
+    /// Resulting coordinates from a portal teleport are limited to ±value.
+    portal_teleport_boundary: VarInt,
+    /// The warning time was sent before the warning distance up until 1.16.5 included.
+    #[pvn(..755)]
+    v1_8_warning_time: VarInt,
+    /// Distance in blocks at which the screen turns red.
+    warning_blocks: VarInt,
+    /// Time in seconds before a moving border reaches the player at which the screen turns red.
+    #[pvn(755..)]
+    warning_time: VarInt,
+}
+
+impl InitializeWorldBorderPacket {
+    const INITIALIZE_ACTION: i32 = 3;
+    const PORTAL_TELEPORT_BOUNDARY: i32 = 29_999_984;
+
+    pub fn new(center: (f64, f64), diameter: f64, warning_blocks: i32, warning_time: i32) -> Self {
+        Self {
+            action: VarInt::new(Self::INITIALIZE_ACTION),
+            x: center.0,
+            z: center.1,
+            old_diameter: diameter,
+            new_diameter: diameter,
+            speed: VarLong::new(0),
+            portal_teleport_boundary: VarInt::new(Self::PORTAL_TELEPORT_BOUNDARY),
+            v1_8_warning_time: VarInt::new(warning_time),
+            warning_blocks: VarInt::new(warning_blocks),
+            warning_time: VarInt::new(warning_time),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warning_time_moved_after_the_warning_distance_in_1_17() {
+        // Given
+        let packet = InitializeWorldBorderPacket::new((8.0, -8.0), 100.0, 5, 15);
+
+        for (version, action, warnings) in [
+            (ProtocolVersion::V1_16_4, vec![3], [15, 5]),
+            (ProtocolVersion::V1_17, vec![], [5, 15]),
+        ] {
+            // When
+            let mut writer = BinaryWriter::default();
+            packet.encode(&mut writer, version).unwrap();
+            let bytes = writer.into_inner();
+
+            // Then
+            assert_eq!(&bytes[..action.len()], action.as_slice());
+            let doubles = &bytes[action.len()..action.len() + 32];
+            assert_eq!(&doubles[..8], 8.0_f64.to_be_bytes());
+            assert_eq!(&doubles[24..], 100.0_f64.to_be_bytes());
+            let rest = &bytes[action.len() + 32..];
+            // Speed, then the portal teleport boundary on 4 bytes
+            assert_eq!(rest[0], 0);
+            assert_eq!(&rest[5..], warnings);
+        }
+    }
+}
@@ -1,23 +1,32 @@
 pub mod boss_bar_packet;
+pub mod chunk_batch_finished_packet;
+pub mod chunk_batch_received_packet;
+pub mod chunk_batch_start_packet;
 pub mod chunk_data_and_update_light_packet;
 pub mod client_bound_keep_alive_packet;
+pub mod client_information_packet;
 pub mod commands_packet;
 mod data;
 pub mod disconnect_packet;
 pub mod game_event_packet;
+pub mod initialize_world_border_packet;
 pub mod legacy_chat_message_packet;
 pub mod login_packet;
 pub mod play_client_bound_plugin_message_packet;
 pub mod player_info_update_packet;
 pub mod player_position_packet;
+pub mod server_bound_plugin_message_packet;
 pub mod set_chunk_cache_center_packet;
 pub mod set_default_spawn_position_packet;
 pub mod set_entity_data_packet;
 pub mod set_player_position_and_rotation_packet;
 pub mod set_player_position_packet;
+pub mod set_player_rotation_packet;
 pub mod synchronize_player_position_packet;
 pub mod system_chat_message_packet;
 pub mod tab_list_packet;
+pub mod unload_chunk_packet;
+pub mod update_light_packet;
 pub mod update_time_packet;
 
 pub use data::chunk_context::{VoidChunkContext, WorldContext};
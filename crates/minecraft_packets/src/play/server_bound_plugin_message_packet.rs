@@ -0,0 +1,147 @@
+use minecraft_protocol::prelude::*;
+
+/// Custom payload sent by the client, used in both the configuration and play states.
+#[derive(Default, PacketIn)]
+pub struct ServerBoundPluginMessagePacket {
+    channel: String,
+    #[pvn(..47)]
+    v1_7_data: UShortPrefixed<Vec<u8>>,
+    #[pvn(47..)]
+    data: Vec<u8>,
+}
+
+impl ServerBoundPluginMessagePacket {
+    pub fn new(channel: impl ToString, data: Vec<u8>) -> Self {
+        Self {
+            channel: channel.to_string(),
+            data,
+            ..Self::default()
+        }
+    }
+
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// Returns the data of the message, as decoded for the given protocol version.
+    pub fn data(&self, protocol_version: ProtocolVersion) -> &[u8] {
+        if protocol_version.is_after_inclusive(ProtocolVersion::V1_8) {
+            &self.data
+        } else {
+            self.v1_7_data.inner()
+        }
+    }
+
+    /// Returns the brand of the client, if this message is a brand message.
+    pub fn brand(&self, protocol_version: ProtocolVersion) -> Option<String> {
+        if !matches!(self.channel.as_str(), "minecraft:brand" | "MC|Brand") {
+            return None;
+        }
+
+        let data = self.data(protocol_version);
+        if protocol_version.is_after_inclusive(ProtocolVersion::V1_8) {
+            let mut reader = BinaryReader::new(data);
+            reader
+                .read::<VarIntPrefixedString>()
+                .ok()
+                .map(VarIntPrefixedString::into_inner)
+        } else {
+            // Prior to 1.8, the brand is not prefixed with its length
+            Some(String::from_utf8_lossy(data).into_owned())
+        }
+    }
+
+    /// Returns the channels listed in a register or unregister message.
+    pub fn channels(&self, protocol_version: ProtocolVersion) -> Vec<String> {
+        self.data(protocol_version)
+            .split(|&byte| byte == 0)
+            .filter(|channel| !channel.is_empty())
+            .map(|channel| String::from_utf8_lossy(channel).into_owned())
+            .collect()
+    }
+
+    pub fn is_register(&self) -> bool {
+        matches!(self.channel.as_str(), "minecraft:register" | "REGISTER")
+    }
+
+    pub fn is_unregister(&self) -> bool {
+        matches!(self.channel.as_str(), "minecraft:unregister" | "UNREGISTER")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::play::server_bound_plugin_message_packet::ServerBoundPluginMessagePacket;
+    use minecraft_protocol::prelude::{BinaryReader, DecodePacket, ProtocolVersion};
+
+    #[test]
+    fn test_brand_decode() {
+        let snapshot = [
+            15, 109, 105, 110, 101, 99, 114, 97, 102, 116, 58, 98, 114, 97, 110, 100, 6, 102, 97,
+            98, 114, 105, 99,
+        ];
+        let mut reader = BinaryReader::new(&snapshot);
+
+        let packet =
+            ServerBoundPluginMessagePacket::decode(&mut reader, ProtocolVersion::V1_21_4).unwrap();
+
+        assert_eq!("minecraft:brand", packet.channel());
+        assert_eq!(
+            Some("fabric".to_string()),
+            packet.brand(ProtocolVersion::V1_21_4)
+        );
+    }
+
+    #[test]
+    fn test_v1_7_brand_decode() {
+        let snapshot = [
+            8, 77, 67, 124, 66, 114, 97, 110, 100, 0, 7, 118, 97, 110, 105, 108, 108, 97,
+        ];
+        let mut reader = BinaryReader::new(&snapshot);
+
+        let packet =
+            ServerBoundPluginMessagePacket::decode(&mut reader, ProtocolVersion::V1_7_2).unwrap();
+
+        assert_eq!(
+            Some("vanilla".to_string()),
+            packet.brand(ProtocolVersion::V1_7_2)
+        );
+        assert_eq!(0, reader.remaining());
+    }
+
+    #[test]
+    fn test_register_channels() {
+        let packet = ServerBoundPluginMessagePacket::new(
+            "minecraft:register",
+            b"fabric:registry/sync\0fabric-screen-handler-api-v1:open_screen".to_vec(),
+        );
+
+        assert!(packet.is_register());
+        assert_eq!(
+            vec![
+                "fabric:registry/sync".to_string(),
+                "fabric-screen-handler-api-v1:open_screen".to_string()
+            ],
+            packet.channels(ProtocolVersion::V1_21_4)
+        );
+    }
+
+    #[test]
+    fn test_v1_7_register_channels_decode() {
+        let mut snapshot = vec![8];
+        snapshot.extend_from_slice(b"REGISTER");
+        snapshot.extend_from_slice(&[0, 7]);
+        snapshot.extend_from_slice(b"a:b\0c:d");
+        let mut reader = BinaryReader::new(&snapshot);
+
+        let packet =
+            ServerBoundPluginMessagePacket::decode(&mut reader, ProtocolVersion::V1_7_2).unwrap();
+
+        assert!(packet.is_register());
+        assert_eq!(
+            vec!["a:b".to_string(), "c:d".to_string()],
+            packet.channels(ProtocolVersion::V1_7_2)
+        );
+        assert!(packet.channels(ProtocolVersion::V1_8).is_empty());
+    }
+}
@@ -7,11 +7,10 @@ pub struct SetEntityMetadataPacket {
 }
 
 impl SetEntityMetadataPacket {
-    pub fn skin_layers(entity_id: i32) -> Self {
+    /// `displayed_skin_parts` is the bit mask sent by the client in its settings.
+    pub fn skin_layers(entity_id: i32, displayed_skin_parts: u8) -> Self {
         let entity_metadata = vec![
-            EntityMetadata::SkinParts(Metadata::Byte(
-                0x01 | 0x02 | 0x04 | 0x08 | 0x10 | 0x20 | 0x40,
-            )),
+            EntityMetadata::SkinParts(Metadata::Byte(displayed_skin_parts as i8)),
             EntityMetadata::End,
         ];
 
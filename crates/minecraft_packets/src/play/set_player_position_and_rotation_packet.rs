@@ -4,6 +4,9 @@ use minecraft_protocol::prelude::*;
 pub struct SetPlayerPositionAndRotationPacket {
     pub x: f64,
     pub feet_y: f64,
+    /// Up until 1.7.10 included, the Y position of the head is sent as well
+    #[pvn(..47)]
+    pub v1_7_head_y: f64,
     pub z: f64,
     pub yaw: f32,
     pub pitch: f32,
@@ -53,4 +53,10 @@ impl SynchronizePlayerPositionPacket {
             on_ground: false,
         }
     }
+
+    pub const fn set_rotation(mut self, yaw: f32, pitch: f32) -> Self {
+        self.yaw = yaw;
+        self.pitch = pitch;
+        self
+    }
 }
@@ -0,0 +1,49 @@
+use minecraft_protocol::prelude::*;
+
+/// Named Forget Level Chunk, since 1.9 included.
+/// Prior to 1.9, a Chunk Data packet without any section is sent instead.
+#[derive(PacketOut)]
+pub struct UnloadChunkPacket {
+    /// Up until 1.20.1 included, the chunk X comes first
+    #[pvn(..764)]
+    v1_9_chunk_x: i32,
+    chunk_z: i32,
+    #[pvn(764..)]
+    chunk_x: i32,
+}
+
+impl UnloadChunkPacket {
+    pub const fn new(chunk_x: i32, chunk_z: i32) -> Self {
+        Self {
+            v1_9_chunk_x: chunk_x,
+            chunk_z,
+            chunk_x,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(protocol_version: ProtocolVersion) -> Vec<u8> {
+        let mut writer = BinaryWriter::default();
+        UnloadChunkPacket::new(1, 2)
+            .encode(&mut writer, protocol_version)
+            .unwrap();
+        writer.into_inner()
+    }
+
+    #[test]
+    fn test_unload_chunk_packet_x_first() {
+        assert_eq!(encode(ProtocolVersion::V1_20), vec![0, 0, 0, 1, 0, 0, 0, 2]);
+    }
+
+    #[test]
+    fn test_unload_chunk_packet_z_first() {
+        assert_eq!(
+            encode(ProtocolVersion::V1_20_2),
+            vec![0, 0, 0, 2, 0, 0, 0, 1]
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use crate::play::data::light_data::LightData;
+use minecraft_protocol::prelude::*;
+
+/// Since 1.14 included, up until 1.17.1 included, the light is not part of the Chunk Data packet.
+/// It must be sent before the chunk it applies to.
+#[derive(PacketOut)]
+pub struct UpdateLightPacket {
+    chunk_x: VarInt,
+    chunk_z: VarInt,
+    /// If edges should be trusted for light updates.
+    /// Since 1.16 included
+    #[pvn(735..)]
+    trust_edges: bool,
+    light_data: LightData,
+}
+
+impl UpdateLightPacket {
+    pub fn new(chunk_x: i32, chunk_z: i32, light_data: LightData) -> Self {
+        Self {
+            chunk_x: VarInt::new(chunk_x),
+            chunk_z: VarInt::new(chunk_z),
+            trust_edges: true,
+            light_data,
+        }
+    }
+
+    pub fn with_level(chunk_x: i32, chunk_z: i32, light_level: u8, section_count: u32) -> Self {
+        Self::new(
+            chunk_x,
+            chunk_z,
+            LightData::new_with_level(light_level, section_count),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_light_packet_v1_14() {
+        // Given
+        let packet = UpdateLightPacket::with_level(1, 2, 15, 1);
+        let mut writer = BinaryWriter::default();
+
+        // When
+        packet.encode(&mut writer, ProtocolVersion::V1_14).unwrap();
+        let bytes = writer.into_inner();
+
+        // Then
+        assert_eq!(&bytes[..6], &[1, 2, 2, 2, 0, 0]);
+        assert_eq!(&bytes[6..9], &[0x80, 0x10, 0xFF]);
+        assert_eq!(bytes.len(), 6 + 2 * (2 + 2048));
+    }
+
+    #[test]
+    fn test_update_light_packet_v1_17() {
+        // Given
+        let packet = UpdateLightPacket::with_level(0, 0, 15, 1);
+        let mut writer = BinaryWriter::default();
+
+        // When
+        packet.encode(&mut writer, ProtocolVersion::V1_17).unwrap();
+        let bytes = writer.into_inner();
+
+        // Then
+        assert_eq!(
+            &bytes[..23],
+            &[
+                0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 2, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0
+            ]
+        );
+        assert_eq!(&bytes[23..27], &[1, 0x80, 0x10, 0xFF]);
+        assert_eq!(bytes.len(), 23 + 2 * (1 + 2 + 2048));
+    }
+}
@@ -46,6 +46,11 @@ impl Dimension {
         }
     }
 
+    #[inline]
+    pub const fn has_sky_light(self) -> bool {
+        matches!(self, Dimension::Overworld)
+    }
+
     #[inline]
     pub const fn height(self) -> i32 {
         256
@@ -21,8 +21,8 @@ pub mod prelude {
     pub use macros::PacketIn;
     pub use macros::PacketOut;
     pub use pico_binutils::prelude::{
-        BinaryReader, BinaryReaderError, BinaryWriter, BinaryWriterError, VarInt,
-        VarIntPrefixedString,
+        BinaryReader, BinaryReaderError, BinaryWriter, BinaryWriterError, UShortPrefixed, VarInt,
+        VarIntPrefixed, VarIntPrefixedString, VarLong,
     };
     pub use pico_nbt::prelude::*;
     pub use protocol_version::protocol_version::ProtocolVersion;
@@ -4,11 +4,16 @@ use minecraft_protocol::prelude::*;
 use thiserror::Error;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
+const READ_BUFFER_SIZE: usize = 4096;
+const MAXIMUM_VAR_INT_SIZE: usize = 5;
+
 pub struct PacketStream<Stream>
 where
     Stream: AsyncWrite + AsyncRead + Unpin,
 {
     stream: Stream,
+    /// Bytes received that do not make a complete packet yet
+    read_buffer: Vec<u8>,
 }
 
 impl<Stream> PacketStream<Stream>
@@ -16,20 +21,29 @@ where
     Stream: AsyncWrite + AsyncRead + Unpin,
 {
     pub fn new(stream: Stream) -> PacketStream<Stream> {
-        PacketStream { stream }
+        PacketStream {
+            stream,
+            read_buffer: Vec::new(),
+        }
     }
 
+    /// Reads the next packet from the stream.
+    ///
+    /// This method is cancellation safe: the bytes already received are kept
+    /// until the packet is complete, so it can be used inside `tokio::select!`.
     pub async fn read_packet(&mut self) -> Result<RawPacket, PacketStreamError> {
-        let packet_length = self.read_packet_length().await?;
+        let mut buffer = [0u8; READ_BUFFER_SIZE];
+        loop {
+            if let Some(packet) = self.take_buffered_packet()? {
+                return Ok(packet);
+            }
 
-        if packet_length == 0 {
-            return Err(PacketStreamError::EmptyPacket);
+            let read = self.stream.read(&mut buffer).await?;
+            if read == 0 {
+                return Err(tokio::io::Error::from(tokio::io::ErrorKind::UnexpectedEof).into());
+            }
+            self.read_buffer.extend_from_slice(&buffer[..read]);
         }
-
-        let mut data = vec![0u8; packet_length];
-        self.stream.read_exact(&mut data).await?;
-
-        RawPacket::new(data).map_err(|_| PacketStreamError::EmptyPacket)
     }
 
     pub async fn write_packet(&mut self, packet: RawPacket) -> Result<(), PacketStreamError> {
@@ -60,24 +74,48 @@ where
         &mut self.stream
     }
 
-    async fn read_packet_length(&mut self) -> Result<usize, PacketStreamError> {
-        let mut var_int_buf = Vec::new();
+    /// Removes the first packet from the read buffer, if it was completely received.
+    fn take_buffered_packet(&mut self) -> Result<Option<RawPacket>, PacketStreamError> {
+        let Some((packet_length, length_size)) = self.buffered_packet_length()? else {
+            return Ok(None);
+        };
 
-        for _ in 0..5 {
-            let mut byte = [0u8; 1];
-            self.stream.read_exact(&mut byte).await?;
-            var_int_buf.push(byte[0]);
+        if packet_length == 0 {
+            self.read_buffer.drain(..length_size);
+            return Err(PacketStreamError::EmptyPacket);
+        }
 
-            match get_packet_length(&var_int_buf) {
-                Ok(length) => return Ok(length),
-                Err(PacketLengthParseError::BinaryReader(BinaryReaderError::UnexpectedEof)) => {
-                    continue;
-                }
-                Err(e) => return Err(e.into()),
-            }
+        let packet_end = length_size + packet_length;
+        if self.read_buffer.len() < packet_end {
+            return Ok(None);
         }
 
-        Err(PacketLengthParseError::BinaryReader(BinaryReaderError::UnexpectedEof).into())
+        let data = self.read_buffer[length_size..packet_end].to_vec();
+        self.read_buffer.drain(..packet_end);
+        RawPacket::new(data)
+            .map(Some)
+            .map_err(|_| PacketStreamError::EmptyPacket)
+    }
+
+    /// Returns the length of the first buffered packet and the size of the length itself.
+    fn buffered_packet_length(&self) -> Result<Option<(usize, usize)>, PacketStreamError> {
+        let length_size = self
+            .read_buffer
+            .iter()
+            .take(MAXIMUM_VAR_INT_SIZE)
+            .position(|byte| byte & 0x80 == 0)
+            .map(|position| position + 1);
+
+        match length_size {
+            Some(length_size) => {
+                let packet_length = get_packet_length(&self.read_buffer[..length_size])?;
+                Ok(Some((packet_length, length_size)))
+            }
+            None if self.read_buffer.len() >= MAXIMUM_VAR_INT_SIZE => {
+                Err(PacketLengthParseError::BinaryReader(BinaryReaderError::UnexpectedEof).into())
+            }
+            None => Ok(None),
+        }
     }
 }
 
@@ -170,6 +208,47 @@ mod tests {
         assert_eq!(packet.data(), [84]);
     }
 
+    #[tokio::test]
+    async fn test_read_is_cancellation_safe() {
+        // Given
+        let reader = tokio_test::io::Builder::new()
+            .read(&[2, 42])
+            .wait(Duration::from_millis(50))
+            .read(&[84])
+            .build();
+
+        let mut packet_stream = PacketStream::new(reader);
+
+        // When
+        let cancelled =
+            tokio::time::timeout(Duration::from_millis(10), packet_stream.read_packet()).await;
+        let packet = packet_stream.read_packet().await.unwrap();
+
+        // Then
+        assert!(cancelled.is_err());
+        assert_eq!(packet.packet_id().unwrap(), 42);
+        assert_eq!(packet.data(), [84]);
+    }
+
+    #[tokio::test]
+    async fn test_two_packets_in_one_read() {
+        // Given
+        let reader = tokio_test::io::Builder::new()
+            .read(&[1, 42, 2, 43, 84])
+            .build();
+
+        let mut packet_stream = PacketStream::new(reader);
+
+        // When
+        let packet_1 = packet_stream.read_packet().await.unwrap();
+        let packet_2 = packet_stream.read_packet().await.unwrap();
+
+        // Then
+        assert_eq!(packet_1.packet_id().unwrap(), 42);
+        assert_eq!(packet_2.packet_id().unwrap(), 43);
+        assert_eq!(packet_2.data(), [84]);
+    }
+
     #[tokio::test]
     async fn test_two_packets() {
         // Given
@@ -25,5 +25,5 @@ pub mod prelude {
     #[cfg(feature = "length_prefixed")]
     pub use crate::length_prefixed::writer::WriteLengthPrefix;
     #[cfg(feature = "var_int")]
-    pub use crate::var_int::VarInt;
+    pub use crate::var_int::{VarInt, VarLong};
 }
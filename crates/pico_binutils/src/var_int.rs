@@ -105,6 +105,71 @@ impl WriteBytes for VarInt {
     }
 }
 
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd, Eq, Hash, Ord)]
+pub struct VarLong(i64);
+
+impl VarLong {
+    pub fn new(value: i64) -> Self {
+        Self(value)
+    }
+
+    pub fn inner(&self) -> i64 {
+        self.0
+    }
+}
+
+impl From<i64> for VarLong {
+    fn from(value: i64) -> Self {
+        Self(value)
+    }
+}
+
+#[cfg(feature = "binary_reader")]
+impl ReadBytes for VarLong {
+    #[inline]
+    fn read(reader: &mut BinaryReader) -> Result<Self, BinaryReaderError> {
+        let mut num_read = 0;
+        let mut result: u64 = 0;
+
+        loop {
+            let byte: u8 = reader.read()?;
+
+            let value = (byte & SEGMENT_BITS) as u64;
+            result |= value << (7 * num_read);
+
+            num_read += 1;
+            if num_read > 10 {
+                return Err(BinaryReaderError::VarIntTooBig);
+            }
+
+            if byte & CONTINUE_BIT == 0 {
+                break;
+            }
+        }
+
+        Ok(VarLong(result as i64))
+    }
+}
+
+#[cfg(feature = "binary_writer")]
+impl WriteBytes for VarLong {
+    fn write(&self, writer: &mut BinaryWriter) -> Result<(), BinaryWriterError> {
+        let mut value = self.0 as u64;
+        loop {
+            let mut temp = (value & (SEGMENT_BITS as u64)) as u8;
+            value >>= 7;
+            if value != 0 {
+                temp |= CONTINUE_BIT;
+            }
+            writer.write(&temp)?;
+            if value == 0 {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,4 +222,28 @@ mod tests {
         let result = reader.read::<VarInt>();
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_var_long_round_trip() {
+        for (bytes, expected) in [
+            (vec![0x00], 0),
+            (vec![0xff, 0x01], 255),
+            (
+                vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f],
+                i64::MAX,
+            ),
+            (
+                vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+                -1,
+            ),
+        ] {
+            let mut reader = BinaryReader::new(&bytes);
+            let result: VarLong = reader.read().unwrap();
+            assert_eq!(result.inner(), expected);
+
+            let mut writer = BinaryWriter::new();
+            writer.write(&VarLong::new(expected)).unwrap();
+            assert_eq!(writer.into_inner(), bytes);
+        }
+    }
 }
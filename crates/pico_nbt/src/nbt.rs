@@ -131,6 +131,13 @@ impl Nbt {
         }
     }
 
+    pub fn get_byte(&self) -> Option<i8> {
+        match self {
+            Self::Byte { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+
     pub fn get_short(&self) -> Option<i16> {
         match self {
             Self::Short { value, .. } => Some(*value),
@@ -145,6 +152,13 @@ impl Nbt {
         }
     }
 
+    pub fn get_long_array(&self) -> Option<Vec<i64>> {
+        match self {
+            Self::LongArray { value, .. } => Some(value.clone()),
+            _ => None,
+        }
+    }
+
     pub fn get_int(&self) -> Option<i32> {
         match self {
             Nbt::Int { value, .. } => Some(*value),
@@ -0,0 +1,498 @@
+use crate::block_entity::BlockEntity;
+use crate::decompress::{decompress_gz, decompress_lz4_blocks, decompress_zlib};
+use crate::pack_direct::unpack_direct;
+use crate::schematic::{BiomeResolution, Schematic, SchematicError};
+use blocks_report::{BlockStateLookup, InternalId};
+use minecraft_protocol::prelude::Coordinates;
+use pico_nbt::prelude::Nbt;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const SECTION_SIZE: usize = 16;
+const SECTION_VOLUME: usize = SECTION_SIZE * SECTION_SIZE * SECTION_SIZE;
+const BIOME_CELL_COUNT: usize = 64;
+const REGION_SIZE: i32 = 32;
+const SECTOR_SIZE: usize = 4096;
+
+/// Area of a vanilla world to load, in chunk coordinates. Both corners are included.
+pub struct AnvilArea {
+    /// Folder of the dimension inside the world folder:
+    /// empty for the overworld, `DIM-1` for the nether and `DIM1` for the end.
+    pub dimension_folder: String,
+    pub min_chunk: (i32, i32),
+    pub max_chunk: (i32, i32),
+}
+
+impl AnvilArea {
+    /// Returns the lowest and the highest chunk coordinates of the area.
+    fn corners(&self) -> ((i32, i32), (i32, i32)) {
+        (
+            (
+                self.min_chunk.0.min(self.max_chunk.0),
+                self.min_chunk.1.min(self.max_chunk.1),
+            ),
+            (
+                self.min_chunk.0.max(self.max_chunk.0),
+                self.min_chunk.1.max(self.max_chunk.1),
+            ),
+        )
+    }
+
+    /// Paths of the region files holding the chunks of the area, some of them may not exist.
+    pub(crate) fn region_files(&self, world_folder: &Path) -> Vec<PathBuf> {
+        let region_folder = world_folder.join(&self.dimension_folder).join("region");
+        let (min_chunk, max_chunk) = self.corners();
+        let mut region_files = Vec::new();
+        for region_x in (min_chunk.0 >> 5)..=(max_chunk.0 >> 5) {
+            for region_z in (min_chunk.1 >> 5)..=(max_chunk.1 >> 5) {
+                region_files.push(region_folder.join(format!("r.{region_x}.{region_z}.mca")));
+            }
+        }
+        region_files
+    }
+}
+
+/// Loads the chunks of a vanilla world saved by 1.18 or newer from its `region/*.mca` files.
+///
+/// Missing chunks are filled with air, the height of the area is trimmed to the sections holding blocks.
+/// Block entities outside of the trimmed height are left out.
+pub fn load_anvil_area(
+    world_folder: &Path,
+    area: &AnvilArea,
+    block_state_lookup: &BlockStateLookup,
+) -> Result<Schematic, SchematicError> {
+    let region_folder = world_folder.join(&area.dimension_folder).join("region");
+    let (min_chunk, max_chunk) = area.corners();
+
+    let internal_air_id = Schematic::internal_air_id(block_state_lookup)?;
+    let mut decoder = ChunkDecoder::new(block_state_lookup, internal_air_id);
+    let mut chunks = Vec::new();
+    let mut block_entities = Vec::new();
+    for region_x in (min_chunk.0 >> 5)..=(max_chunk.0 >> 5) {
+        for region_z in (min_chunk.1 >> 5)..=(max_chunk.1 >> 5) {
+            let Some(region) = RegionFile::open(&region_folder, region_x, region_z)? else {
+                continue;
+            };
+            let chunk_xs = (region_x * REGION_SIZE).max(min_chunk.0)
+                ..=(region_x * REGION_SIZE + REGION_SIZE - 1).min(max_chunk.0);
+            for chunk_x in chunk_xs {
+                let chunk_zs = (region_z * REGION_SIZE).max(min_chunk.1)
+                    ..=(region_z * REGION_SIZE + REGION_SIZE - 1).min(max_chunk.1);
+                for chunk_z in chunk_zs {
+                    if let Some(chunk) = region.read_chunk(chunk_x, chunk_z)? {
+                        let sections = decoder.decode_sections(&chunk, (chunk_x, chunk_z));
+                        block_entities.extend(read_block_entities(&chunk));
+                        chunks.push((chunk_x - min_chunk.0, chunk_z - min_chunk.1, sections));
+                    }
+                }
+            }
+        }
+    }
+    decoder.report_unknown_entries();
+
+    let section_ys = chunks
+        .iter()
+        .flat_map(|(_, _, sections)| sections)
+        .filter(|section| section.has_blocks)
+        .map(|section| section.y);
+    let min_section_y = section_ys.clone().min().unwrap_or_default();
+    let max_section_y = section_ys.max().unwrap_or(min_section_y - 1);
+    let height_in_sections = max_section_y - min_section_y + 1;
+
+    let width = (max_chunk.0 - min_chunk.0 + 1) as usize * SECTION_SIZE;
+    let length = (max_chunk.1 - min_chunk.1 + 1) as usize * SECTION_SIZE;
+    let height = height_in_sections as usize * SECTION_SIZE;
+    let mut block_data = vec![internal_air_id; width * height * length];
+    let mut biome_data = vec![0; block_data.len() / BIOME_CELL_COUNT];
+    for (chunk_x, chunk_z, sections) in &chunks {
+        for section in sections {
+            if section.y < min_section_y || section.y > max_section_y {
+                continue;
+            }
+            let origin = Coordinates::new(
+                *chunk_x * SECTION_SIZE as i32,
+                (section.y - min_section_y) * SECTION_SIZE as i32,
+                *chunk_z * SECTION_SIZE as i32,
+            );
+            section.copy_blocks(&mut block_data, origin, width, length);
+            section.copy_biomes(&mut biome_data, origin / 4, width / 4, length / 4);
+        }
+    }
+
+    let dimensions = Coordinates::new(width as i32, height as i32, length as i32);
+    let world_origin = Coordinates::new(
+        min_chunk.0 * SECTION_SIZE as i32,
+        min_section_y * SECTION_SIZE as i32,
+        min_chunk.1 * SECTION_SIZE as i32,
+    );
+    let block_entities = block_entities
+        .into_iter()
+        .map(|block_entity| block_entity.translate(world_origin * -1))
+        .filter(|block_entity| (0..dimensions.y()).contains(&block_entity.position().y()))
+        .collect();
+    Ok(Schematic::from_blocks(
+        block_data,
+        dimensions,
+        Coordinates::default(),
+        internal_air_id,
+    )
+    .with_world_origin(world_origin)
+    .with_biomes(decoder.biome_palette, biome_data, BiomeResolution::Cell)
+    .with_block_entities(block_entities))
+}
+
+/// Block entities of a chunk, positioned in world coordinates.
+fn read_block_entities(chunk: &Nbt) -> Vec<BlockEntity> {
+    chunk
+        .find_tag("block_entities")
+        .and_then(|t| t.get_nbt_vec())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|block_entity| {
+            let axis = |axis: &str| block_entity.find_tag(axis).and_then(|t| t.get_int());
+            let position = Coordinates::new(axis("x")?, axis("y")?, axis("z")?);
+            BlockEntity::from_compound(position, block_entity)
+        })
+        .collect()
+}
+
+/// A region file, holding 32×32 chunks behind a table of their location.
+struct RegionFile {
+    bytes: Vec<u8>,
+    folder: PathBuf,
+}
+
+impl RegionFile {
+    /// Returns `None` when no chunk of the region was ever saved.
+    fn open(folder: &Path, region_x: i32, region_z: i32) -> io::Result<Option<Self>> {
+        match fs::read(folder.join(format!("r.{region_x}.{region_z}.mca"))) {
+            Ok(bytes) => Ok(Some(Self {
+                bytes,
+                folder: folder.to_path_buf(),
+            })),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn read_chunk(&self, chunk_x: i32, chunk_z: i32) -> Result<Option<Nbt>, SchematicError> {
+        let truncated = || io::Error::new(io::ErrorKind::UnexpectedEof, "truncated region file");
+
+        let location_index = 4
+            * (chunk_x.rem_euclid(REGION_SIZE) + chunk_z.rem_euclid(REGION_SIZE) * REGION_SIZE)
+                as usize;
+        let Some(location) = self.bytes.get(location_index..location_index + 4) else {
+            return Ok(None);
+        };
+        let sector = u32::from_be_bytes([0, location[0], location[1], location[2]]) as usize;
+        if sector == 0 {
+            return Ok(None);
+        }
+
+        let start = sector * SECTOR_SIZE;
+        let header = self.bytes.get(start..start + 5).ok_or_else(truncated)?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let compression = header[4];
+        // Chunks too large for the region file are stored next to it
+        let external_payload;
+        let payload = if compression & 0x80 == 0 {
+            self.bytes
+                .get(start + 5..start + 4 + length)
+                .ok_or_else(truncated)?
+        } else {
+            external_payload = fs::read(self.folder.join(format!("c.{chunk_x}.{chunk_z}.mcc")))?;
+            &external_payload
+        };
+
+        let bytes = match compression & 0x7F {
+            1 => decompress_gz(payload)?,
+            2 => decompress_zlib(payload)?,
+            3 => payload.to_vec(),
+            4 => decompress_lz4_blocks(payload)?,
+            unsupported => return Err(SchematicError::UnsupportedChunkCompression(unsupported)),
+        };
+        Ok(Some(Nbt::from_bytes(&bytes)?))
+    }
+}
+
+struct AnvilSection {
+    y: i32,
+    has_blocks: bool,
+    /// Blocks indexed by `y << 8 | z << 4 | x`.
+    blocks: Vec<InternalId>,
+    /// Indices into the biome palette of the world, one per 4×4×4 cell.
+    biomes: Vec<u32>,
+}
+
+impl AnvilSection {
+    fn copy_blocks(
+        &self,
+        block_data: &mut [InternalId],
+        origin: Coordinates,
+        width: usize,
+        length: usize,
+    ) {
+        for (index, &internal_id) in self.blocks.iter().enumerate() {
+            let x = origin.x() as usize + (index & 0xF);
+            let z = origin.z() as usize + ((index >> 4) & 0xF);
+            let y = origin.y() as usize + (index >> 8);
+            block_data[(y * length + z) * width + x] = internal_id;
+        }
+    }
+
+    fn copy_biomes(
+        &self,
+        biome_data: &mut [u32],
+        origin: Coordinates,
+        width: usize,
+        length: usize,
+    ) {
+        for (index, &biome) in self.biomes.iter().enumerate() {
+            let x = origin.x() as usize + (index & 0x3);
+            let z = origin.z() as usize + ((index >> 2) & 0x3);
+            let y = origin.y() as usize + (index >> 4);
+            biome_data[(y * length + z) * width + x] = biome;
+        }
+    }
+}
+
+/// Reads the paletted sections of chunks, sharing the lookups between all of them.
+struct ChunkDecoder<'a> {
+    block_state_lookup: &'a BlockStateLookup<'a>,
+    internal_air_id: InternalId,
+    block_states: HashMap<String, InternalId>,
+    biome_palette: Vec<String>,
+    biome_ids: HashMap<String, u32>,
+    unknown_block_states: BTreeSet<String>,
+    skipped_chunks: usize,
+}
+
+impl<'a> ChunkDecoder<'a> {
+    fn new(block_state_lookup: &'a BlockStateLookup<'a>, internal_air_id: InternalId) -> Self {
+        Self {
+            block_state_lookup,
+            internal_air_id,
+            block_states: HashMap::new(),
+            biome_palette: Vec::new(),
+            biome_ids: HashMap::new(),
+            unknown_block_states: BTreeSet::new(),
+            skipped_chunks: 0,
+        }
+    }
+
+    fn decode_sections(&mut self, chunk: &Nbt, chunk_position: (i32, i32)) -> Vec<AnvilSection> {
+        // Chunks saved before 1.18 store their sections in a `Level` compound
+        let Some(sections) = chunk.find_tag("sections").and_then(|t| t.get_nbt_vec()) else {
+            if chunk.find_tag("Level").is_some() {
+                self.skipped_chunks += 1;
+            } else {
+                warn!("Chunk {chunk_position:?} has no sections, it is left empty");
+            }
+            return Vec::new();
+        };
+
+        sections
+            .iter()
+            .filter_map(|section| {
+                let y = i32::from(section.find_tag("Y").and_then(|t| t.get_byte())?);
+                let block_states = section.find_tag("block_states")?;
+                let (blocks, has_blocks) = self.decode_blocks(block_states);
+                let biomes = section
+                    .find_tag("biomes")
+                    .map(|biomes| self.decode_biomes(biomes))
+                    .unwrap_or_else(|| vec![0; BIOME_CELL_COUNT]);
+                Some(AnvilSection {
+                    y,
+                    has_blocks,
+                    blocks,
+                    biomes,
+                })
+            })
+            .collect()
+    }
+
+    fn decode_blocks(&mut self, block_states: &Nbt) -> (Vec<InternalId>, bool) {
+        let palette: Vec<InternalId> = block_states
+            .find_tag("palette")
+            .and_then(|t| t.get_nbt_vec())
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| self.block_state(entry))
+            .collect();
+        let has_blocks = palette.iter().any(|&id| id != self.internal_air_id);
+
+        // At least 4 bits per entry, entries do not span across longs
+        let bits_per_entry = bits_for(palette.len()).max(4);
+        let blocks = unpack_palette(block_states, &palette, bits_per_entry, SECTION_VOLUME)
+            .unwrap_or_else(|| vec![self.internal_air_id; SECTION_VOLUME]);
+        (blocks, has_blocks)
+    }
+
+    fn decode_biomes(&mut self, biomes: &Nbt) -> Vec<u32> {
+        let palette: Vec<u32> = biomes
+            .find_tag("palette")
+            .and_then(|t| t.get_nbt_vec())
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| self.biome_id(entry.get_string().unwrap_or_default()))
+            .collect();
+
+        let bits_per_entry = bits_for(palette.len());
+        unpack_palette(biomes, &palette, bits_per_entry, BIOME_CELL_COUNT)
+            .unwrap_or_else(|| vec![0; BIOME_CELL_COUNT])
+    }
+
+    fn block_state(&mut self, entry: &Nbt) -> InternalId {
+        let state_string = Schematic::block_state_string(entry);
+        if let Some(&internal_id) = self.block_states.get(&state_string) {
+            return internal_id;
+        }
+        let internal_id = self
+            .block_state_lookup
+            .parse_state_string(&state_string)
+            .unwrap_or_else(|_| {
+                self.unknown_block_states.insert(state_string.clone());
+                self.internal_air_id
+            });
+        self.block_states.insert(state_string, internal_id);
+        internal_id
+    }
+
+    fn biome_id(&mut self, name: String) -> u32 {
+        *self.biome_ids.entry(name).or_insert_with_key(|name| {
+            self.biome_palette.push(name.clone());
+            (self.biome_palette.len() - 1) as u32
+        })
+    }
+
+    fn report_unknown_entries(&self) {
+        if !self.unknown_block_states.is_empty() {
+            let names: Vec<&str> = self
+                .unknown_block_states
+                .iter()
+                .map(String::as_str)
+                .collect();
+            warn!(
+                "Unknown block states in the world were replaced with air: {}",
+                names.join(", ")
+            );
+        }
+        if self.skipped_chunks > 0 {
+            warn!(
+                "{} chunks saved before 1.18 were left empty, open the world in 1.18 or newer to upgrade them",
+                self.skipped_chunks
+            );
+        }
+    }
+}
+
+/// Number of bits needed to store the indices of a palette.
+fn bits_for(palette_len: usize) -> u8 {
+    (usize::BITS - palette_len.saturating_sub(1).leading_zeros()) as u8
+}
+
+/// Maps the packed `data` of a paletted container to the palette values.
+/// A palette with a single entry has no data. Returns `None` when the palette is empty.
+fn unpack_palette<T: Copy>(
+    container: &Nbt,
+    palette: &[T],
+    bits_per_entry: u8,
+    entry_count: usize,
+) -> Option<Vec<T>> {
+    let first = *palette.first()?;
+    if palette.len() == 1 {
+        return Some(vec![first; entry_count]);
+    }
+    let data: Vec<u64> = container
+        .find_tag("data")
+        .and_then(|t| t.get_long_array())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|long| long as u64)
+        .collect();
+    Some(
+        unpack_direct(&data, bits_per_entry, entry_count)
+            .into_iter()
+            .map(|index| palette.get(index as usize).copied().unwrap_or(first))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_blocks::TestBlocks;
+    use std::path::PathBuf;
+
+    fn load(min_chunk: (i32, i32), max_chunk: (i32, i32)) -> Schematic {
+        let blocks = TestBlocks::new();
+        let world_folder = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data/anvil_world");
+        let area = AnvilArea {
+            dimension_folder: String::new(),
+            min_chunk,
+            max_chunk,
+        };
+        load_anvil_area(&world_folder, &area, &blocks.lookup()).unwrap()
+    }
+
+    #[test]
+    fn test_load_anvil_area() {
+        // When
+        let schematic = load((0, 0), (1, 0));
+
+        // Then
+        let block = |x, y, z| schematic.get_block_state_id(Coordinates::new(x, y, z));
+        let dimensions = schematic.get_dimensions();
+        assert_eq!(
+            (dimensions.x(), dimensions.y(), dimensions.z()),
+            (32, 32, 16)
+        );
+        let world_origin = schematic.get_world_origin();
+        assert_eq!(
+            (world_origin.x(), world_origin.y(), world_origin.z()),
+            (0, 0, 0)
+        );
+        // Zlib compressed chunk
+        assert_eq!(block(0, 0, 0), TestBlocks::STONE);
+        assert_eq!(block(1, 0, 0), TestBlocks::AIR);
+        assert_eq!(block(5, 20, 7), TestBlocks::STONE);
+        // LZ4 compressed chunk
+        assert_eq!(block(16, 0, 0), TestBlocks::RED_WOOL);
+        assert_eq!(block(17, 0, 0), TestBlocks::OAK_LOG_X);
+    }
+
+    #[test]
+    fn test_anvil_biomes() {
+        // When
+        let schematic = load((0, 0), (1, 0));
+
+        // Then
+        let biome = |x, y, z| schematic.get_biome(Coordinates::new(x, y, z));
+        assert_eq!(biome(0, 0, 0), Some("minecraft:plains"));
+        assert_eq!(biome(17, 3, 2), Some("minecraft:desert"));
+        assert_eq!(biome(20, 0, 0), Some("minecraft:plains"));
+    }
+
+    #[test]
+    fn test_missing_chunks_are_air() {
+        // When
+        let schematic = load((1, 1), (-1, 0));
+
+        // Then
+        let dimensions = schematic.get_dimensions();
+        assert_eq!((dimensions.x(), dimensions.z()), (48, 32));
+        let world_origin = schematic.get_world_origin();
+        assert_eq!((world_origin.x(), world_origin.z()), (-16, 0));
+        assert_eq!(
+            schematic.get_block_state_id(Coordinates::new(16, 0, 0)),
+            TestBlocks::STONE
+        );
+        assert_eq!(
+            schematic.get_block_state_id(Coordinates::new(16, 0, 16)),
+            TestBlocks::AIR
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use minecraft_protocol::prelude::Coordinates;
+use pico_nbt::prelude::Nbt;
+
+/// A block entity of a schematic, such as a sign, a banner or a chest.
+#[derive(Clone)]
+pub struct BlockEntity {
+    position: Coordinates,
+    /// Type of the block entity, such as `minecraft:sign`.
+    id: String,
+    /// Tags of the block entity, without the ones describing its position and type.
+    data: Vec<Nbt>,
+}
+
+impl BlockEntity {
+    const POSITION_AND_TYPE_TAGS: [&'static str; 7] =
+        ["Pos", "Id", "id", "x", "y", "z", "keepPacked"];
+
+    pub fn new(position: Coordinates, id: String, data: Vec<Nbt>) -> Self {
+        let data = data
+            .into_iter()
+            .filter(|tag| {
+                tag.get_name()
+                    .is_none_or(|name| !Self::POSITION_AND_TYPE_TAGS.contains(&name.as_str()))
+            })
+            .collect();
+        Self { position, id, data }
+    }
+
+    /// Reads a block entity whose type is stored in its `id` tag, next to its data.
+    pub(crate) fn from_compound(position: Coordinates, compound: &Nbt) -> Option<Self> {
+        let id = compound.find_tag("id").and_then(|t| t.get_string())?;
+        Some(Self::new(position, id, compound.get_nbt_vec()?))
+    }
+
+    /// Position of the block entity, relative to the schematic.
+    pub fn position(&self) -> Coordinates {
+        self.position
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn data(&self) -> &[Nbt] {
+        &self.data
+    }
+
+    pub(crate) fn translate(mut self, offset: Coordinates) -> Self {
+        self.position = self.position + offset;
+        self
+    }
+}
@@ -1,6 +1,5 @@
 use crate::pack_direct::pack_direct;
 use crate::palette::Palette;
-use crate::prelude::Schematic;
 use blocks_report::InternalId;
 use minecraft_protocol::prelude::Coordinates;
 use std::collections::HashMap;
@@ -38,9 +37,10 @@ impl ChunkProcessor {
     }
 
     /// Processes a 16x16x16 section using a stack-allocated array for block data.
+    /// `block_at` returns the block at the given world position.
     pub fn process_section(
         &mut self,
-        schematic: &Schematic,
+        block_at: impl Fn(Coordinates) -> InternalId,
         section_position: Coordinates,
     ) -> Result<Palette, ChunkProcessorError> {
         const SECTION_VOLUME: usize = 4096;
@@ -59,7 +59,7 @@ impl ChunkProcessor {
             for z in 0..SECTION_SIZE {
                 for x in 0..SECTION_SIZE {
                     let world_pos = section_origin + Coordinates::new(x, y, z);
-                    let internal_id = schematic.get_block_state_id(world_pos);
+                    let internal_id = block_at(world_pos);
 
                     block_ids[block_index] = internal_id;
                     block_index += 1;
@@ -1,4 +1,4 @@
-use flate2::read::GzDecoder;
+use flate2::read::{GzDecoder, ZlibDecoder};
 use std::fs::File;
 use std::io;
 use std::io::Read;
@@ -14,3 +14,65 @@ pub fn decompress_gz_file(path: &Path) -> io::Result<Vec<u8>> {
 
     Ok(buffer)
 }
+
+pub fn decompress_gz(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    GzDecoder::new(bytes).read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+pub fn decompress_zlib(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    ZlibDecoder::new(bytes).read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Decompresses the block stream written by lz4-java's `LZ4BlockOutputStream`, used by Minecraft
+/// since 1.20.5. Every block starts with a 21 bytes header, a block of size 0 ends the stream.
+/// Checksums are not verified.
+pub fn decompress_lz4_blocks(mut bytes: &[u8]) -> io::Result<Vec<u8>> {
+    const MAGIC: &[u8] = b"LZ4Block";
+    const HEADER_SIZE: usize = 21;
+    const METHOD_RAW: u8 = 0x10;
+    const METHOD_LZ4: u8 = 0x20;
+
+    let invalid_data = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+    let read_length = |header: &[u8], start: usize| {
+        u32::from_le_bytes([
+            header[start],
+            header[start + 1],
+            header[start + 2],
+            header[start + 3],
+        ]) as usize
+    };
+
+    let mut buffer = Vec::new();
+    while !bytes.is_empty() {
+        let header = bytes
+            .get(..HEADER_SIZE)
+            .filter(|header| header.starts_with(MAGIC))
+            .ok_or_else(|| invalid_data("invalid LZ4 block header"))?;
+        let method = header[MAGIC.len()] & 0xF0;
+        let compressed_length = read_length(header, 9);
+        let decompressed_length = read_length(header, 13);
+        if decompressed_length == 0 {
+            break;
+        }
+
+        let block = bytes
+            .get(HEADER_SIZE..HEADER_SIZE + compressed_length)
+            .ok_or_else(|| invalid_data("truncated LZ4 block"))?;
+        match method {
+            METHOD_RAW => buffer.extend_from_slice(block),
+            METHOD_LZ4 => {
+                let decompressed = lz4_flex::block::decompress(block, decompressed_length)
+                    .map_err(|err| invalid_data(&err.to_string()))?;
+                buffer.extend_from_slice(&decompressed);
+            }
+            _ => return Err(invalid_data("unknown LZ4 block compression method")),
+        }
+        bytes = &bytes[HEADER_SIZE + compressed_length..];
+    }
+
+    Ok(buffer)
+}
@@ -0,0 +1,206 @@
+use crate::world::WorldLoadingError;
+use blocks_report::{BlockStateLookup, InternalId};
+use minecraft_protocol::prelude::Coordinates;
+
+const SECTION_SIZE: i32 = 16;
+
+/// Layers of blocks generated around the spawn, such as a superflat floor or a platform.
+pub struct FlatGenerator {
+    /// Block state strings and thicknesses of the layers, from the bottom up.
+    pub layers: Vec<(String, u32)>,
+    /// Y coordinate of the bottom of the lowest layer.
+    pub min_y: i32,
+    /// Horizontal area covered by the layers, as block coordinates (x, z). Both corners are included.
+    pub min_corner: (i32, i32),
+    pub max_corner: (i32, i32),
+}
+
+impl FlatGenerator {
+    /// Layers covering every chunk within the given radius of the chunk at 0,0.
+    pub fn superflat(layers: Vec<(String, u32)>, min_y: i32, radius_in_chunks: u32) -> Self {
+        let radius = radius_in_chunks as i32;
+        Self {
+            layers,
+            min_y,
+            min_corner: (-radius * SECTION_SIZE, -radius * SECTION_SIZE),
+            max_corner: (
+                (radius + 1) * SECTION_SIZE - 1,
+                (radius + 1) * SECTION_SIZE - 1,
+            ),
+        }
+    }
+
+    /// A square platform of one block thick, centered on 0,0.
+    pub fn platform(block: String, size: u32, y: i32) -> Self {
+        let min = -(size as i32) / 2;
+        let max = min + size as i32 - 1;
+        Self {
+            layers: vec![(block, 1)],
+            min_y: y,
+            min_corner: (min, min),
+            max_corner: (max, max),
+        }
+    }
+
+    /// Looks up the block of every layer.
+    pub(crate) fn resolve(
+        &self,
+        block_state_lookup: &BlockStateLookup,
+    ) -> Result<GeneratedLayers, WorldLoadingError> {
+        let mut blocks = Vec::new();
+        for (state_string, thickness) in &self.layers {
+            let internal_id = block_state_lookup
+                .parse_state_string(state_string)
+                .map_err(|_| WorldLoadingError::UnknownGeneratorBlock(state_string.clone()))?;
+            blocks.extend(std::iter::repeat_n(internal_id, *thickness as usize));
+        }
+        Ok(GeneratedLayers {
+            blocks,
+            min: Coordinates::new(self.min_corner.0, self.min_y, self.min_corner.1),
+            max: Coordinates::new(self.max_corner.0, 0, self.max_corner.1),
+        })
+    }
+}
+
+/// Layers of a [`FlatGenerator`], with their blocks looked up.
+pub(crate) struct GeneratedLayers {
+    /// One block per y coordinate, from `min.y()` upwards.
+    blocks: Vec<InternalId>,
+    min: Coordinates,
+    /// Highest x and z coordinates covered, included. The y coordinate is unused.
+    max: Coordinates,
+}
+
+impl GeneratedLayers {
+    /// Returns `None` outside of the layers.
+    pub(crate) fn block_at(&self, position: Coordinates) -> Option<InternalId> {
+        let is_inside = (self.min.x()..=self.max.x()).contains(&position.x())
+            && (self.min.z()..=self.max.z()).contains(&position.z());
+        if !is_inside {
+            return None;
+        }
+        let layer = usize::try_from(position.y() - self.min.y()).ok()?;
+        self.blocks.get(layer).copied()
+    }
+
+    /// Returns the block filling the whole section, if the section is made of a single layer.
+    /// `Some(None)` means the section is outside of the layers.
+    pub(crate) fn uniform_section(
+        &self,
+        section_position: Coordinates,
+    ) -> Option<Option<InternalId>> {
+        let min = section_position * SECTION_SIZE;
+        let max = min + (SECTION_SIZE - 1);
+        let horizontal = |min: i32, max: i32, layers_min: i32, layers_max: i32| {
+            if max < layers_min || min > layers_max {
+                Some(false)
+            } else if min >= layers_min && max <= layers_max {
+                Some(true)
+            } else {
+                None
+            }
+        };
+        let covers_x = horizontal(min.x(), max.x(), self.min.x(), self.max.x());
+        let covers_z = horizontal(min.z(), max.z(), self.min.z(), self.max.z());
+        match (covers_x, covers_z) {
+            (Some(false), _) | (_, Some(false)) => return Some(None),
+            (Some(true), Some(true)) => {}
+            _ => return None,
+        }
+        let bottom = self.block_at(Coordinates::new(min.x(), min.y(), min.z()));
+        let is_uniform = (min.y()..=max.y())
+            .all(|y| self.block_at(Coordinates::new(min.x(), y, min.z())) == bottom);
+        is_uniform.then_some(bottom)
+    }
+
+    /// Lowest corner of the layers and the corner past their highest coordinates.
+    pub(crate) fn bounds(&self) -> Option<(Coordinates, Coordinates)> {
+        if self.blocks.is_empty() || self.min.x() > self.max.x() || self.min.z() > self.max.z() {
+            return None;
+        }
+        let end = Coordinates::new(
+            self.max.x() + 1,
+            self.min.y() + self.blocks.len() as i32,
+            self.max.z() + 1,
+        );
+        Some((self.min, end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generated_layers(generator: &FlatGenerator) -> GeneratedLayers {
+        let blocks = generator
+            .layers
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (_, thickness))| {
+                std::iter::repeat_n(index as InternalId + 1, *thickness as usize)
+            })
+            .collect();
+        GeneratedLayers {
+            blocks,
+            min: Coordinates::new(
+                generator.min_corner.0,
+                generator.min_y,
+                generator.min_corner.1,
+            ),
+            max: Coordinates::new(generator.max_corner.0, 0, generator.max_corner.1),
+        }
+    }
+
+    #[test]
+    fn test_superflat_layers() {
+        // Given
+        let generator = FlatGenerator::superflat(
+            vec![
+                ("minecraft:bedrock".to_string(), 1),
+                ("minecraft:dirt".to_string(), 2),
+            ],
+            -64,
+            1,
+        );
+
+        // When
+        let layers = generated_layers(&generator);
+
+        // Then
+        assert_eq!(layers.block_at(Coordinates::new(-16, -64, 31)), Some(1));
+        assert_eq!(layers.block_at(Coordinates::new(0, -62, 0)), Some(2));
+        assert_eq!(layers.block_at(Coordinates::new(0, -61, 0)), None);
+        assert_eq!(layers.block_at(Coordinates::new(32, -64, 0)), None);
+        let (min, end) = layers.bounds().unwrap();
+        assert_eq!((min.x(), min.y(), min.z()), (-16, -64, -16));
+        assert_eq!((end.x(), end.y(), end.z()), (32, -61, 32));
+    }
+
+    #[test]
+    fn test_uniform_sections() {
+        // Given
+        let generator = FlatGenerator::superflat(vec![("minecraft:stone".to_string(), 32)], 0, 0);
+        let platform = FlatGenerator::platform("minecraft:stone".to_string(), 5, 64);
+
+        // When
+        let layers = generated_layers(&generator);
+        let platform = generated_layers(&platform);
+
+        // Then
+        assert_eq!(
+            layers.uniform_section(Coordinates::new(0, 1, 0)),
+            Some(Some(1))
+        );
+        assert_eq!(
+            layers.uniform_section(Coordinates::new(0, 2, 0)),
+            Some(None)
+        );
+        assert_eq!(
+            layers.uniform_section(Coordinates::new(1, 0, 0)),
+            Some(None)
+        );
+        // The platform covers part of the section only
+        assert_eq!((platform.min.x(), platform.max.x()), (-2, 2));
+        assert_eq!(platform.uniform_section(Coordinates::new(0, 4, 0)), None);
+    }
+}
@@ -1,13 +1,30 @@
+mod anvil_world;
+mod block_entity;
 mod chunk_processor;
 mod decompress;
+mod generator;
+mod light_engine;
+mod litematic_schematic;
+mod mcedit_schematic;
 mod pack_direct;
 mod palette;
+mod placement;
 mod schematic;
+mod structure_schematic;
+#[cfg(test)]
+mod test_blocks;
 mod world;
+mod world_cache;
 
 pub mod prelude {
-    pub use crate::pack_direct::pack_direct;
+    pub use crate::anvil_world::AnvilArea;
+    pub use crate::block_entity::BlockEntity;
+    pub use crate::generator::FlatGenerator;
+    pub use crate::light_engine::SectionLight;
+    pub use crate::pack_direct::{pack_compact, pack_direct, unpack_compact, unpack_direct};
     pub use crate::palette::Palette;
+    pub use crate::placement::{Mirror, Rotation, SchematicPlacement};
     pub use crate::schematic::{Schematic, SchematicError};
-    pub use crate::world::{World, WorldLoadingError};
+    pub use crate::world::{SectionBiomes, World, WorldLoadingError};
+    pub use crate::world_cache::{WorldCache, WorldCacheError, WorldCacheKey};
 }
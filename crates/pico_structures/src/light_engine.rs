@@ -0,0 +1,286 @@
+use blocks_report::{BlockStateLookup, InternalId};
+use minecraft_protocol::prelude::Coordinates;
+use std::collections::VecDeque;
+
+const SECTION_SIZE: usize = 16;
+const MAX_LIGHT_LEVEL: u8 = 15;
+const NIBBLE_ARRAY_SIZE: usize = SECTION_SIZE * SECTION_SIZE * SECTION_SIZE / 2;
+
+/// Sky and block light of a 16x16x16 section, as nibble arrays indexed by `y << 8 | z << 4 | x`.
+#[derive(PartialEq, Eq, Hash)]
+pub struct SectionLight {
+    pub(crate) sky_light: Vec<u8>,
+    pub(crate) block_light: Vec<u8>,
+}
+
+impl SectionLight {
+    pub fn sky_light(&self) -> &[u8] {
+        &self.sky_light
+    }
+
+    pub fn block_light(&self) -> &[u8] {
+        &self.block_light
+    }
+}
+
+/// Computes the light of the whole world once, when it is loaded.
+///
+/// Sky light comes down from the top of each column and from the open sides of the world,
+/// block light spreads out of emitting blocks. Both lose at least one level per block.
+pub struct LightEngine {
+    width: usize,
+    length: usize,
+    sky_light: Vec<u8>,
+    block_light: Vec<u8>,
+}
+
+impl LightEngine {
+    /// Computes the light of the blocks of a whole number of sections.
+    /// `origin` is the world position of the lowest corner of the first section.
+    pub fn from_blocks(
+        block_at: impl Fn(Coordinates) -> InternalId,
+        block_state_lookup: &BlockStateLookup,
+        origin: Coordinates,
+        size_in_chunks: Coordinates,
+    ) -> Self {
+        let width = size_in_chunks.x() as usize * SECTION_SIZE;
+        let height = size_in_chunks.y() as usize * SECTION_SIZE;
+        let length = size_in_chunks.z() as usize * SECTION_SIZE;
+
+        let mut opacity = vec![0; width * height * length];
+        let mut emitted_light = vec![0; width * height * length];
+        for y in 0..height {
+            for z in 0..length {
+                for x in 0..width {
+                    let position = origin + Coordinates::new(x as i32, y as i32, z as i32);
+                    let properties = block_state_lookup.properties(block_at(position));
+                    let index = (y * length + z) * width + x;
+                    opacity[index] = properties.light_opacity();
+                    emitted_light[index] = properties.luminance();
+                }
+            }
+        }
+
+        Self::new(width, height, length, &opacity, emitted_light)
+    }
+
+    fn new(
+        width: usize,
+        height: usize,
+        length: usize,
+        opacity: &[u8],
+        emitted_light: Vec<u8>,
+    ) -> Self {
+        let volume = Volume {
+            width,
+            height,
+            length,
+            opacity,
+        };
+        Self {
+            width,
+            length,
+            sky_light: volume.sky_light(),
+            block_light: volume.block_light(emitted_light),
+        }
+    }
+
+    /// Returns the light of the section at the given section coordinates, counted from the first section.
+    pub fn section_light(&self, section_position: Coordinates) -> SectionLight {
+        let mut sky_light = vec![0; NIBBLE_ARRAY_SIZE];
+        let mut block_light = vec![0; NIBBLE_ARRAY_SIZE];
+        let origin_x = section_position.x() as usize * SECTION_SIZE;
+        let origin_y = section_position.y() as usize * SECTION_SIZE;
+        let origin_z = section_position.z() as usize * SECTION_SIZE;
+
+        for y in 0..SECTION_SIZE {
+            for z in 0..SECTION_SIZE {
+                for x in 0..SECTION_SIZE {
+                    let index =
+                        ((origin_y + y) * self.length + origin_z + z) * self.width + origin_x + x;
+                    let nibble_index = (y << 8) | (z << 4) | x;
+                    let shift = (nibble_index & 1) * 4;
+                    sky_light[nibble_index / 2] |= self.sky_light[index] << shift;
+                    block_light[nibble_index / 2] |= self.block_light[index] << shift;
+                }
+            }
+        }
+
+        SectionLight {
+            sky_light,
+            block_light,
+        }
+    }
+}
+
+/// The blocks of the world, indexed by `(y * length + z) * width + x`.
+struct Volume<'a> {
+    width: usize,
+    height: usize,
+    length: usize,
+    opacity: &'a [u8],
+}
+
+impl Volume<'_> {
+    const fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        (y * self.length + z) * self.width + x
+    }
+
+    /// Lights every column from the top, then spreads the light sideways and below overhangs.
+    fn sky_light(&self) -> Vec<u8> {
+        let mut sky_light = vec![0; self.opacity.len()];
+        for z in 0..self.length {
+            for x in 0..self.width {
+                let mut level = MAX_LIGHT_LEVEL;
+                for y in (0..self.height).rev() {
+                    let index = self.index(x, y, z);
+                    level = level.saturating_sub(self.opacity[index]);
+                    sky_light[index] = level;
+                }
+            }
+        }
+
+        // The world is surrounded by sky on all sides but below
+        for y in 0..self.height {
+            for z in 0..self.length {
+                for x in 0..self.width {
+                    let is_on_side =
+                        x == 0 || z == 0 || x == self.width - 1 || z == self.length - 1;
+                    if is_on_side {
+                        let index = self.index(x, y, z);
+                        let level = MAX_LIGHT_LEVEL.saturating_sub(self.opacity[index].max(1));
+                        sky_light[index] = sky_light[index].max(level);
+                    }
+                }
+            }
+        }
+
+        let queue = (0..sky_light.len())
+            .filter(|&index| {
+                let level = sky_light[index];
+                level > 1
+                    && self
+                        .neighbours(index)
+                        .any(|neighbour| sky_light[neighbour] < level - 1)
+            })
+            .collect();
+        self.flood_fill(&mut sky_light, queue);
+        sky_light
+    }
+
+    /// Spreads the light of emitting blocks.
+    fn block_light(&self, emitted_light: Vec<u8>) -> Vec<u8> {
+        let mut block_light = emitted_light;
+        let queue = (0..block_light.len())
+            .filter(|&index| block_light[index] > 0)
+            .collect();
+        self.flood_fill(&mut block_light, queue);
+        block_light
+    }
+
+    /// Spreads the light from the queued blocks to their neighbours.
+    fn flood_fill(&self, light: &mut [u8], mut queue: VecDeque<usize>) {
+        while let Some(index) = queue.pop_front() {
+            let level = light[index];
+            for neighbour in self.neighbours(index) {
+                let attenuation = self.opacity[neighbour].max(1);
+                if level > attenuation && light[neighbour] < level - attenuation {
+                    light[neighbour] = level - attenuation;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+    }
+
+    /// Returns the indices of the blocks sharing a face with the given block.
+    fn neighbours(&self, index: usize) -> impl Iterator<Item = usize> + use<> {
+        let (width, height, length) = (self.width, self.height, self.length);
+        let x = index % width;
+        let z = (index / width) % length;
+        let y = index / (width * length);
+        let layer = width * length;
+
+        [
+            (x > 0).then(|| index - 1),
+            (x + 1 < width).then(|| index + 1),
+            (z > 0).then(|| index - width),
+            (z + 1 < length).then(|| index + width),
+            (y > 0).then(|| index - layer),
+            (y + 1 < height).then(|| index + layer),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: usize = 16;
+
+    fn index(x: usize, y: usize, z: usize) -> usize {
+        (y * SIZE + z) * SIZE + x
+    }
+
+    fn engine(blocks: &[((usize, usize, usize), u8, u8)]) -> LightEngine {
+        let mut opacity = vec![0; SIZE * SIZE * SIZE];
+        let mut emitted_light = vec![0; SIZE * SIZE * SIZE];
+        for &((x, y, z), block_opacity, block_luminance) in blocks {
+            opacity[index(x, y, z)] = block_opacity;
+            emitted_light[index(x, y, z)] = block_luminance;
+        }
+        LightEngine::new(SIZE, SIZE, SIZE, &opacity, emitted_light)
+    }
+
+    #[test]
+    fn test_open_sky_is_fully_lit() {
+        // Given
+        let engine = engine(&[]);
+
+        // When
+        let light = engine.section_light(Coordinates::new(0, 0, 0));
+
+        // Then
+        assert!(light.sky_light().iter().all(|&level| level == 0xFF));
+        assert!(light.block_light().iter().all(|&level| level == 0));
+    }
+
+    #[test]
+    fn test_sky_light_spreads_below_a_roof() {
+        // Given
+        let roof: Vec<_> = (0..SIZE)
+            .flat_map(|x| (0..SIZE).map(move |z| ((x, 10, z), 15, 0)))
+            .collect();
+
+        // When
+        let engine = engine(&roof);
+
+        // Then
+        assert_eq!(engine.sky_light[index(0, 5, 0)], 14);
+        assert_eq!(engine.sky_light[index(1, 5, 1)], 13);
+        assert_eq!(engine.sky_light[index(8, 5, 8)], 7);
+        assert_eq!(engine.sky_light[index(8, 10, 8)], 0);
+        assert_eq!(engine.sky_light[index(8, 11, 8)], 15);
+    }
+
+    #[test]
+    fn test_block_light_decreases_with_distance() {
+        // Given
+        let engine = engine(&[((8, 8, 8), 15, 15), ((10, 8, 8), 15, 0)]);
+
+        // When
+        let light = engine.section_light(Coordinates::new(0, 0, 0));
+
+        // Then
+        let nibble = |x: usize, y: usize, z: usize| {
+            let nibble_index = (y << 8) | (z << 4) | x;
+            (light.block_light()[nibble_index / 2] >> ((nibble_index & 1) * 4)) & 0xF
+        };
+        assert_eq!(nibble(8, 8, 8), 15);
+        assert_eq!(nibble(9, 8, 8), 14);
+        assert_eq!(nibble(8, 12, 8), 11);
+        assert_eq!(nibble(10, 8, 8), 0);
+        assert_eq!(nibble(11, 8, 8), 10);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::block_entity::BlockEntity;
+use crate::pack_direct::unpack_compact;
+use crate::schematic::{Schematic, SchematicError};
+use blocks_report::{BlockStateLookup, InternalId};
+use minecraft_protocol::prelude::Coordinates;
+use pico_nbt::prelude::Nbt;
+use tracing::warn;
+
+/// Loads a `.litematic` file written by Litematica.
+///
+/// A litematic holds several regions, each with its own palette and bit packed blocks.
+/// All regions are merged into a single grid, keeping their position relative to each other.
+pub fn load_litematic_schematic(
+    root: &Nbt,
+    block_state_lookup: &BlockStateLookup,
+) -> Result<Schematic, SchematicError> {
+    let internal_air_id = Schematic::internal_air_id(block_state_lookup)?;
+    let regions_nbt = Schematic::get_tag_as(root, "Regions", |t| t.get_nbt_vec())?;
+    let regions = regions_nbt
+        .iter()
+        .map(|region| Region::parse(region, block_state_lookup, internal_air_id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let Some(min) = regions.iter().map(|region| region.min).reduce(min_corner) else {
+        return Ok(Schematic::default());
+    };
+    let end = regions
+        .iter()
+        .map(Region::end)
+        .reduce(max_corner)
+        .unwrap_or(min);
+    let dimensions = end - min;
+
+    let width = dimensions.x() as usize;
+    let length = dimensions.z() as usize;
+    let mut block_data = vec![internal_air_id; width * length * dimensions.y() as usize];
+    let mut block_entities = Vec::new();
+    for region in regions {
+        let origin = region.min - min;
+        for (index, &internal_id) in region.blocks.iter().enumerate() {
+            if internal_id == internal_air_id {
+                continue;
+            }
+            let position = origin + region.local_position(index);
+            let grid_index = (position.y() as usize * length + position.z() as usize) * width
+                + position.x() as usize;
+            block_data[grid_index] = internal_id;
+        }
+        block_entities.extend(
+            region
+                .block_entities
+                .into_iter()
+                .map(|block_entity| block_entity.translate(origin)),
+        );
+    }
+
+    Ok(
+        Schematic::from_blocks(block_data, dimensions, min, internal_air_id)
+            .with_block_entities(block_entities),
+    )
+}
+
+struct Region {
+    /// Corner of the region with the lowest coordinates, relative to the litematic origin.
+    min: Coordinates,
+    size: Coordinates,
+    /// Blocks indexed by `y * length * width + z * width + x`, from the minimum corner.
+    blocks: Vec<InternalId>,
+    /// Block entities positioned from the minimum corner.
+    block_entities: Vec<BlockEntity>,
+}
+
+impl Region {
+    fn parse(
+        nbt: &Nbt,
+        block_state_lookup: &BlockStateLookup,
+        internal_air_id: InternalId,
+    ) -> Result<Self, SchematicError> {
+        let position = read_coordinates(nbt, "Position")?;
+        let signed_size = read_coordinates(nbt, "Size")?;
+
+        // A negative size extends the region towards the negative coordinates
+        let axis = |position: i32, size: i32| {
+            if size < 0 {
+                (position + size + 1, -size)
+            } else {
+                (position, size)
+            }
+        };
+        let (min_x, width) = axis(position.x(), signed_size.x());
+        let (min_y, height) = axis(position.y(), signed_size.y());
+        let (min_z, length) = axis(position.z(), signed_size.z());
+
+        let palette: Vec<InternalId> =
+            Schematic::get_tag_as(nbt, "BlockStatePalette", |t| t.get_nbt_vec())?
+                .iter()
+                .map(|entry| {
+                    let state_string = Schematic::block_state_string(entry);
+                    block_state_lookup
+                .parse_state_string(&state_string)
+                .unwrap_or_else(|_| {
+                    warn!(
+                        "Unknown block state {state_string} in litematic palette, replaced with air"
+                    );
+                    internal_air_id
+                })
+                })
+                .collect();
+
+        // At least 2 bits per entry, entries span across longs
+        let bits_per_entry = (usize::BITS - palette.len().saturating_sub(1).leading_zeros()).max(2);
+        let block_states: Vec<u64> =
+            Schematic::get_tag_as(nbt, "BlockStates", |t| t.get_long_array())?
+                .into_iter()
+                .map(|long| long as u64)
+                .collect();
+        let volume = (width * height * length) as usize;
+        let blocks = unpack_compact(&block_states, bits_per_entry as u8, volume)
+            .into_iter()
+            .map(|palette_id| {
+                palette
+                    .get(palette_id as usize)
+                    .copied()
+                    .unwrap_or(internal_air_id)
+            })
+            .collect();
+
+        let block_entities = nbt
+            .find_tag("TileEntities")
+            .and_then(|t| t.get_nbt_vec())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|tile_entity| {
+                let axis = |axis: &str| tile_entity.find_tag(axis).and_then(|t| t.get_int());
+                let position = Coordinates::new(axis("x")?, axis("y")?, axis("z")?);
+                BlockEntity::from_compound(position, tile_entity)
+            })
+            .collect();
+
+        Ok(Self {
+            min: Coordinates::new(min_x, min_y, min_z),
+            size: Coordinates::new(width, height, length),
+            blocks,
+            block_entities,
+        })
+    }
+
+    /// Corner past the highest coordinates of the region.
+    fn end(&self) -> Coordinates {
+        self.min + self.size
+    }
+
+    fn local_position(&self, index: usize) -> Coordinates {
+        let index = index as i32;
+        let layer = self.size.x() * self.size.z();
+        Coordinates::new(
+            index % self.size.x(),
+            index / layer,
+            (index % layer) / self.size.x(),
+        )
+    }
+}
+
+fn read_coordinates(nbt: &Nbt, tag_name: &str) -> Result<Coordinates, SchematicError> {
+    let compound = nbt
+        .find_tag(tag_name)
+        .ok_or_else(|| SchematicError::MissingTag(tag_name.to_string()))?;
+    let axis = |axis: &str| {
+        compound
+            .find_tag(axis)
+            .and_then(|t| t.get_int())
+            .ok_or_else(|| SchematicError::MissingTag(format!("{tag_name}.{axis}")))
+    };
+    Ok(Coordinates::new(axis("x")?, axis("y")?, axis("z")?))
+}
+
+fn min_corner(a: Coordinates, b: Coordinates) -> Coordinates {
+    Coordinates::new(a.x().min(b.x()), a.y().min(b.y()), a.z().min(b.z()))
+}
+
+fn max_corner(a: Coordinates, b: Coordinates) -> Coordinates {
+    Coordinates::new(a.x().max(b.x()), a.y().max(b.y()), a.z().max(b.z()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_blocks::TestBlocks;
+    use minecraft_protocol::prelude::Coordinates;
+
+    #[test]
+    fn test_regions_are_merged() {
+        // Given
+        let blocks = TestBlocks::new();
+
+        // When
+        let schematic = blocks.load("regions.litematic").unwrap();
+
+        // Then
+        let block = |x, y, z| schematic.get_block_state_id(Coordinates::new(x, y, z));
+        let dimensions = schematic.get_dimensions();
+        assert_eq!((dimensions.x(), dimensions.y(), dimensions.z()), (4, 2, 2));
+        assert_eq!(block(0, 0, 0), TestBlocks::STONE);
+        assert_eq!(block(1, 0, 0), TestBlocks::OAK_LOG_X);
+        assert_eq!(block(2, 0, 0), TestBlocks::AIR);
+    }
+
+    #[test]
+    fn test_negative_region_size() {
+        // Given
+        let blocks = TestBlocks::new();
+
+        // When
+        let schematic = blocks.load("regions.litematic").unwrap();
+
+        // Then
+        let block = |x, y, z| schematic.get_block_state_id(Coordinates::new(x, y, z));
+        assert_eq!(block(2, 1, 1), TestBlocks::RED_WOOL);
+        assert_eq!(block(3, 1, 1), TestBlocks::STONE);
+    }
+}
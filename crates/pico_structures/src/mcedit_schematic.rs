@@ -0,0 +1,185 @@
+use crate::schematic::{Schematic, SchematicError};
+use blocks_report::{BlockStateLookup, InternalId, LegacyBlockId};
+use minecraft_protocol::prelude::Coordinates;
+use pico_nbt::prelude::Nbt;
+use std::collections::{BTreeMap, HashMap};
+use tracing::warn;
+
+/// Loads a pre-1.13 `.schematic` file written by MCEdit, Schematica or WorldEdit 6.
+///
+/// Blocks are stored as numeric IDs and metadata, they are translated to flattened block states
+/// using the bundled legacy block names. Blocks that cannot be translated are replaced with air.
+pub fn load_mcedit_schematic(
+    root: &Nbt,
+    block_state_lookup: &BlockStateLookup,
+) -> Result<Schematic, SchematicError> {
+    let materials = Schematic::get_tag_as(root, "Materials", |t| t.get_string())?;
+    if materials != "Alpha" {
+        return Err(SchematicError::UnsupportedMaterials(materials));
+    }
+
+    let dimensions = Schematic::extract_dimensions(root)?;
+    let block_count = (dimensions.x() * dimensions.y() * dimensions.z()) as usize;
+    let blocks = Schematic::get_tag_as(root, "Blocks", |t| t.get_byte_array())?;
+    let data = Schematic::get_tag_as(root, "Data", |t| t.get_byte_array())?;
+    // Upper 4 bits of the block IDs above 255, two blocks per byte
+    let add_blocks = root
+        .find_tag("AddBlocks")
+        .and_then(|t| t.get_byte_array())
+        .unwrap_or_default();
+    if blocks.len() < block_count || data.len() < block_count {
+        warn!("Schematic Blocks or Data is smaller than expected dimensions. Truncating.");
+    }
+
+    let internal_air_id = Schematic::internal_air_id(block_state_lookup)?;
+    let mut translator = LegacyBlockTranslator::new(block_state_lookup, internal_air_id);
+    let block_data = (0..block_count)
+        .map(|index| {
+            let Some(&block) = blocks.get(index) else {
+                return internal_air_id;
+            };
+            let add = add_blocks.get(index >> 1).map_or(0, |&add| {
+                let shift = if index & 1 == 0 { 4 } else { 0 };
+                (add as u8 >> shift) & 0x0F
+            });
+            let block_id = u16::from(block as u8) | (u16::from(add) << 8);
+            let metadata = data.get(index).map_or(0, |&meta| meta as u8 & 0x0F);
+            translator.translate(block_id, metadata)
+        })
+        .collect();
+    translator.report_untranslated_blocks();
+
+    Ok(Schematic::from_blocks(
+        block_data,
+        dimensions,
+        extract_offset(root),
+        internal_air_id,
+    ))
+}
+
+/// WorldEdit stores the position of the schematic relative to the player who copied it.
+fn extract_offset(root: &Nbt) -> Coordinates {
+    let offset = |tag_name: &str| {
+        root.find_tag(tag_name)
+            .and_then(|t| t.get_int())
+            .unwrap_or_default()
+    };
+    Coordinates::new(
+        offset("WEOffsetX"),
+        offset("WEOffsetY"),
+        offset("WEOffsetZ"),
+    )
+}
+
+struct LegacyBlockTranslator<'a> {
+    block_state_lookup: &'a BlockStateLookup<'a>,
+    internal_air_id: InternalId,
+    translated_blocks: HashMap<(u16, u8), InternalId>,
+    /// Number of blocks replaced with air, by legacy block ID and metadata
+    untranslated_blocks: BTreeMap<(u16, u8), usize>,
+}
+
+impl<'a> LegacyBlockTranslator<'a> {
+    fn new(block_state_lookup: &'a BlockStateLookup<'a>, internal_air_id: InternalId) -> Self {
+        Self {
+            block_state_lookup,
+            internal_air_id,
+            translated_blocks: HashMap::new(),
+            untranslated_blocks: BTreeMap::new(),
+        }
+    }
+
+    fn translate(&mut self, block_id: u16, metadata: u8) -> InternalId {
+        let internal_id = *self
+            .translated_blocks
+            .entry((block_id, metadata))
+            .or_insert_with(|| {
+                Self::lookup(self.block_state_lookup, block_id, metadata)
+                    .unwrap_or(self.internal_air_id)
+            });
+
+        let is_untranslated = internal_id == self.internal_air_id && block_id != 0;
+        if is_untranslated {
+            *self
+                .untranslated_blocks
+                .entry((block_id, metadata))
+                .or_default() += 1;
+        }
+        internal_id
+    }
+
+    fn lookup(
+        block_state_lookup: &BlockStateLookup,
+        block_id: u16,
+        metadata: u8,
+    ) -> Option<InternalId> {
+        // Block IDs above 255 only exist in modded schematics
+        if block_id > 0xFF {
+            return None;
+        }
+        let legacy_id: LegacyBlockId = (block_id << 4) | u16::from(metadata);
+        let state_string = block_state_lookup.legacy_state_string(legacy_id)?;
+        block_state_lookup.parse_state_string(state_string).ok()
+    }
+
+    fn report_untranslated_blocks(&self) {
+        if self.untranslated_blocks.is_empty() {
+            return;
+        }
+        let block_count: usize = self.untranslated_blocks.values().sum();
+        let summary: Vec<String> = self
+            .untranslated_blocks
+            .iter()
+            .map(|((block_id, metadata), count)| format!("{block_id}:{metadata} (x{count})"))
+            .collect();
+        warn!(
+            "{block_count} blocks of the schematic could not be translated and were replaced with air: {}",
+            summary.join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_blocks::TestBlocks;
+    use minecraft_protocol::prelude::Coordinates;
+
+    #[test]
+    fn test_load_mcedit_schematic() {
+        // Given
+        let blocks = TestBlocks::new();
+
+        // When
+        let schematic = blocks.load("mcedit.schematic").unwrap();
+
+        // Then
+        let block = |x, y, z| schematic.get_block_state_id(Coordinates::new(x, y, z));
+        let offset = schematic.get_offset();
+        assert_eq!((offset.x(), offset.y(), offset.z()), (-1, 0, 2));
+        assert_eq!(block(0, 0, 0), TestBlocks::STONE);
+        assert_eq!(block(1, 0, 0), TestBlocks::OAK_LOG_X);
+        assert_eq!(block(0, 0, 1), TestBlocks::RED_WOOL);
+        // Unknown metadata falls back to the first state of the block
+        assert_eq!(block(1, 0, 1), TestBlocks::STONE);
+    }
+
+    #[test]
+    fn test_untranslated_blocks_become_air() {
+        // Given
+        let blocks = TestBlocks::new();
+
+        // When
+        let schematic = blocks.load("mcedit.schematic").unwrap();
+
+        // Then
+        // Block ID 300 from AddBlocks and unknown block ID 250
+        assert_eq!(
+            schematic.get_block_state_id(Coordinates::new(0, 1, 0)),
+            TestBlocks::AIR
+        );
+        assert_eq!(
+            schematic.get_block_state_id(Coordinates::new(1, 1, 0)),
+            TestBlocks::AIR
+        );
+    }
+}
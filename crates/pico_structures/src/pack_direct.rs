@@ -33,9 +33,76 @@ pub fn pack_direct(mut entries_iter: impl Iterator<Item = u32>, bits_per_entry:
     packed_data
 }
 
+/// Packs entries the way versions prior to 1.16 expect them:
+/// entries are stored back to back and can span across two longs.
+pub fn pack_compact(entries_iter: impl Iterator<Item = u32>, bits_per_entry: u8) -> Vec<u64> {
+    assert!(
+        bits_per_entry > 0 && bits_per_entry <= 32,
+        "bits_per_entry must be between 1 and 32"
+    );
+    let bpe = bits_per_entry as usize;
+    let mask = (1u64 << bits_per_entry) - 1;
+
+    let mut packed_data = Vec::with_capacity(4096 * bpe / 64);
+
+    for (index, id) in entries_iter.enumerate() {
+        let value = (id as u64) & mask;
+        let bit_index = index * bpe;
+        let long_index = bit_index / 64;
+        let offset = bit_index % 64;
+
+        if long_index >= packed_data.len() {
+            packed_data.push(0);
+        }
+        packed_data[long_index] |= value << offset;
+
+        if offset + bpe > 64 {
+            packed_data.push(value >> (64 - offset));
+        }
+    }
+
+    packed_data
+}
+
+/// Reads back the entries packed by [`pack_direct`].
+pub fn unpack_direct(packed_data: &[u64], bits_per_entry: u8, entry_count: usize) -> Vec<u32> {
+    let bpe = bits_per_entry as usize;
+    let epl = 64 / bpe;
+    let mask = (1u64 << bits_per_entry) - 1;
+
+    (0..entry_count)
+        .map(|index| {
+            let word = packed_data.get(index / epl).copied().unwrap_or_default();
+            let shift = (index % epl) * bpe;
+            ((word >> shift) & mask) as u32
+        })
+        .collect()
+}
+
+/// Reads back the entries packed by [`pack_compact`].
+pub fn unpack_compact(packed_data: &[u64], bits_per_entry: u8, entry_count: usize) -> Vec<u32> {
+    let bpe = bits_per_entry as usize;
+    let mask = (1u64 << bits_per_entry) - 1;
+    let word = |long_index: usize| packed_data.get(long_index).copied().unwrap_or_default();
+
+    (0..entry_count)
+        .map(|index| {
+            let bit_index = index * bpe;
+            let long_index = bit_index / 64;
+            let offset = bit_index % 64;
+
+            let mut value = word(long_index) >> offset;
+            if offset + bpe > 64 {
+                value |= word(long_index + 1) << (64 - offset);
+            }
+            (value & mask) as u32
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::pack_direct::pack_direct;
+    use crate::pack_direct::{pack_compact, pack_direct, unpack_compact, unpack_direct};
 
     #[test]
     fn should_pack_five_bytes() {
@@ -52,4 +119,45 @@ mod tests {
         // Then
         assert_eq!(expected_longs, result);
     }
+
+    #[test]
+    fn should_pack_entries_across_longs() {
+        // Given
+        let entries: Vec<u32> = vec![
+            1, 2, 2, 3, 4, 4, 5, 6, 6, 4, 8, 0, 7, 4, 3, 13, 15, 16, 9, 14, 10, 12, 0, 2,
+        ];
+        let expected_longs = vec![0x7020863148418841u64, 0x001018A7260F68C8u64];
+
+        // When
+        let result = pack_compact(entries.into_iter(), 5);
+
+        // Then
+        assert_eq!(expected_longs, result);
+    }
+
+    #[test]
+    fn should_unpack_packed_entries() {
+        // Given
+        let entries: Vec<u32> = (0..4096).map(|i| i % 23).collect();
+        let packed = pack_direct(entries.iter().copied(), 5);
+
+        // When
+        let result = unpack_direct(&packed, 5, entries.len());
+
+        // Then
+        assert_eq!(entries, result);
+    }
+
+    #[test]
+    fn should_unpack_entries_across_longs() {
+        // Given
+        let entries: Vec<u32> = (0..4096).map(|i| i % 23).collect();
+        let packed = pack_compact(entries.iter().copied(), 5);
+
+        // When
+        let result = unpack_compact(&packed, 5, entries.len());
+
+        // Then
+        assert_eq!(entries, result);
+    }
 }
@@ -1,5 +1,6 @@
 use blocks_report::InternalId;
 
+#[derive(PartialEq, Eq, Hash)]
 pub enum Palette {
     Single {
         internal_id: InternalId, // Must be remapped before sending
@@ -34,4 +35,17 @@ impl Palette {
     pub fn direct(internal_data: Vec<InternalId>) -> Self {
         Self::Direct { internal_data }
     }
+
+    /// Bytes allocated on the heap by the palette.
+    pub fn heap_size(&self) -> usize {
+        match self {
+            Self::Single { .. } => 0,
+            Self::Paletted {
+                internal_palette,
+                packed_data,
+                ..
+            } => size_of_val(internal_palette.as_slice()) + size_of_val(packed_data.as_slice()),
+            Self::Direct { internal_data } => size_of_val(internal_data.as_slice()),
+        }
+    }
 }
@@ -0,0 +1,549 @@
+use crate::block_entity::BlockEntity;
+use crate::schematic::{BiomeResolution, Schematic, SchematicError};
+use blocks_report::{BlockStateLookup, InternalId};
+use minecraft_protocol::prelude::Coordinates;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+const HORIZONTAL_DIRECTIONS: [&str; 4] = ["north", "east", "south", "west"];
+
+/// Rotation of a placed schematic around the vertical axis, clockwise when seen from above.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    Counterclockwise90,
+}
+
+impl Rotation {
+    const fn quarter_turns(self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Clockwise90 => 1,
+            Self::Clockwise180 => 2,
+            Self::Counterclockwise90 => 3,
+        }
+    }
+}
+
+/// Mirroring of a placed schematic, applied before the rotation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mirror {
+    #[default]
+    None,
+    /// Flips the x axis, east becomes west.
+    X,
+    /// Flips the z axis, north becomes south.
+    Z,
+}
+
+/// A schematic file placed in the world.
+pub struct SchematicPlacement {
+    pub path: PathBuf,
+    /// World position of the minimum corner of the schematic, once rotated and mirrored.
+    pub origin: Coordinates,
+    pub rotation: Rotation,
+    pub mirror: Mirror,
+    /// Palette used by structures with several palettes.
+    pub palette: usize,
+}
+
+impl SchematicPlacement {
+    /// Places a schematic file with its minimum corner at world coordinates 0,0,0.
+    pub fn at_world_origin(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            origin: Coordinates::default(),
+            rotation: Rotation::default(),
+            mirror: Mirror::default(),
+            palette: 0,
+        }
+    }
+}
+
+/// Composes the placed schematics into a single schematic whose offset is its minimum corner in the world.
+///
+/// Later placements override the blocks of earlier ones, except with air.
+pub(crate) fn compose_placements(
+    placed_schematics: &[(Schematic, &SchematicPlacement)],
+    block_state_lookup: &BlockStateLookup,
+) -> Result<Schematic, SchematicError> {
+    let internal_air_id = Schematic::internal_air_id(block_state_lookup)?;
+    let transforms: Vec<Transform> = placed_schematics
+        .iter()
+        .map(|(schematic, placement)| Transform::new(schematic.get_dimensions(), placement))
+        .collect();
+    let Some((min, end)) = transforms
+        .iter()
+        .map(|transform| (transform.origin, transform.end()))
+        .reduce(|(a_min, a_end), (b_min, b_end)| {
+            (
+                Coordinates::new(
+                    a_min.x().min(b_min.x()),
+                    a_min.y().min(b_min.y()),
+                    a_min.z().min(b_min.z()),
+                ),
+                Coordinates::new(
+                    a_end.x().max(b_end.x()),
+                    a_end.y().max(b_end.y()),
+                    a_end.z().max(b_end.z()),
+                ),
+            )
+        })
+    else {
+        return Ok(Schematic::default());
+    };
+
+    let mut composition = Composition::new(min, end - min, internal_air_id);
+    for ((schematic, _), transform) in placed_schematics.iter().zip(&transforms) {
+        composition.place(schematic, transform, block_state_lookup);
+    }
+    Ok(composition.into_schematic())
+}
+
+/// Blocks of the placed schematics, merged one after the other.
+struct Composition {
+    /// World position of the minimum corner.
+    min: Coordinates,
+    dimensions: Coordinates,
+    internal_air_id: InternalId,
+    block_data: Vec<InternalId>,
+    block_entities: BTreeMap<(i32, i32, i32), BlockEntity>,
+    biome_palette: Vec<String>,
+    biome_ids: HashMap<String, u32>,
+    /// One biome per 4×4×4 cell, 0 being the empty name of cells without biome.
+    biome_data: Vec<u32>,
+}
+
+impl Composition {
+    fn new(min: Coordinates, dimensions: Coordinates, internal_air_id: InternalId) -> Self {
+        let volume = (dimensions.x() * dimensions.y() * dimensions.z()) as usize;
+        let cells = (dimensions + 3) / 4;
+        Self {
+            min,
+            dimensions,
+            internal_air_id,
+            block_data: vec![internal_air_id; volume],
+            block_entities: BTreeMap::new(),
+            biome_palette: vec![String::new()],
+            biome_ids: HashMap::new(),
+            biome_data: vec![0; (cells.x() * cells.y() * cells.z()) as usize],
+        }
+    }
+
+    fn place(
+        &mut self,
+        schematic: &Schematic,
+        transform: &Transform,
+        block_state_lookup: &BlockStateLookup,
+    ) {
+        let mut transformed_states: HashMap<InternalId, InternalId> = HashMap::new();
+        let source_dimensions = schematic.get_dimensions();
+        for y in 0..source_dimensions.y() {
+            for z in 0..source_dimensions.z() {
+                for x in 0..source_dimensions.x() {
+                    let source = Coordinates::new(x, y, z);
+                    let internal_id = schematic.get_block_state_id(source);
+                    let position = transform.position(source) - self.min;
+                    let Some(index) = self.index(position) else {
+                        continue;
+                    };
+                    self.place_biome(schematic, source, position);
+
+                    if internal_id == self.internal_air_id {
+                        continue;
+                    }
+                    self.block_data[index] = *transformed_states
+                        .entry(internal_id)
+                        .or_insert_with(|| transform.block_state(internal_id, block_state_lookup));
+                    if !self.block_entities.is_empty() {
+                        self.block_entities
+                            .remove(&(position.x(), position.y(), position.z()));
+                    }
+                }
+            }
+        }
+
+        for block_entity in schematic.get_block_entities() {
+            let position = transform.position(block_entity.position()) - self.min;
+            if self.index(position).is_some() {
+                let block_entity = BlockEntity::new(
+                    position,
+                    block_entity.id().to_string(),
+                    block_entity.data().to_vec(),
+                );
+                self.block_entities
+                    .insert((position.x(), position.y(), position.z()), block_entity);
+            }
+        }
+    }
+
+    /// Biomes are sampled at the corner of every cell, relative to the minimum corner.
+    fn place_biome(&mut self, schematic: &Schematic, source: Coordinates, position: Coordinates) {
+        if position.x() % 4 != 0 || position.y() % 4 != 0 || position.z() % 4 != 0 {
+            return;
+        }
+        let Some(biome) = schematic.get_biome(source) else {
+            return;
+        };
+        let biome_id = match self.biome_ids.get(biome) {
+            Some(&biome_id) => biome_id,
+            None => {
+                self.biome_palette.push(biome.to_string());
+                let biome_id = (self.biome_palette.len() - 1) as u32;
+                self.biome_ids.insert(biome.to_string(), biome_id);
+                biome_id
+            }
+        };
+        let cells = (self.dimensions + 3) / 4;
+        let cell = position / 4;
+        self.biome_data[((cell.y() * cells.z() + cell.z()) * cells.x() + cell.x()) as usize] =
+            biome_id;
+    }
+
+    fn index(&self, position: Coordinates) -> Option<usize> {
+        let is_inside = (0..self.dimensions.x()).contains(&position.x())
+            && (0..self.dimensions.y()).contains(&position.y())
+            && (0..self.dimensions.z()).contains(&position.z());
+        is_inside.then(|| {
+            ((position.y() * self.dimensions.z() + position.z()) * self.dimensions.x()
+                + position.x()) as usize
+        })
+    }
+
+    fn into_schematic(self) -> Schematic {
+        let schematic = Schematic::from_blocks(
+            self.block_data,
+            self.dimensions,
+            Coordinates::default(),
+            self.internal_air_id,
+        )
+        .with_world_origin(self.min)
+        .with_block_entities(self.block_entities.into_values().collect());
+        if self.biome_palette.len() > 1 {
+            schematic.with_biomes(self.biome_palette, self.biome_data, BiomeResolution::Cell)
+        } else {
+            schematic
+        }
+    }
+}
+
+/// Moves the blocks of a schematic to their place in the world.
+struct Transform {
+    origin: Coordinates,
+    /// Dimensions of the schematic before the rotation.
+    dimensions: Coordinates,
+    rotation: Rotation,
+    mirror: Mirror,
+}
+
+impl Transform {
+    fn new(dimensions: Coordinates, placement: &SchematicPlacement) -> Self {
+        Self {
+            origin: placement.origin,
+            dimensions,
+            rotation: placement.rotation,
+            mirror: placement.mirror,
+        }
+    }
+
+    /// Corner past the highest coordinates of the placed schematic.
+    fn end(&self) -> Coordinates {
+        let size = if self.rotation.quarter_turns() % 2 == 1 {
+            Coordinates::new(
+                self.dimensions.z(),
+                self.dimensions.y(),
+                self.dimensions.x(),
+            )
+        } else {
+            self.dimensions
+        };
+        self.origin + size
+    }
+
+    fn position(&self, source: Coordinates) -> Coordinates {
+        let (width, length) = (self.dimensions.x(), self.dimensions.z());
+        let (mut x, mut z) = (source.x(), source.z());
+        match self.mirror {
+            Mirror::None => {}
+            Mirror::X => x = width - 1 - x,
+            Mirror::Z => z = length - 1 - z,
+        }
+        let (x, z) = match self.rotation {
+            Rotation::None => (x, z),
+            Rotation::Clockwise90 => (length - 1 - z, x),
+            Rotation::Clockwise180 => (width - 1 - x, length - 1 - z),
+            Rotation::Counterclockwise90 => (z, width - 1 - x),
+        };
+        self.origin + Coordinates::new(x, source.y(), z)
+    }
+
+    /// Rotates and mirrors the properties of a block state, such as its facing or its connections.
+    /// States that do not exist once transformed are kept as is.
+    fn block_state(
+        &self,
+        internal_id: InternalId,
+        block_state_lookup: &BlockStateLookup,
+    ) -> InternalId {
+        if self.rotation == Rotation::None && self.mirror == Mirror::None {
+            return internal_id;
+        }
+        block_state_lookup
+            .state_string(internal_id)
+            .map(|state_string| self.state_string(&state_string))
+            .and_then(|state_string| block_state_lookup.parse_state_string(&state_string).ok())
+            .unwrap_or(internal_id)
+    }
+
+    fn state_string(&self, state_string: &str) -> String {
+        let Some((name, properties)) = state_string
+            .strip_suffix(']')
+            .and_then(|state| state.split_once('['))
+        else {
+            return state_string.to_string();
+        };
+        let properties: Vec<String> = properties
+            .split(',')
+            .filter_map(|property| property.split_once('='))
+            .map(|(key, value)| {
+                let (key, value) = self.property(key, value);
+                format!("{key}={value}")
+            })
+            .collect();
+        format!("{name}[{}]", properties.join(","))
+    }
+
+    fn property(&self, key: &str, value: &str) -> (String, String) {
+        match key {
+            "facing" => (key.to_string(), self.direction(value).to_string()),
+            // Connections of fences, walls, panes and redstone
+            "north" | "east" | "south" | "west" => {
+                (self.direction(key).to_string(), value.to_string())
+            }
+            "axis" if self.rotation.quarter_turns() % 2 == 1 => {
+                let axis = match value {
+                    "x" => "z",
+                    "z" => "x",
+                    axis => axis,
+                };
+                (key.to_string(), axis.to_string())
+            }
+            "rotation" => (key.to_string(), self.rotation_value(value)),
+            "shape" | "hinge" | "type" => (key.to_string(), self.shape(value)),
+            _ => (key.to_string(), value.to_string()),
+        }
+    }
+
+    fn direction<'a>(&self, direction: &'a str) -> &'a str {
+        let Some(index) = HORIZONTAL_DIRECTIONS.iter().position(|&d| d == direction) else {
+            return direction;
+        };
+        // North and south have an even index, east and west an odd one
+        let mirrored = match self.mirror {
+            Mirror::X if index % 2 == 1 => (index + 2) % 4,
+            Mirror::Z if index % 2 == 0 => (index + 2) % 4,
+            _ => index,
+        };
+        HORIZONTAL_DIRECTIONS[(mirrored + self.rotation.quarter_turns()) % 4]
+    }
+
+    /// Rotation of signs, banners and heads, in sixteenths of a turn starting from south.
+    fn rotation_value(&self, value: &str) -> String {
+        let Ok(rotation) = value.parse::<usize>() else {
+            return value.to_string();
+        };
+        let mirrored = match self.mirror {
+            Mirror::None => rotation,
+            Mirror::X => 16 - rotation,
+            Mirror::Z => 24 - rotation,
+        };
+        ((mirrored + 4 * self.rotation.quarter_turns()) % 16).to_string()
+    }
+
+    /// Rail shapes name their directions, mirroring swaps the left and right of stairs, doors and chests.
+    fn shape(&self, value: &str) -> String {
+        let mut parts: Vec<&str> = value
+            .split('_')
+            .map(|part| match (part, self.mirror) {
+                (_, Mirror::None) => part,
+                ("left", _) => "right",
+                ("right", _) => "left",
+                _ => part,
+            })
+            .map(|part| self.direction(part))
+            .collect();
+        // Rails going through two directions list north or south first
+        if parts.len() == 2
+            && parts
+                .iter()
+                .all(|part| HORIZONTAL_DIRECTIONS.contains(part))
+        {
+            let rank = |part: &&str| {
+                ["north", "south", "east", "west"]
+                    .iter()
+                    .position(|d| d == part)
+            };
+            parts.sort_by_key(rank);
+        }
+        parts.join("_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_blocks::TestBlocks;
+
+    fn transform(rotation: Rotation, mirror: Mirror) -> Transform {
+        Transform {
+            origin: Coordinates::new(10, 100, 20),
+            dimensions: Coordinates::new(3, 1, 2),
+            rotation,
+            mirror,
+        }
+    }
+
+    #[test]
+    fn test_rotated_positions() {
+        // Given
+        let transform = transform(Rotation::Clockwise90, Mirror::None);
+
+        // When
+        let north_west = transform.position(Coordinates::new(0, 0, 0));
+        let north_east = transform.position(Coordinates::new(2, 0, 0));
+
+        // Then
+        assert_eq!((north_west.x(), north_west.z()), (11, 20));
+        assert_eq!((north_east.x(), north_east.z()), (11, 22));
+        let end = transform.end();
+        assert_eq!((end.x(), end.y(), end.z()), (12, 101, 23));
+    }
+
+    #[test]
+    fn test_rotated_block_states() {
+        // Given
+        let transform = transform(Rotation::Clockwise90, Mirror::None);
+
+        // When
+        let stairs = transform.state_string("minecraft:oak_stairs[facing=north,shape=inner_left]");
+        let log = transform.state_string("minecraft:oak_log[axis=x]");
+        let fence = transform.state_string("minecraft:oak_fence[north=true,west=false]");
+        let rail = transform.state_string("minecraft:rail[shape=south_east]");
+        let sign = transform.state_string("minecraft:oak_sign[rotation=14]");
+
+        // Then
+        assert_eq!(stairs, "minecraft:oak_stairs[facing=east,shape=inner_left]");
+        assert_eq!(log, "minecraft:oak_log[axis=z]");
+        assert_eq!(fence, "minecraft:oak_fence[east=true,north=false]");
+        assert_eq!(rail, "minecraft:rail[shape=south_west]");
+        assert_eq!(sign, "minecraft:oak_sign[rotation=2]");
+    }
+
+    #[test]
+    fn test_mirrored_block_states() {
+        // Given
+        let transform = transform(Rotation::None, Mirror::X);
+
+        // When
+        let stairs = transform.state_string("minecraft:oak_stairs[facing=east,shape=outer_left]");
+        let door = transform.state_string("minecraft:oak_door[facing=north,hinge=left]");
+        let sign = transform.state_string("minecraft:oak_sign[rotation=4]");
+        let position = transform.position(Coordinates::new(0, 0, 1));
+
+        // Then
+        assert_eq!(
+            stairs,
+            "minecraft:oak_stairs[facing=west,shape=outer_right]"
+        );
+        assert_eq!(door, "minecraft:oak_door[facing=north,hinge=right]");
+        assert_eq!(sign, "minecraft:oak_sign[rotation=12]");
+        assert_eq!((position.x(), position.z()), (12, 21));
+    }
+
+    #[test]
+    fn test_later_placements_override_earlier_ones() {
+        // Given
+        let blocks = TestBlocks::new();
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data/structure.nbt");
+        let placements = [
+            SchematicPlacement::at_world_origin(&path),
+            SchematicPlacement {
+                path: path.clone(),
+                origin: Coordinates::new(1, 0, 0),
+                rotation: Rotation::Clockwise90,
+                mirror: Mirror::None,
+                palette: 0,
+            },
+        ];
+
+        // When
+        let schematic = Schematic::load_placements(&placements, &blocks.lookup()).unwrap();
+
+        // Then
+        let block = |x, y, z| schematic.get_block_state_id(Coordinates::new(x, y, z));
+        let dimensions = schematic.get_dimensions();
+        assert_eq!((dimensions.x(), dimensions.y(), dimensions.z()), (3, 2, 2));
+        assert_eq!(block(0, 0, 0), TestBlocks::STONE);
+        assert_eq!(block(2, 0, 0), TestBlocks::STONE);
+        assert_eq!(block(2, 1, 1), TestBlocks::RED_WOOL);
+        // Air of the second placement does not replace the wool of the first one
+        assert_eq!(block(1, 1, 0), TestBlocks::RED_WOOL);
+    }
+
+    #[test]
+    fn test_placements_at_negative_coordinates_are_kept() {
+        // Given
+        let blocks = TestBlocks::new();
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data/structure.nbt");
+        let placements = [SchematicPlacement {
+            path,
+            origin: Coordinates::new(-5, -64, -3),
+            rotation: Rotation::None,
+            mirror: Mirror::None,
+            palette: 0,
+        }];
+
+        // When
+        let schematic = Schematic::load_placements(&placements, &blocks.lookup()).unwrap();
+
+        // Then
+        let world_origin = schematic.get_world_origin();
+        assert_eq!(
+            (world_origin.x(), world_origin.y(), world_origin.z()),
+            (-5, -64, -3)
+        );
+        let dimensions = schematic.get_dimensions();
+        assert_eq!((dimensions.x(), dimensions.y(), dimensions.z()), (2, 2, 2));
+        assert_eq!(
+            schematic.get_block_state_id(Coordinates::new(0, 0, 0)),
+            TestBlocks::STONE
+        );
+    }
+
+    #[test]
+    fn test_placements_use_their_palette() {
+        // Given
+        let blocks = TestBlocks::new();
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data/palettes.nbt");
+        let placement = |palette| SchematicPlacement {
+            palette,
+            ..SchematicPlacement::at_world_origin(&path)
+        };
+
+        // When
+        let second = Schematic::load_placements(&[placement(1)], &blocks.lookup()).unwrap();
+        let missing = Schematic::load_placements(&[placement(2)], &blocks.lookup());
+
+        // Then
+        assert_eq!(
+            second.get_block_state_id(Coordinates::new(0, 0, 0)),
+            TestBlocks::RED_WOOL
+        );
+        assert!(matches!(
+            missing.err(),
+            Some(SchematicError::InvalidPaletteIndex(2))
+        ));
+    }
+}
@@ -1,5 +1,11 @@
+use crate::anvil_world::{AnvilArea, load_anvil_area};
+use crate::block_entity::BlockEntity;
 use crate::decompress::decompress_gz_file;
-use blocks_report::{BlockStateLookup, InternalId, InternalMapping};
+use crate::litematic_schematic::load_litematic_schematic;
+use crate::mcedit_schematic::load_mcedit_schematic;
+use crate::placement::{SchematicPlacement, compose_placements};
+use crate::structure_schematic::load_structure_schematic;
+use blocks_report::{BlockStateLookup, InternalId};
 use minecraft_protocol::prelude::{Coordinates, VarInt};
 use pico_binutils::prelude::{BinaryReader, BinaryReaderError};
 use pico_nbt::prelude::{Nbt, NbtDecodeError};
@@ -19,8 +25,16 @@ pub enum SchematicError {
     MissingTag(String),
     #[error("NBT tag '{0}' has an incorrect type")]
     IncorrectTagType(String),
-    #[error("Unsupported schematic version: {0}. Only version 2 is supported.")]
+    #[error("Unsupported Sponge schematic version: {0}. Only versions 2 and 3 are supported.")]
     UnsupportedVersion(i32),
+    #[error("Unsupported MCEdit schematic materials: {0}. Only Alpha is supported.")]
+    UnsupportedMaterials(String),
+    #[error("Palette {0} does not exist, only structures can have several palettes")]
+    InvalidPaletteIndex(usize),
+    #[error("Invalid structure size: {0}x{1}x{2}")]
+    InvalidSize(i32, i32, i32),
+    #[error("Unsupported chunk compression type: {0}")]
+    UnsupportedChunkCompression(u8),
     #[error("Air internal ID not found")]
     AirNotFound,
 }
@@ -30,132 +44,397 @@ pub struct Schematic {
     /// A flat vector storing all block state IDs, indexed by `y * length * width + z * width + x`.
     block_data: Vec<InternalId>,
     dimensions: Coordinates,
+    /// Position of the schematic relative to the player who copied it.
+    offset: Coordinates,
+    /// World position of the minimum corner, 0,0,0 for schematic files.
+    world_origin: Coordinates,
+    biomes: Option<SchematicBiomes>,
+    block_entities: Vec<BlockEntity>,
     internal_air_id: InternalId,
 }
 
+/// Biome names of the schematic, per block since version 3 and per column before.
+struct SchematicBiomes {
+    palette: Vec<String>,
+    /// Indices into the palette, indexed like the blocks.
+    data: Vec<u32>,
+    resolution: BiomeResolution,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum BiomeResolution {
+    /// One biome per column, indexed by `z * width + x`.
+    Column,
+    /// One biome per block, indexed like the blocks.
+    Block,
+    /// One biome per 4×4×4 cell, indexed like the blocks with every dimension divided by 4.
+    Cell,
+}
+
 impl Schematic {
-    /// Loads a `.schem` file from the given path for a specific Minecraft protocol version.
+    const AIR_IDENTIFIER: &'static str = "minecraft:air";
+
+    /// Loads a schematic file from the given path.
+    /// Files with the `.litematic` extension are read as Litematica schematics,
+    /// files with the `.nbt` extension as structures, using the palette at the given index
+    /// when they have several. Other formats only have the palette 0.
     pub fn load_schematic_file(
         path: &Path,
-        internal_mapping: &InternalMapping,
+        palette_index: usize,
+        block_state_lookup: &BlockStateLookup,
     ) -> Result<Self, SchematicError> {
         let nbt = Self::load_nbt_from_file(path)?;
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("nbt") => load_structure_schematic(&nbt, palette_index, block_state_lookup),
+            _ if palette_index != 0 => Err(SchematicError::InvalidPaletteIndex(palette_index)),
+            Some("litematic") => load_litematic_schematic(&nbt, block_state_lookup),
+            _ => Self::from_nbt(&nbt, block_state_lookup),
+        }
+    }
 
-        Self::validate_version(&nbt)?;
-        let dimensions = Self::extract_dimensions(&nbt)?;
-        let (schematic_id_to_internal_id, internal_air_id) =
-            Self::get_schematic_id_to_internal_id(&nbt, internal_mapping)?;
-        let block_data = Self::parse_block_data(
-            &nbt,
-            schematic_id_to_internal_id,
-            dimensions,
+    /// Loads an area of a vanilla world from the region files of one of its dimensions.
+    /// The world origin of the returned schematic is the world position of its minimum corner.
+    pub fn load_anvil_world(
+        world_folder: &Path,
+        area: &AnvilArea,
+        block_state_lookup: &BlockStateLookup,
+    ) -> Result<Self, SchematicError> {
+        load_anvil_area(world_folder, area, block_state_lookup)
+    }
+
+    /// Loads several schematic files and composes them into a single schematic,
+    /// whose world origin is the minimum corner of all the placements. Later placements override earlier ones.
+    pub fn load_placements(
+        placements: &[SchematicPlacement],
+        block_state_lookup: &BlockStateLookup,
+    ) -> Result<Self, SchematicError> {
+        let placed_schematics = placements
+            .iter()
+            .map(|placement| {
+                let schematic = Self::load_schematic_file(
+                    &placement.path,
+                    placement.palette,
+                    block_state_lookup,
+                )?;
+                Ok((schematic, placement))
+            })
+            .collect::<Result<Vec<_>, SchematicError>>()?;
+        compose_placements(&placed_schematics, block_state_lookup)
+    }
+
+    /// Parses a Sponge schematic, a legacy MCEdit schematic detected by its `Materials` tag,
+    /// a Litematica schematic detected by its `Regions` tag, or a structure detected by its `blocks` tag.
+    /// Structures with several palettes use the first one.
+    /// Sponge version 3 wraps every tag in a `Schematic` compound.
+    pub fn from_nbt(
+        nbt: &Nbt,
+        block_state_lookup: &BlockStateLookup,
+    ) -> Result<Self, SchematicError> {
+        let root = match nbt.find_tag("Schematic") {
+            Some(schematic @ Nbt::Compound { .. }) => schematic,
+            _ => nbt,
+        };
+        if root.find_tag("Materials").is_some() {
+            return load_mcedit_schematic(root, block_state_lookup);
+        }
+        if root.find_tag("Regions").is_some() {
+            return load_litematic_schematic(root, block_state_lookup);
+        }
+        if root.find_tag("blocks").is_some() {
+            return load_structure_schematic(root, 0, block_state_lookup);
+        }
+
+        let version = Self::get_tag_as(root, "Version", |t| t.get_int())?;
+        let (blocks, palette_tag, data_tag) = match version {
+            2 => (root, "Palette", "BlockData"),
+            3 => (Self::get_compound(root, "Blocks")?, "Palette", "Data"),
+            _ => return Err(SchematicError::UnsupportedVersion(version)),
+        };
+
+        let dimensions = Self::extract_dimensions(root)?;
+        let offset = Self::extract_offset(root);
+        let internal_air_id = Self::internal_air_id(block_state_lookup)?;
+        let schematic_id_to_internal_id = Self::get_schematic_id_to_internal_id(
+            blocks,
+            palette_tag,
+            block_state_lookup,
             internal_air_id,
         )?;
+        let block_count = (dimensions.x() * dimensions.y() * dimensions.z()) as usize;
+        let block_data = Self::parse_palette_data(blocks, data_tag, block_count)?
+            .into_iter()
+            .map(|schematic_id| {
+                schematic_id_to_internal_id
+                    .get(schematic_id as usize)
+                    .copied()
+                    .unwrap_or(internal_air_id)
+            })
+            .collect();
+        let biomes = Self::extract_biomes(root, version, dimensions)?;
+        let block_entities = Self::extract_block_entities(blocks);
 
         Ok(Self {
             block_data,
             dimensions,
+            offset,
+            world_origin: Coordinates::default(),
+            biomes,
+            block_entities,
             internal_air_id,
         })
     }
 
+    /// Creates a schematic without biomes from blocks indexed by `y * length * width + z * width + x`.
+    pub(crate) fn from_blocks(
+        block_data: Vec<InternalId>,
+        dimensions: Coordinates,
+        offset: Coordinates,
+        internal_air_id: InternalId,
+    ) -> Self {
+        Self {
+            block_data,
+            dimensions,
+            offset,
+            world_origin: Coordinates::default(),
+            biomes: None,
+            block_entities: Vec::new(),
+            internal_air_id,
+        }
+    }
+
+    /// Places a schematic created by [`Self::from_blocks`] at the given world position.
+    pub(crate) const fn with_world_origin(mut self, world_origin: Coordinates) -> Self {
+        self.world_origin = world_origin;
+        self
+    }
+
+    /// Adds block entities to a schematic created by [`Self::from_blocks`].
+    pub(crate) fn with_block_entities(mut self, block_entities: Vec<BlockEntity>) -> Self {
+        self.block_entities = block_entities;
+        self
+    }
+
+    /// Adds biomes to a schematic created by [`Self::from_blocks`].
+    pub(crate) fn with_biomes(
+        mut self,
+        palette: Vec<String>,
+        data: Vec<u32>,
+        resolution: BiomeResolution,
+    ) -> Self {
+        self.biomes = Some(SchematicBiomes {
+            palette,
+            data,
+            resolution,
+        });
+        self
+    }
+
+    pub(crate) fn internal_air_id(
+        block_state_lookup: &BlockStateLookup,
+    ) -> Result<InternalId, SchematicError> {
+        block_state_lookup
+            .parse_state_string(Self::AIR_IDENTIFIER)
+            .map_err(|_| SchematicError::AirNotFound)
+    }
+
     fn load_nbt_from_file(path: &Path) -> Result<Nbt, SchematicError> {
         let bytes = decompress_gz_file(path)?;
         Nbt::from_bytes(&bytes).map_err(Into::into)
     }
 
-    fn validate_version(nbt: &Nbt) -> Result<(), SchematicError> {
-        let version = Self::get_tag_as(nbt, "Version", |t| t.get_int())?;
-        if version != 2 {
-            return Err(SchematicError::UnsupportedVersion(version));
-        }
-        Ok(())
+    fn as_compound(nbt: &Nbt) -> Option<&Nbt> {
+        matches!(nbt, Nbt::Compound { .. }).then_some(nbt)
+    }
+
+    fn get_compound<'a>(nbt: &'a Nbt, tag_name: &str) -> Result<&'a Nbt, SchematicError> {
+        nbt.find_tag(tag_name)
+            .ok_or_else(|| SchematicError::MissingTag(tag_name.to_string()))
+            .and_then(|tag| {
+                Self::as_compound(tag)
+                    .ok_or_else(|| SchematicError::IncorrectTagType(tag_name.to_string()))
+            })
     }
 
-    fn extract_dimensions(nbt: &Nbt) -> Result<Coordinates, SchematicError> {
-        let width = Self::get_tag_as::<i16>(nbt, "Width", |t| t.get_short())? as i32;
-        let height = Self::get_tag_as::<i16>(nbt, "Height", |t| t.get_short())? as i32;
-        let length = Self::get_tag_as::<i16>(nbt, "Length", |t| t.get_short())? as i32;
+    pub(crate) fn extract_dimensions(nbt: &Nbt) -> Result<Coordinates, SchematicError> {
+        let width = Self::get_tag_as::<i16>(nbt, "Width", |t| t.get_short())? as u16 as i32;
+        let height = Self::get_tag_as::<i16>(nbt, "Height", |t| t.get_short())? as u16 as i32;
+        let length = Self::get_tag_as::<i16>(nbt, "Length", |t| t.get_short())? as u16 as i32;
         Ok(Coordinates::new(width, height, length))
     }
 
+    fn extract_offset(nbt: &Nbt) -> Coordinates {
+        match nbt
+            .find_tag("Offset")
+            .and_then(|t| t.get_int_array())
+            .as_deref()
+        {
+            Some([x, y, z]) => Coordinates::new(*x, *y, *z),
+            _ => Coordinates::default(),
+        }
+    }
+
+    /// Maps every ID of the palette to the matching internal ID, unknown blocks become air.
     fn get_schematic_id_to_internal_id(
         nbt: &Nbt,
-        internal_mapping: &InternalMapping,
-    ) -> Result<(Vec<InternalId>, InternalId), SchematicError> {
-        let max_schematic_id = Self::get_tag_as(nbt, "PaletteMax", |t| t.get_int())?;
-        let block_state_lookup = BlockStateLookup::new(internal_mapping);
-
-        const AIR_IDENTIFIER: &str = "minecraft:air";
-        let internal_air_id = block_state_lookup
-            .parse_state_string(AIR_IDENTIFIER)
-            .map_err(|_| SchematicError::AirNotFound)?;
-
-        let mut schematic_id_to_internal_id: Vec<InternalId> =
-            vec![internal_air_id; (max_schematic_id + 1) as usize];
-
-        let palette_nbt = Self::get_tag_as(nbt, "Palette", |t| t.get_nbt_vec())?;
-
-        for block_tag in palette_nbt {
-            if let Some(schematic_palette_id) = block_tag.get_int() {
-                let internal_id = block_tag
-                    .get_name()
-                    .and_then(|name| block_state_lookup.parse_state_string(&name).ok())
-                    .unwrap_or(internal_air_id);
-
-                if let Some(entry) =
-                    schematic_id_to_internal_id.get_mut(schematic_palette_id as usize)
-                {
-                    *entry = internal_id;
-                } else {
-                    warn!(
-                        "Schematic palette contains ID {} which is greater than PaletteMax of {}. Skipping.",
-                        schematic_palette_id, max_schematic_id
-                    );
-                }
+        palette_tag: &str,
+        block_state_lookup: &BlockStateLookup,
+        internal_air_id: InternalId,
+    ) -> Result<Vec<InternalId>, SchematicError> {
+        let mut schematic_id_to_internal_id: Vec<InternalId> = Vec::new();
+
+        for (name, schematic_palette_id) in Self::parse_palette(nbt, palette_tag)? {
+            let internal_id = block_state_lookup
+                .parse_state_string(&name)
+                .unwrap_or_else(|_| {
+                    warn!("Unknown block state {name} in schematic palette, replaced with air");
+                    internal_air_id
+                });
+            if schematic_id_to_internal_id.len() <= schematic_palette_id {
+                schematic_id_to_internal_id.resize(schematic_palette_id + 1, internal_air_id);
             }
+            schematic_id_to_internal_id[schematic_palette_id] = internal_id;
         }
 
-        Ok((schematic_id_to_internal_id, internal_air_id))
+        Ok(schematic_id_to_internal_id)
+    }
+
+    /// Reads a palette compound, whose tags are named after the entries and hold their ID.
+    fn parse_palette(nbt: &Nbt, palette_tag: &str) -> Result<Vec<(String, usize)>, SchematicError> {
+        let palette_nbt = Self::get_tag_as(nbt, palette_tag, |t| t.get_nbt_vec())?;
+        Ok(palette_nbt
+            .iter()
+            .filter_map(|tag| {
+                let id = usize::try_from(tag.get_int()?).ok()?;
+                Some((tag.get_name()?, id))
+            })
+            .collect())
     }
 
-    fn parse_block_data(
+    /// Reads the VarInt palette IDs of a byte array, padded with zeros if it is too short.
+    fn parse_palette_data(
         nbt: &Nbt,
-        schematic_id_to_internal_id: Vec<InternalId>,
-        dimensions: Coordinates,
-        fallback_id: InternalId,
-    ) -> Result<Vec<InternalId>, SchematicError> {
-        let total_blocks = (dimensions.x() * dimensions.y() * dimensions.z()) as usize;
-        let block_data_i8 = Self::get_tag_as::<Vec<i8>>(nbt, "BlockData", |t| t.get_byte_array())?;
-        let block_data_u8: Vec<u8> = block_data_i8.iter().map(|&b| b as u8).collect();
-        let mut reader = BinaryReader::new(&block_data_u8);
+        data_tag: &str,
+        entry_count: usize,
+    ) -> Result<Vec<u32>, SchematicError> {
+        let data_i8 = Self::get_tag_as::<Vec<i8>>(nbt, data_tag, |t| t.get_byte_array())?;
+        let data_u8: Vec<u8> = data_i8.iter().map(|&b| b as u8).collect();
+        let mut reader = BinaryReader::new(&data_u8);
 
-        let mut block_data = Vec::with_capacity(total_blocks);
+        let mut data = Vec::with_capacity(entry_count);
 
-        for _ in 0..total_blocks {
+        for _ in 0..entry_count {
             if reader.remaining() == 0 {
-                warn!("Schematic BlockData is smaller than expected dimensions. Truncating.");
+                warn!("Schematic {data_tag} is smaller than expected dimensions. Truncating.");
                 break;
             }
+            data.push(reader.read::<VarInt>()?.inner() as u32);
+        }
 
-            let schematic_block_id = reader.read::<VarInt>()?.inner();
+        // Ensure the vec is the correct size if the data was truncated
+        data.resize(entry_count, 0);
+
+        Ok(data)
+    }
 
-            let internal_id = schematic_id_to_internal_id
-                .get(schematic_block_id as usize)
-                .copied()
-                .unwrap_or(fallback_id);
+    /// Biomes are optional, stored per column in version 2 and per block in version 3.
+    fn extract_biomes(
+        root: &Nbt,
+        version: i32,
+        dimensions: Coordinates,
+    ) -> Result<Option<SchematicBiomes>, SchematicError> {
+        let column_count = (dimensions.x() * dimensions.z()) as usize;
+        let (biomes, palette_tag, data_tag, is_per_column) = if version == 2 {
+            if root.find_tag("BiomeData").is_none() {
+                return Ok(None);
+            }
+            (root, "BiomePalette", "BiomeData", true)
+        } else {
+            match root.find_tag("Biomes").and_then(Self::as_compound) {
+                Some(biomes) => (biomes, "Palette", "Data", false),
+                None => return Ok(None),
+            }
+        };
 
-            block_data.push(internal_id);
+        let mut palette = Vec::new();
+        for (name, id) in Self::parse_palette(biomes, palette_tag)? {
+            if palette.len() <= id {
+                palette.resize(id + 1, String::new());
+            }
+            palette[id] = name;
         }
+        let entry_count = if is_per_column {
+            column_count
+        } else {
+            column_count * dimensions.y() as usize
+        };
+        let data = Self::parse_palette_data(biomes, data_tag, entry_count)?;
 
-        // Ensure the vec is the correct size if the data was truncated
-        block_data.resize(total_blocks, fallback_id);
+        let resolution = if is_per_column {
+            BiomeResolution::Column
+        } else {
+            BiomeResolution::Block
+        };
+        Ok(Some(SchematicBiomes {
+            palette,
+            data,
+            resolution,
+        }))
+    }
+
+    /// Block entities are optional. Their data is stored next to their position and type in version 2,
+    /// and in a `Data` compound since version 3.
+    fn extract_block_entities(blocks: &Nbt) -> Vec<BlockEntity> {
+        blocks
+            .find_tag("BlockEntities")
+            .and_then(|t| t.get_nbt_vec())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|block_entity| {
+                let [x, y, z] = block_entity.find_tag("Pos")?.get_int_array()?[..] else {
+                    return None;
+                };
+                let id = block_entity.find_tag("Id")?.get_string()?;
+                let data = match block_entity.find_tag("Data") {
+                    Some(data) => data.get_nbt_vec()?,
+                    None => block_entity.get_nbt_vec()?,
+                };
+                Some(BlockEntity::new(Coordinates::new(x, y, z), id, data))
+            })
+            .collect()
+    }
 
-        Ok(block_data)
+    /// Formats a palette entry, `{Name, Properties}`, the way `BlockStateLookup` parses it.
+    pub(crate) fn block_state_string(entry: &Nbt) -> String {
+        let name = entry
+            .find_tag("Name")
+            .and_then(|t| t.get_string())
+            .unwrap_or_default();
+        let properties: Vec<String> = entry
+            .find_tag("Properties")
+            .and_then(|t| t.get_nbt_vec())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|property| {
+                Some(format!(
+                    "{}={}",
+                    property.get_name()?,
+                    property.get_string()?
+                ))
+            })
+            .collect();
+
+        if properties.is_empty() {
+            name
+        } else {
+            format!("{name}[{}]", properties.join(","))
+        }
     }
 
     /// Helper function to safely get a required NBT tag and extract its value.
-    fn get_tag_as<T>(
+    pub(crate) fn get_tag_as<T>(
         nbt: &Nbt,
         tag_name: &str,
         getter: fn(&Nbt) -> Option<T>,
@@ -206,4 +485,111 @@ impl Schematic {
     pub fn get_dimensions(&self) -> Coordinates {
         self.dimensions
     }
+
+    pub fn get_offset(&self) -> Coordinates {
+        self.offset
+    }
+
+    pub fn get_world_origin(&self) -> Coordinates {
+        self.world_origin
+    }
+
+    /// Block entities of the schematic, positioned relative to it.
+    pub fn get_block_entities(&self) -> &[BlockEntity] {
+        &self.block_entities
+    }
+
+    /// Gets the name of the biome at the given relative coordinates, if the schematic has biomes.
+    pub fn get_biome(&self, schematic_position: Coordinates) -> Option<&str> {
+        if self.is_out_of_bounds(&schematic_position) {
+            return None;
+        }
+        let biomes = self.biomes.as_ref()?;
+        let index = match biomes.resolution {
+            BiomeResolution::Column => self.position_to_index(Coordinates::new(
+                schematic_position.x(),
+                0,
+                schematic_position.z(),
+            )),
+            BiomeResolution::Block => self.position_to_index(schematic_position),
+            BiomeResolution::Cell => {
+                let cells = (self.dimensions + 3) / 4;
+                let cell = schematic_position / 4;
+                ((cell.y() * cells.z() + cell.z()) * cells.x() + cell.x()) as usize
+            }
+        };
+        let palette_id = *biomes.data.get(index)?;
+        biomes
+            .palette
+            .get(palette_id as usize)
+            .map(String::as_str)
+            .filter(|name| !name.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_blocks::TestBlocks;
+
+    fn load(file_name: &str) -> Result<Schematic, SchematicError> {
+        TestBlocks::new().load(file_name)
+    }
+
+    fn assert_sample_schematic(schematic: &Schematic) {
+        let dimensions = schematic.get_dimensions();
+        assert_eq!((dimensions.x(), dimensions.y(), dimensions.z()), (3, 2, 2));
+        let offset = schematic.get_offset();
+        assert_eq!((offset.x(), offset.y(), offset.z()), (1, -2, 3));
+        for x in 0..3 {
+            assert_eq!(schematic.get_block_state_id(Coordinates::new(x, 0, 0)), 1);
+        }
+        assert_eq!(schematic.get_block_state_id(Coordinates::new(2, 1, 1)), 3);
+        assert_eq!(schematic.get_block_state_id(Coordinates::new(0, 1, 0)), 0);
+        assert_eq!(
+            schematic.get_biome(Coordinates::new(1, 1, 0)),
+            Some("minecraft:desert")
+        );
+        assert_eq!(
+            schematic.get_biome(Coordinates::new(0, 0, 1)),
+            Some("minecraft:plains")
+        );
+        // The data of a sign is stored next to its position in version 2 and in `Data` in version 3
+        let [sign] = schematic.get_block_entities() else {
+            panic!("expected a single block entity");
+        };
+        let position = sign.position();
+        assert_eq!((position.x(), position.y(), position.z()), (2, 0, 1));
+        assert_eq!(sign.id(), "minecraft:sign");
+        let tag_names: Vec<String> = sign.data().iter().filter_map(Nbt::get_name).collect();
+        assert_eq!(tag_names, ["front_text", "is_waxed"]);
+    }
+
+    #[test]
+    fn test_load_sponge_v2() {
+        // When
+        let schematic = load("sponge_v2.schem").unwrap();
+
+        // Then
+        assert_sample_schematic(&schematic);
+    }
+
+    #[test]
+    fn test_load_sponge_v3() {
+        // When
+        let schematic = load("sponge_v3.schem").unwrap();
+
+        // Then
+        assert_sample_schematic(&schematic);
+    }
+
+    #[test]
+    fn test_unsupported_version_is_named() {
+        // When
+        let error = load("sponge_v4.schem").err().unwrap();
+
+        // Then
+        assert!(matches!(error, SchematicError::UnsupportedVersion(4)));
+        assert!(error.to_string().contains('4'));
+    }
 }
@@ -0,0 +1,194 @@
+use crate::block_entity::BlockEntity;
+use crate::schematic::{Schematic, SchematicError};
+use blocks_report::{BlockStateLookup, InternalId};
+use minecraft_protocol::prelude::Coordinates;
+use pico_nbt::prelude::Nbt;
+use tracing::warn;
+
+const STRUCTURE_VOID: &str = "minecraft:structure_void";
+
+/// Loads a vanilla structure `.nbt` file saved by a structure block.
+///
+/// Blocks are listed one by one with their position, positions that are not listed are air.
+/// Structures with several `palettes` use the palette at the given index, `structure_void` is air.
+/// Block entities are stored in the `nbt` compound of their block.
+pub fn load_structure_schematic(
+    root: &Nbt,
+    palette_index: usize,
+    block_state_lookup: &BlockStateLookup,
+) -> Result<Schematic, SchematicError> {
+    let dimensions = read_int_list(root, "size")?;
+    let (width, height, length) = validate_size(dimensions)?;
+    let internal_air_id = Schematic::internal_air_id(block_state_lookup)?;
+    let palette: Vec<InternalId> = select_palette(root, palette_index)?
+        .iter()
+        .map(|entry| {
+            let state_string = Schematic::block_state_string(entry);
+            if state_string == STRUCTURE_VOID {
+                return internal_air_id;
+            }
+            block_state_lookup
+                .parse_state_string(&state_string)
+                .unwrap_or_else(|_| {
+                    warn!(
+                        "Unknown block state {state_string} in structure palette, replaced with air"
+                    );
+                    internal_air_id
+                })
+        })
+        .collect();
+
+    let mut block_data = vec![internal_air_id; width * height * length];
+    let mut block_entities = Vec::new();
+    for block in Schematic::get_tag_as(root, "blocks", |t| t.get_nbt_vec())? {
+        let position = read_int_list(&block, "pos")?;
+        let state = Schematic::get_tag_as(&block, "state", |t| t.get_int())?;
+        let is_inside = (0..dimensions.x()).contains(&position.x())
+            && (0..dimensions.y()).contains(&position.y())
+            && (0..dimensions.z()).contains(&position.z());
+        if !is_inside {
+            warn!("Structure block outside of the structure size, ignored");
+            continue;
+        }
+        let index = (position.y() as usize * length + position.z() as usize) * width
+            + position.x() as usize;
+        block_data[index] = usize::try_from(state)
+            .ok()
+            .and_then(|state| palette.get(state))
+            .copied()
+            .unwrap_or(internal_air_id);
+        if let Some(block_entity) = block
+            .find_tag("nbt")
+            .and_then(|nbt| BlockEntity::from_compound(position, nbt))
+        {
+            block_entities.push(block_entity);
+        }
+    }
+
+    Ok(Schematic::from_blocks(
+        block_data,
+        dimensions,
+        Coordinates::default(),
+        internal_air_id,
+    )
+    .with_block_entities(block_entities))
+}
+
+/// Returns the width, height and length of the structure, none of them can be negative.
+fn validate_size(size: Coordinates) -> Result<(usize, usize, usize), SchematicError> {
+    let invalid_size = || SchematicError::InvalidSize(size.x(), size.y(), size.z());
+    let width = usize::try_from(size.x()).map_err(|_| invalid_size())?;
+    let height = usize::try_from(size.y()).map_err(|_| invalid_size())?;
+    let length = usize::try_from(size.z()).map_err(|_| invalid_size())?;
+    width
+        .checked_mul(height)
+        .and_then(|area| area.checked_mul(length))
+        .ok_or_else(invalid_size)?;
+    Ok((width, height, length))
+}
+
+/// Structures have either a single `palette`, or several `palettes` of the same length.
+fn select_palette(root: &Nbt, palette_index: usize) -> Result<Vec<Nbt>, SchematicError> {
+    if let Some(palettes) = root.find_tag("palettes").and_then(|t| t.get_nbt_vec()) {
+        return palettes
+            .get(palette_index)
+            .and_then(Nbt::get_nbt_vec)
+            .ok_or(SchematicError::InvalidPaletteIndex(palette_index));
+    }
+    if palette_index != 0 {
+        return Err(SchematicError::InvalidPaletteIndex(palette_index));
+    }
+    Schematic::get_tag_as(root, "palette", |t| t.get_nbt_vec())
+}
+
+/// Reads a list of 3 ints, such as `size` or `pos`.
+fn read_int_list(nbt: &Nbt, tag_name: &str) -> Result<Coordinates, SchematicError> {
+    let values: Vec<i32> = Schematic::get_tag_as(nbt, tag_name, |t| t.get_nbt_vec())?
+        .iter()
+        .filter_map(Nbt::get_int)
+        .collect();
+    match values.as_slice() {
+        [x, y, z] => Ok(Coordinates::new(*x, *y, *z)),
+        _ => Err(SchematicError::IncorrectTagType(tag_name.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_blocks::TestBlocks;
+    use std::path::PathBuf;
+
+    fn load(palette_index: usize) -> Result<Schematic, SchematicError> {
+        let blocks = TestBlocks::new();
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data/palettes.nbt");
+        Schematic::load_schematic_file(&path, palette_index, &blocks.lookup())
+    }
+
+    #[test]
+    fn test_load_structure() {
+        // Given
+        let blocks = TestBlocks::new();
+
+        // When
+        let schematic = blocks.load("structure.nbt").unwrap();
+
+        // Then
+        let block = |x, y, z| schematic.get_block_state_id(Coordinates::new(x, y, z));
+        let dimensions = schematic.get_dimensions();
+        assert_eq!((dimensions.x(), dimensions.y(), dimensions.z()), (2, 2, 2));
+        assert_eq!(block(0, 0, 0), TestBlocks::STONE);
+        assert_eq!(block(1, 0, 1), TestBlocks::OAK_LOG_X);
+        assert_eq!(block(1, 1, 0), TestBlocks::RED_WOOL);
+        // Structure void and positions missing from the block list
+        assert_eq!(block(0, 1, 1), TestBlocks::AIR);
+        assert_eq!(block(1, 1, 1), TestBlocks::AIR);
+    }
+
+    #[test]
+    fn test_palette_is_chosen_by_index() {
+        // When
+        let first = load(0).unwrap();
+        let second = load(1).unwrap();
+
+        // Then
+        let origin = Coordinates::new(0, 0, 0);
+        assert_eq!(first.get_block_state_id(origin), TestBlocks::STONE);
+        assert_eq!(second.get_block_state_id(origin), TestBlocks::RED_WOOL);
+        assert!(matches!(
+            load(2).err(),
+            Some(SchematicError::InvalidPaletteIndex(2))
+        ));
+    }
+
+    #[test]
+    fn test_negative_size_is_an_error() {
+        // Given
+        let blocks = TestBlocks::new();
+        let size = [2, 1, -3]
+            .into_iter()
+            .map(|value| Nbt::Int { name: None, value })
+            .collect();
+        let root = Nbt::compound(
+            "",
+            vec![
+                Nbt::List {
+                    name: Some("size".to_string()),
+                    value: size,
+                    tag_type: 3,
+                },
+                Nbt::compound_list("palette", Vec::new()),
+                Nbt::compound_list("blocks", Vec::new()),
+            ],
+        );
+
+        // When
+        let result = load_structure_schematic(&root, 0, &blocks.lookup());
+
+        // Then
+        assert!(matches!(
+            result.err(),
+            Some(SchematicError::InvalidSize(2, 1, -3))
+        ));
+    }
+}
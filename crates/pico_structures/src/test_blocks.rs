@@ -0,0 +1,89 @@
+use crate::prelude::{Schematic, SchematicError};
+use blocks_report::{BlockPropertiesTable, BlockStateLookup, InternalId, InternalMapping};
+use blocks_report_data::internal_mapping::{
+    InternalBlockMapping, InternalProperties, InternalState,
+};
+use blocks_report_data::legacy_mapping::{LEGACY_BLOCK_COUNT, LegacyBlockNames};
+use minecraft_protocol::prelude::LengthPaddedVec;
+use std::path::PathBuf;
+
+/// A handful of blocks to load the schematics of the `test_data` directory.
+pub struct TestBlocks {
+    internal_mapping: InternalMapping,
+    block_properties: BlockPropertiesTable,
+    legacy_block_names: LegacyBlockNames,
+}
+
+impl TestBlocks {
+    pub const AIR: InternalId = 0;
+    pub const STONE: InternalId = 1;
+    pub const OAK_LOG_X: InternalId = 3;
+    pub const RED_WOOL: InternalId = 4;
+
+    pub fn new() -> Self {
+        let mut legacy_block_names = vec![String::new(); LEGACY_BLOCK_COUNT];
+        legacy_block_names[0] = "minecraft:air".to_string();
+        legacy_block_names[1 << 4] = "minecraft:stone".to_string();
+        legacy_block_names[17 << 4] = "minecraft:oak_log[axis=y]".to_string();
+        legacy_block_names[(17 << 4) | 4] = "minecraft:oak_log[axis=x]".to_string();
+        legacy_block_names[(35 << 4) | 14] = "minecraft:red_wool".to_string();
+
+        Self {
+            internal_mapping: InternalMapping {
+                mapping: LengthPaddedVec::new(vec![
+                    block("minecraft:air", &[(Self::AIR, None)]),
+                    block("minecraft:stone", &[(Self::STONE, None)]),
+                    block(
+                        "minecraft:oak_log",
+                        &[
+                            (2, Some(("axis", "y"))),
+                            (Self::OAK_LOG_X, Some(("axis", "x"))),
+                        ],
+                    ),
+                    block("minecraft:red_wool", &[(Self::RED_WOOL, None)]),
+                ]),
+            },
+            block_properties: BlockPropertiesTable::default(),
+            legacy_block_names: LengthPaddedVec::new(legacy_block_names),
+        }
+    }
+
+    pub fn lookup(&self) -> BlockStateLookup<'_> {
+        BlockStateLookup::new(
+            &self.internal_mapping,
+            &self.block_properties,
+            &self.legacy_block_names,
+        )
+    }
+
+    pub fn load(&self, file_name: &str) -> Result<Schematic, SchematicError> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("test_data")
+            .join(file_name);
+        Schematic::load_schematic_file(&path, 0, &self.lookup())
+    }
+}
+
+fn block(name: &str, states: &[(InternalId, Option<(&str, &str)>)]) -> InternalBlockMapping {
+    InternalBlockMapping {
+        name: name.to_string(),
+        states: LengthPaddedVec::new(
+            states
+                .iter()
+                .map(|(internal_id, property)| InternalState {
+                    internal_id: *internal_id,
+                    properties: LengthPaddedVec::new(
+                        property
+                            .iter()
+                            .map(|(name, value)| InternalProperties {
+                                name: name.to_string(),
+                                value: value.to_string(),
+                            })
+                            .collect(),
+                    ),
+                })
+                .collect(),
+        ),
+        default_internal_id: states[0].0,
+    }
+}
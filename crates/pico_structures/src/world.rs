@@ -1,49 +1,400 @@
+use crate::block_entity::BlockEntity;
 use crate::chunk_processor::{ChunkProcessor, ChunkProcessorError};
+use crate::generator::{FlatGenerator, GeneratedLayers};
+use crate::light_engine::{LightEngine, SectionLight};
 use crate::palette::Palette;
-use crate::prelude::Schematic;
+use crate::prelude::{Schematic, SchematicError};
+use blocks_report::{BlockStateLookup, InternalId};
 use minecraft_protocol::prelude::Coordinates;
 use rayon::iter::ParallelIterator;
 use rayon::prelude::IntoParallelIterator;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use std::sync::Arc;
 use thiserror::Error;
 
+const SECTION_SIZE: i32 = 16;
+const NIBBLE_ARRAY_SIZE: usize = 2048;
+const BIOME_CELL_SIZE: i32 = 4;
+const BIOME_CELLS_PER_SIDE: i32 = SECTION_SIZE / BIOME_CELL_SIZE;
+
 pub struct World {
-    world_sections: Vec<Palette>,
-    size_in_chunks: Coordinates,
+    /// Sections holding blocks, keyed by their index in the bounding box of the world.
+    /// Sections of air without biomes are left out, identical sections share their palette, light and biomes.
+    pub(crate) sections: HashMap<i32, WorldSection>,
+    /// Block entities of each chunk column, indexed by `x * size_z + z`.
+    pub(crate) block_entities: Vec<Vec<BlockEntity>>,
+    /// Section coordinates of the first stored section.
+    pub(crate) origin: Coordinates,
+    pub(crate) size_in_chunks: Coordinates,
+    /// Every biome found in the sections.
+    biome_names: Vec<String>,
+}
+
+pub(crate) struct WorldSection {
+    pub(crate) palette: Arc<Palette>,
+    pub(crate) light: Arc<SectionLight>,
+    /// `None` when the whole section uses the biome of the world.
+    pub(crate) biomes: Option<Arc<SectionBiomes>>,
+}
+
+/// Biomes of the 4×4×4 cells of a section, indexed by `(y * 4 + z) * 4 + x`.
+#[derive(PartialEq, Eq, Hash)]
+pub struct SectionBiomes {
+    /// Biome names, empty for the cells using the biome of the world.
+    pub(crate) palette: Vec<String>,
+    /// One index into the palette per cell.
+    pub(crate) cells: Vec<u8>,
+}
+
+impl SectionBiomes {
+    pub const CELL_COUNT: usize = 64;
+
+    pub fn palette(&self) -> &[String] {
+        &self.palette
+    }
+
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    /// Samples the biome of the schematic at the lowest corner of every cell.
+    /// Returns `None` if no cell has a biome.
+    fn from_schematic(schematic: &Schematic, section_position: Coordinates) -> Option<Self> {
+        let min = section_position * SECTION_SIZE - schematic.get_world_origin();
+        let mut palette = vec![String::new()];
+        let mut cells = Vec::with_capacity(Self::CELL_COUNT);
+        for y in 0..BIOME_CELLS_PER_SIDE {
+            for z in 0..BIOME_CELLS_PER_SIDE {
+                for x in 0..BIOME_CELLS_PER_SIDE {
+                    let position = min + Coordinates::new(x, y, z) * BIOME_CELL_SIZE;
+                    let biome = schematic.get_biome(position).unwrap_or_default();
+                    let index = match palette.iter().position(|name| name == biome) {
+                        Some(index) => index,
+                        None => {
+                            palette.push(biome.to_string());
+                            palette.len() - 1
+                        }
+                    };
+                    cells.push(index as u8);
+                }
+            }
+        }
+        (palette.len() > 1).then_some(Self { palette, cells })
+    }
+}
+
+/// Memory used by the sections of a world.
+pub struct WorldMemoryStats {
+    /// Sections of the bounding box of the world, including the ones left out.
+    pub bounding_sections: usize,
+    pub stored_sections: usize,
+    pub unique_palettes: usize,
+    pub unique_lights: usize,
+    /// Approximate size of the palettes, light and section table.
+    pub bytes: usize,
+}
+
+impl Display for WorldMemoryStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} sections stored, {} unique palettes, {} unique light arrays, {} KiB",
+            self.stored_sections,
+            self.bounding_sections,
+            self.unique_palettes,
+            self.unique_lights,
+            self.bytes.div_ceil(1024)
+        )
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum WorldLoadingError {
     #[error(transparent)]
     ChunkProcessor(#[from] ChunkProcessorError),
+    #[error(transparent)]
+    Schematic(#[from] SchematicError),
+    #[error("unknown block state {0} in the world generator")]
+    UnknownGeneratorBlock(String),
+}
+
+/// The blocks of a schematic, on top of the layers of a generator.
+struct WorldBlocks<'a> {
+    schematic: Option<&'a Schematic>,
+    layers: Option<&'a GeneratedLayers>,
+    internal_air_id: InternalId,
+}
+
+impl WorldBlocks<'_> {
+    /// Air of the schematic does not replace the generated blocks.
+    fn block_at(&self, position: Coordinates) -> InternalId {
+        self.schematic
+            .map(|schematic| schematic.get_block_state_id(position - schematic.get_world_origin()))
+            .filter(|internal_id| *internal_id != self.internal_air_id)
+            .or_else(|| self.layers.and_then(|layers| layers.block_at(position)))
+            .unwrap_or(self.internal_air_id)
+    }
+
+    /// Returns whether the schematic has blocks in the section.
+    fn overlaps_schematic(&self, section_position: Coordinates) -> bool {
+        self.schematic.is_some_and(|schematic| {
+            let min = section_position * SECTION_SIZE - schematic.get_world_origin();
+            let dimensions = schematic.get_dimensions();
+            min.x() < dimensions.x()
+                && min.y() < dimensions.y()
+                && min.z() < dimensions.z()
+                && min.x() + SECTION_SIZE > 0
+                && min.y() + SECTION_SIZE > 0
+                && min.z() + SECTION_SIZE > 0
+        })
+    }
+
+    /// Lowest corner of the blocks and the corner past their highest coordinates.
+    fn bounds(&self) -> (Coordinates, Coordinates) {
+        let schematic_bounds = self.schematic.map(|schematic| {
+            let world_origin = schematic.get_world_origin();
+            (world_origin, world_origin + schematic.get_dimensions())
+        });
+        let layers_bounds = self.layers.and_then(GeneratedLayers::bounds);
+        match (schematic_bounds, layers_bounds) {
+            (Some((min, end)), Some((layers_min, layers_end))) => (
+                Coordinates::new(
+                    min.x().min(layers_min.x()),
+                    min.y().min(layers_min.y()),
+                    min.z().min(layers_min.z()),
+                ),
+                Coordinates::new(
+                    end.x().max(layers_end.x()),
+                    end.y().max(layers_end.y()),
+                    end.z().max(layers_end.z()),
+                ),
+            ),
+            (Some(bounds), None) | (None, Some(bounds)) => bounds,
+            (None, None) => (Coordinates::new_uniform(0), Coordinates::new_uniform(0)),
+        }
+    }
 }
 
 impl World {
-    pub fn from_schematic(schematic: &Schematic) -> Result<Self, WorldLoadingError> {
-        let dimensions = schematic.get_dimensions();
-        let size_in_chunks = (dimensions + 15) / 16;
+    pub fn from_schematic(
+        schematic: &Schematic,
+        block_state_lookup: &BlockStateLookup,
+    ) -> Result<Self, WorldLoadingError> {
+        Self::build(Some(schematic), None, block_state_lookup)
+    }
+
+    /// Generates the layers of the generator, the blocks of the schematic are placed over them.
+    pub fn generate(
+        generator: &FlatGenerator,
+        schematic: Option<&Schematic>,
+        block_state_lookup: &BlockStateLookup,
+    ) -> Result<Self, WorldLoadingError> {
+        let layers = generator.resolve(block_state_lookup)?;
+        Self::build(schematic, Some(&layers), block_state_lookup)
+    }
+
+    fn build(
+        schematic: Option<&Schematic>,
+        layers: Option<&GeneratedLayers>,
+        block_state_lookup: &BlockStateLookup,
+    ) -> Result<Self, WorldLoadingError> {
+        let blocks = WorldBlocks {
+            schematic,
+            layers,
+            internal_air_id: Schematic::internal_air_id(block_state_lookup)?,
+        };
+        let (min, end) = blocks.bounds();
+        let origin = Coordinates::new(
+            min.x().div_euclid(SECTION_SIZE),
+            min.y().div_euclid(SECTION_SIZE),
+            min.z().div_euclid(SECTION_SIZE),
+        );
+        let size_in_chunks = Coordinates::new(
+            (end.x() + SECTION_SIZE - 1).div_euclid(SECTION_SIZE),
+            (end.y() + SECTION_SIZE - 1).div_euclid(SECTION_SIZE),
+            (end.z() + SECTION_SIZE - 1).div_euclid(SECTION_SIZE),
+        ) - origin;
         let chunk_count = size_in_chunks.x() * size_in_chunks.y() * size_in_chunks.z();
 
-        let world_sections: Result<Vec<_>, _> = (0..chunk_count)
+        let palettes = (0..chunk_count)
             .into_par_iter()
             .map(|i| {
-                let chunk_x = i / (size_in_chunks.y() * size_in_chunks.z());
-                let chunk_y = (i / size_in_chunks.z()) % size_in_chunks.y();
-                let chunk_z = i % size_in_chunks.z();
+                let section_position = origin + section_position_at(i, size_in_chunks);
 
-                let section_position = Coordinates::new(chunk_x, chunk_y, chunk_z);
+                // Sections made of a single layer are filled without looking at each block
+                if !blocks.overlaps_schematic(section_position)
+                    && let Some(internal_id) =
+                        layers.and_then(|layers| layers.uniform_section(section_position))
+                {
+                    return Ok(Palette::single(
+                        internal_id.unwrap_or(blocks.internal_air_id),
+                    ));
+                }
 
                 let mut processor = ChunkProcessor::new();
-                processor.process_section(schematic, section_position)
+                processor.process_section(|position| blocks.block_at(position), section_position)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let is_air = |palette: &Palette| matches!(palette, Palette::Single { internal_id } if *internal_id == blocks.internal_air_id);
+        let palettes: Vec<_> = (0..chunk_count)
+            .zip(palettes)
+            .filter_map(|(i, palette)| {
+                let section_position = origin + section_position_at(i, size_in_chunks);
+                let biomes = schematic.and_then(|schematic| {
+                    SectionBiomes::from_schematic(schematic, section_position)
+                });
+                (!is_air(&palette) || biomes.is_some()).then_some((i, palette, biomes))
             })
             .collect();
 
-        Ok(Self {
-            world_sections: world_sections?,
+        let light_engine = LightEngine::from_blocks(
+            |position| blocks.block_at(position),
+            block_state_lookup,
+            origin * SECTION_SIZE,
             size_in_chunks,
-        })
+        );
+        let sections: Vec<_> = palettes
+            .into_par_iter()
+            .map(|(i, palette, biomes)| {
+                let light = light_engine.section_light(section_position_at(i, size_in_chunks));
+                (i, palette, light, biomes)
+            })
+            .collect();
+
+        let mut block_entities =
+            vec![Vec::new(); (size_in_chunks.x() * size_in_chunks.z()) as usize];
+        if let Some(schematic) = schematic {
+            let dimensions = schematic.get_dimensions();
+            for block_entity in schematic.get_block_entities() {
+                let position = block_entity.position();
+                let is_inside = (0..dimensions.x()).contains(&position.x())
+                    && (0..dimensions.y()).contains(&position.y())
+                    && (0..dimensions.z()).contains(&position.z());
+                if is_inside {
+                    let block_entity = block_entity.clone().translate(schematic.get_world_origin());
+                    let position = block_entity.position();
+                    let chunk_x = position.x().div_euclid(SECTION_SIZE) - origin.x();
+                    let chunk_z = position.z().div_euclid(SECTION_SIZE) - origin.z();
+                    let index = chunk_x * size_in_chunks.z() + chunk_z;
+                    block_entities[index as usize].push(block_entity);
+                }
+            }
+        }
+
+        Ok(Self::from_sections(
+            origin,
+            size_in_chunks,
+            sections,
+            block_entities,
+        ))
+    }
+
+    /// Stores the given sections, sharing the palette, light and biomes of identical sections.
+    pub(crate) fn from_sections(
+        origin: Coordinates,
+        size_in_chunks: Coordinates,
+        sections: Vec<(i32, Palette, SectionLight, Option<SectionBiomes>)>,
+        block_entities: Vec<Vec<BlockEntity>>,
+    ) -> Self {
+        let mut palettes = HashSet::new();
+        let mut lights = HashSet::new();
+        let mut section_biomes = HashSet::new();
+        let sections = sections
+            .into_iter()
+            .map(|(index, palette, light, biomes)| {
+                let section = WorldSection {
+                    palette: intern(&mut palettes, palette),
+                    light: intern(&mut lights, light),
+                    biomes: biomes.map(|biomes| intern(&mut section_biomes, biomes)),
+                };
+                (index, section)
+            })
+            .collect();
+        let mut biome_names: Vec<String> = section_biomes
+            .iter()
+            .flat_map(|biomes| biomes.palette.iter())
+            .filter(|name| !name.is_empty())
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        biome_names.sort_unstable();
+        Self {
+            sections,
+            block_entities,
+            origin,
+            size_in_chunks,
+            biome_names,
+        }
+    }
+
+    pub fn memory_stats(&self) -> WorldMemoryStats {
+        let mut palettes = HashSet::new();
+        let mut lights = HashSet::new();
+        let mut palette_bytes = 0;
+        for section in self.sections.values() {
+            if palettes.insert(Arc::as_ptr(&section.palette)) {
+                palette_bytes += size_of::<Palette>() + section.palette.heap_size();
+            }
+            lights.insert(Arc::as_ptr(&section.light));
+        }
+        WorldMemoryStats {
+            bounding_sections: (self.size_in_chunks.x()
+                * self.size_in_chunks.y()
+                * self.size_in_chunks.z()) as usize,
+            stored_sections: self.sections.len(),
+            unique_palettes: palettes.len(),
+            unique_lights: lights.len(),
+            bytes: palette_bytes
+                + lights.len() * (size_of::<SectionLight>() + NIBBLE_ARRAY_SIZE * 2)
+                + self.sections.capacity() * size_of::<(i32, WorldSection)>(),
+        }
+    }
+
+    /// Returns the block entities of a chunk column, positioned in world coordinates.
+    pub fn get_block_entities(&self, chunk_x: i32, chunk_z: i32) -> &[BlockEntity] {
+        let chunk_x = chunk_x - self.origin.x();
+        let chunk_z = chunk_z - self.origin.z();
+        if chunk_x < 0
+            || chunk_x >= self.size_in_chunks.x()
+            || chunk_z < 0
+            || chunk_z >= self.size_in_chunks.z()
+        {
+            return &[];
+        }
+        &self.block_entities[(chunk_x * self.size_in_chunks.z() + chunk_z) as usize]
     }
 
+    /// Returns `None` for sections made only of air.
     pub fn get_section(&self, chunk_coords: &Coordinates) -> Option<&Palette> {
+        self.section_index(chunk_coords)
+            .and_then(|index| self.sections.get(&index))
+            .map(|section| section.palette.as_ref())
+    }
+
+    /// Returns the light computed when the world was loaded for the given section.
+    pub fn get_section_light(&self, chunk_coords: &Coordinates) -> Option<&SectionLight> {
+        self.section_index(chunk_coords)
+            .and_then(|index| self.sections.get(&index))
+            .map(|section| section.light.as_ref())
+    }
+
+    /// Returns `None` for sections using the biome of the world.
+    pub fn get_section_biomes(&self, chunk_coords: &Coordinates) -> Option<&SectionBiomes> {
+        self.section_index(chunk_coords)
+            .and_then(|index| self.sections.get(&index))
+            .and_then(|section| section.biomes.as_deref())
+    }
+
+    /// Names of the biomes stored in the sections, such as `minecraft:plains`.
+    pub fn biome_names(&self) -> &[String] {
+        &self.biome_names
+    }
+
+    fn section_index(&self, chunk_coords: &Coordinates) -> Option<i32> {
+        let chunk_coords = *chunk_coords - self.origin;
         if chunk_coords.x() < 0
             || chunk_coords.x() >= self.size_in_chunks.x()
             || chunk_coords.y() < 0
@@ -58,6 +409,145 @@ impl World {
             + (chunk_coords.y() * self.size_in_chunks.z())
             + (chunk_coords.x() * self.size_in_chunks.y() * self.size_in_chunks.z());
 
-        self.world_sections.get(index as usize)
+        Some(index)
+    }
+}
+
+/// Returns the shared copy of the value, adding it to the set if it is the first one.
+fn intern<T: Eq + Hash>(values: &mut HashSet<Arc<T>>, value: T) -> Arc<T> {
+    if let Some(existing) = values.get(&value) {
+        return Arc::clone(existing);
+    }
+    let value = Arc::new(value);
+    values.insert(Arc::clone(&value));
+    value
+}
+
+/// Returns the coordinates of the section stored at the given index.
+fn section_position_at(index: i32, size_in_chunks: Coordinates) -> Coordinates {
+    let chunk_x = index / (size_in_chunks.y() * size_in_chunks.z());
+    let chunk_y = (index / size_in_chunks.z()) % size_in_chunks.y();
+    let chunk_z = index % size_in_chunks.z();
+
+    Coordinates::new(chunk_x, chunk_y, chunk_z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_blocks::TestBlocks;
+
+    #[test]
+    fn test_generated_layers_extend_the_schematic() {
+        // Given
+        let blocks = TestBlocks::new();
+        let schematic = blocks.load("sponge_v3.schem").unwrap();
+        let generator = FlatGenerator::superflat(vec![("minecraft:stone".to_string(), 16)], -16, 1);
+
+        // When
+        let world = World::generate(&generator, Some(&schematic), &blocks.lookup()).unwrap();
+
+        // Then
+        assert!(matches!(
+            world.get_section(&Coordinates::new(-1, -1, 1)),
+            Some(Palette::Single {
+                internal_id: TestBlocks::STONE
+            })
+        ));
+        assert!(matches!(
+            world.get_section(&Coordinates::new(0, 0, 0)),
+            Some(Palette::Paletted { .. })
+        ));
+        assert!(world.get_section(&Coordinates::new(2, -1, 0)).is_none());
+        assert!(world.get_section(&Coordinates::new(0, 1, 0)).is_none());
+        assert_eq!(world.get_block_entities(0, 0).len(), 1);
+        assert!(world.get_block_entities(-1, 0).is_empty());
+    }
+
+    #[test]
+    fn test_air_sections_are_left_out_and_identical_sections_shared() {
+        // Given
+        let blocks = TestBlocks::new();
+        let schematic = blocks.load("sponge_v3.schem").unwrap();
+        let generator = FlatGenerator::superflat(vec![("minecraft:stone".to_string(), 16)], -16, 1);
+
+        // When
+        let world = World::generate(&generator, Some(&schematic), &blocks.lookup()).unwrap();
+        let stats = world.memory_stats();
+
+        // Then
+        assert!(world.get_section(&Coordinates::new(1, 0, 1)).is_none());
+        assert_eq!(stats.bounding_sections, 18);
+        assert_eq!(stats.stored_sections, 10);
+        assert_eq!(stats.unique_palettes, 2);
+        assert!(stats.unique_lights <= 2);
+    }
+
+    #[test]
+    fn test_biomes_of_the_schematic_are_sampled_per_cell() {
+        // Given
+        let blocks = TestBlocks::new();
+        let schematic = blocks.load("sponge_v3.schem").unwrap();
+        let generator = FlatGenerator::superflat(vec![("minecraft:stone".to_string(), 16)], -16, 1);
+
+        // When
+        let world = World::generate(&generator, Some(&schematic), &blocks.lookup()).unwrap();
+
+        // Then
+        let biomes = world
+            .get_section_biomes(&Coordinates::new(0, 0, 0))
+            .unwrap();
+        let biome_at = |cell: usize| biomes.palette()[biomes.cells()[cell] as usize].as_str();
+        assert_eq!(biomes.cells().len(), SectionBiomes::CELL_COUNT);
+        assert_eq!(
+            Some(biome_at(0)),
+            schematic.get_biome(Coordinates::new_uniform(0))
+        );
+        // Cells outside of the schematic use the biome of the world
+        assert_eq!(biome_at(1), "");
+        assert_eq!(world.biome_names(), [biome_at(0)]);
+        assert!(
+            world
+                .get_section_biomes(&Coordinates::new(0, -1, 0))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_schematic_is_placed_at_its_world_origin() {
+        // Given
+        let blocks = TestBlocks::new();
+        let schematic = Schematic::from_blocks(
+            vec![TestBlocks::STONE],
+            Coordinates::new_uniform(1),
+            Coordinates::new(40, 0, 40),
+            TestBlocks::AIR,
+        )
+        .with_world_origin(Coordinates::new(-20, 5, 3));
+
+        // When
+        let world = World::from_schematic(&schematic, &blocks.lookup()).unwrap();
+
+        // Then
+        assert!(world.get_section(&Coordinates::new(-2, 0, 0)).is_some());
+        assert!(world.get_section(&Coordinates::new(0, 0, 0)).is_none());
+        assert_eq!(world.memory_stats().bounding_sections, 1);
+    }
+
+    #[test]
+    fn test_unknown_generator_block() {
+        // Given
+        let blocks = TestBlocks::new();
+        let generator = FlatGenerator::platform("minecraft:dirt".to_string(), 5, 64);
+
+        // When
+        let error = World::generate(&generator, None, &blocks.lookup())
+            .err()
+            .unwrap();
+
+        // Then
+        assert!(
+            matches!(error, WorldLoadingError::UnknownGeneratorBlock(block) if block == "minecraft:dirt")
+        );
     }
 }
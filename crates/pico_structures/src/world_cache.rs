@@ -0,0 +1,518 @@
+use crate::anvil_world::AnvilArea;
+use crate::block_entity::BlockEntity;
+use crate::generator::FlatGenerator;
+use crate::light_engine::SectionLight;
+use crate::palette::Palette;
+use crate::placement::SchematicPlacement;
+use crate::world::{SectionBiomes, World};
+use blocks_report::InternalId;
+use minecraft_protocol::prelude::*;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+use thiserror::Error;
+
+const MAGIC: &[u8] = b"PLWC";
+/// Incremented whenever the layout of the cache changes.
+const FORMAT_VERSION: u8 = 6;
+const KEY_SIZE: usize = 32;
+const HEADER_SIZE: usize = MAGIC.len() + 1 + KEY_SIZE;
+const NIBBLE_ARRAY_SIZE: usize = 2048;
+/// LZ4 cannot compress better than this, a larger size prefix means the cache is corrupted.
+const MAX_COMPRESSION_RATIO: usize = 255;
+
+#[derive(Debug, Error)]
+pub enum WorldCacheError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    BinaryReader(#[from] BinaryReaderError),
+    #[error(transparent)]
+    BinaryWriter(#[from] BinaryWriterError),
+    #[error(transparent)]
+    Nbt(#[from] NbtDecodeError),
+    #[error("invalid compressed data: {0}")]
+    Decompress(#[from] lz4_flex::block::DecompressError),
+    #[error("corrupted world cache: {0}")]
+    Corrupted(&'static str),
+}
+
+/// Content hash of everything a world is built from.
+/// A cache whose key differs was built from other files or by another version of the server.
+pub struct WorldCacheKey {
+    hasher: Sha256,
+}
+
+impl WorldCacheKey {
+    pub fn new(block_data_version: u64) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(block_data_version.to_le_bytes());
+        Self { hasher }
+    }
+
+    /// Adds the content of a schematic file and where it is placed.
+    pub fn schematic(&mut self, placement: &SchematicPlacement) -> io::Result<()> {
+        self.hasher.update(b"schematic");
+        self.bytes(&fs::read(&placement.path)?);
+        self.coordinates(placement.origin);
+        self.hasher
+            .update([placement.rotation as u8, placement.mirror as u8]);
+        self.hasher.update((placement.palette as u64).to_le_bytes());
+        Ok(())
+    }
+
+    /// Adds the content of the region files holding the area.
+    pub fn anvil_world(&mut self, world_folder: &Path, area: &AnvilArea) -> io::Result<()> {
+        self.hasher.update(b"anvil_world");
+        for corner in [area.min_chunk, area.max_chunk] {
+            self.hasher.update(corner.0.to_le_bytes());
+            self.hasher.update(corner.1.to_le_bytes());
+        }
+        for region_file in area.region_files(world_folder) {
+            match fs::read(region_file) {
+                Ok(bytes) => self.bytes(&bytes),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => self.bytes(&[]),
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn generator(&mut self, generator: &FlatGenerator) {
+        self.hasher.update(b"generator");
+        for (block, thickness) in &generator.layers {
+            self.bytes(block.as_bytes());
+            self.hasher.update(thickness.to_le_bytes());
+        }
+        self.hasher.update(generator.min_y.to_le_bytes());
+        for corner in [generator.min_corner, generator.max_corner] {
+            self.hasher.update(corner.0.to_le_bytes());
+            self.hasher.update(corner.1.to_le_bytes());
+        }
+    }
+
+    /// Bytes are prefixed with their length, so that consecutive values cannot be confused.
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.hasher.update((bytes.len() as u64).to_le_bytes());
+        self.hasher.update(bytes);
+    }
+
+    fn coordinates(&mut self, coordinates: Coordinates) {
+        for value in [coordinates.x(), coordinates.y(), coordinates.z()] {
+            self.hasher.update(value.to_le_bytes());
+        }
+    }
+
+    fn finish(self) -> [u8; KEY_SIZE] {
+        self.hasher.finalize().into()
+    }
+}
+
+/// A loaded world saved to disk, so that the next start does not need to load its sources again.
+///
+/// The file starts with a header holding the format version and the key of the sources,
+/// followed by the LZ4 compressed sections, light and block entities of the world.
+pub struct WorldCache {
+    path: PathBuf,
+    key: [u8; KEY_SIZE],
+}
+
+impl WorldCache {
+    pub fn new(path: impl Into<PathBuf>, key: WorldCacheKey) -> Self {
+        Self {
+            path: path.into(),
+            key: key.finish(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns `None` when the cache does not exist yet, or was built from other sources.
+    pub fn load(&self) -> Result<Option<World>, WorldCacheError> {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        if bytes.len() < HEADER_SIZE || bytes[..HEADER_SIZE] != self.header() {
+            return Ok(None);
+        }
+
+        let Some(body) = Self::decompress(&bytes[HEADER_SIZE..])? else {
+            return Ok(None);
+        };
+        let mut reader = BinaryReader::new(&body);
+        let cached_world = CachedWorld::decode(&mut reader, ProtocolVersion::latest())?;
+        cached_world.into_world().map(Some)
+    }
+
+    /// Replaces the cache with the given world.
+    pub fn save(&self, world: &World) -> Result<(), WorldCacheError> {
+        let mut writer = BinaryWriter::new();
+        CachedWorld::from_world(world)?.encode(&mut writer, ProtocolVersion::latest())?;
+
+        let mut bytes = self.header();
+        bytes.extend(lz4_flex::block::compress_prepend_size(writer.as_slice()));
+
+        // Written aside then renamed, so that a crash never leaves a truncated cache behind
+        let mut temporary_path = self.path.clone().into_os_string();
+        temporary_path.push(".tmp");
+        fs::write(&temporary_path, bytes)?;
+        fs::rename(&temporary_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Returns `None` when the size prefix is more than the compressed data can hold,
+    /// instead of allocating it.
+    fn decompress(compressed: &[u8]) -> Result<Option<Vec<u8>>, WorldCacheError> {
+        let Some((size, data)) = compressed.split_first_chunk() else {
+            return Ok(None);
+        };
+        let size = u32::from_le_bytes(*size) as usize;
+        if size > data.len().saturating_mul(MAX_COMPRESSION_RATIO) {
+            return Ok(None);
+        }
+        Ok(Some(lz4_flex::block::decompress(data, size)?))
+    }
+
+    fn header(&self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+        header.extend(MAGIC);
+        header.push(FORMAT_VERSION);
+        header.extend(self.key);
+        header
+    }
+}
+
+#[derive(PacketOut, PacketIn)]
+struct CachedWorld {
+    origin: CachedCoordinates,
+    size_in_chunks: CachedCoordinates,
+    sections: LengthPaddedVec<CachedSection>,
+    block_entities: LengthPaddedVec<CachedBlockEntity>,
+}
+
+#[derive(PacketOut, PacketIn)]
+struct CachedCoordinates {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+/// Sections of air without biomes are not stored.
+#[derive(PacketOut, PacketIn)]
+struct CachedSection {
+    /// Index of the section in the bounding box of the world.
+    index: i32,
+    /// 0 for a single block, 1 for a paletted section and 2 for direct IDs.
+    kind: u8,
+    bits_per_entry: u8,
+    /// The single block, the palette or every block of the section.
+    internal_ids: LengthPaddedVec<InternalId>,
+    packed_data: LengthPaddedVec<u64>,
+    sky_light: LengthPaddedVec<u8>,
+    block_light: LengthPaddedVec<u8>,
+    /// Empty when the section uses the biome of the world.
+    biome_palette: LengthPaddedVec<String>,
+    biome_cells: LengthPaddedVec<u8>,
+}
+
+#[derive(PacketOut, PacketIn)]
+struct CachedBlockEntity {
+    position: CachedCoordinates,
+    id: String,
+    /// The tags of the block entity, as an NBT compound.
+    data: LengthPaddedVec<u8>,
+}
+
+impl CachedWorld {
+    const SINGLE: u8 = 0;
+    const PALETTED: u8 = 1;
+    const DIRECT: u8 = 2;
+
+    fn from_world(world: &World) -> Result<Self, WorldCacheError> {
+        let mut stored_sections: Vec<_> = world.sections.iter().collect();
+        stored_sections.sort_unstable_by_key(|(index, _)| **index);
+        let sections = stored_sections
+            .into_iter()
+            .map(|(index, section)| {
+                let light = &section.light;
+                let (biome_palette, biome_cells) = section
+                    .biomes
+                    .as_ref()
+                    .map(|biomes| (biomes.palette.clone(), biomes.cells.clone()))
+                    .unwrap_or_default();
+                let (kind, bits_per_entry, internal_ids, packed_data) =
+                    match section.palette.as_ref() {
+                        Palette::Single { internal_id } => {
+                            (Self::SINGLE, 0, vec![*internal_id], vec![])
+                        }
+                        Palette::Paletted {
+                            bits_per_entry,
+                            internal_palette,
+                            packed_data,
+                        } => (
+                            Self::PALETTED,
+                            *bits_per_entry,
+                            internal_palette.clone(),
+                            packed_data.clone(),
+                        ),
+                        Palette::Direct { internal_data } => {
+                            (Self::DIRECT, 0, internal_data.clone(), vec![])
+                        }
+                    };
+                CachedSection {
+                    index: *index,
+                    kind,
+                    bits_per_entry,
+                    internal_ids: LengthPaddedVec::new(internal_ids),
+                    packed_data: LengthPaddedVec::new(packed_data),
+                    sky_light: LengthPaddedVec::new(light.sky_light.clone()),
+                    block_light: LengthPaddedVec::new(light.block_light.clone()),
+                    biome_palette: LengthPaddedVec::new(biome_palette),
+                    biome_cells: LengthPaddedVec::new(biome_cells),
+                }
+            })
+            .collect();
+
+        let mut block_entities = Vec::new();
+        for block_entity in world.block_entities.iter().flatten() {
+            let data = Nbt::compound("", block_entity.data().to_vec())
+                .to_bytes(NbtFeatures::builder().build())?;
+            block_entities.push(CachedBlockEntity {
+                position: block_entity.position().into(),
+                id: block_entity.id().to_string(),
+                data: LengthPaddedVec::new(data),
+            });
+        }
+
+        Ok(Self {
+            origin: world.origin.into(),
+            size_in_chunks: world.size_in_chunks.into(),
+            sections: LengthPaddedVec::new(sections),
+            block_entities: LengthPaddedVec::new(block_entities),
+        })
+    }
+
+    fn into_world(self) -> Result<World, WorldCacheError> {
+        let origin = Coordinates::from(self.origin);
+        let size_in_chunks = Coordinates::from(self.size_in_chunks);
+        let sizes = [size_in_chunks.x(), size_in_chunks.y(), size_in_chunks.z()];
+        if sizes.iter().any(|size| *size < 0) {
+            return Err(WorldCacheError::Corrupted("negative world size"));
+        }
+        let section_count = sizes.iter().product::<i32>();
+
+        let mut sections = Vec::new();
+        for section in self.sections.into_inner() {
+            if !(0..section_count).contains(&section.index) {
+                return Err(WorldCacheError::Corrupted("section outside of the world"));
+            }
+            let internal_ids = section.internal_ids.into_inner();
+            let palette = match section.kind {
+                Self::SINGLE => {
+                    let [internal_id] = internal_ids[..] else {
+                        return Err(WorldCacheError::Corrupted("single block section"));
+                    };
+                    Palette::single(internal_id)
+                }
+                Self::PALETTED => Palette::paletted(
+                    section.bits_per_entry,
+                    internal_ids,
+                    section.packed_data.into_inner(),
+                ),
+                Self::DIRECT => Palette::direct(internal_ids),
+                _ => return Err(WorldCacheError::Corrupted("unknown section kind")),
+            };
+            let sky_light = section.sky_light.into_inner();
+            let block_light = section.block_light.into_inner();
+            if sky_light.len() != NIBBLE_ARRAY_SIZE || block_light.len() != NIBBLE_ARRAY_SIZE {
+                return Err(WorldCacheError::Corrupted("section light"));
+            }
+            let light = SectionLight {
+                sky_light,
+                block_light,
+            };
+            let biome_palette = section.biome_palette.into_inner();
+            let biomes = if biome_palette.is_empty() {
+                None
+            } else {
+                let cells = section.biome_cells.into_inner();
+                let has_valid_cells = cells.len() == SectionBiomes::CELL_COUNT
+                    && cells
+                        .iter()
+                        .all(|index| usize::from(*index) < biome_palette.len());
+                if !has_valid_cells {
+                    return Err(WorldCacheError::Corrupted("section biomes"));
+                }
+                Some(SectionBiomes {
+                    palette: biome_palette,
+                    cells,
+                })
+            };
+            sections.push((section.index, palette, light, biomes));
+        }
+
+        let mut block_entities =
+            vec![Vec::new(); (size_in_chunks.x() * size_in_chunks.z()) as usize];
+        for block_entity in self.block_entities.into_inner() {
+            let position = Coordinates::from(block_entity.position);
+            let chunk_x = position.x().div_euclid(16) - origin.x();
+            let chunk_z = position.z().div_euclid(16) - origin.z();
+            if !(0..size_in_chunks.x()).contains(&chunk_x)
+                || !(0..size_in_chunks.z()).contains(&chunk_z)
+            {
+                return Err(WorldCacheError::Corrupted(
+                    "block entity outside of the world",
+                ));
+            }
+            let data = Nbt::from_bytes(block_entity.data.inner())?
+                .get_nbt_vec()
+                .ok_or(WorldCacheError::Corrupted("block entity data"))?;
+            let index = chunk_x * size_in_chunks.z() + chunk_z;
+            block_entities[index as usize].push(BlockEntity::new(position, block_entity.id, data));
+        }
+
+        Ok(World::from_sections(
+            origin,
+            size_in_chunks,
+            sections,
+            block_entities,
+        ))
+    }
+}
+
+impl From<Coordinates> for CachedCoordinates {
+    fn from(value: Coordinates) -> Self {
+        Self {
+            x: value.x(),
+            y: value.y(),
+            z: value.z(),
+        }
+    }
+}
+
+impl From<CachedCoordinates> for Coordinates {
+    fn from(value: CachedCoordinates) -> Self {
+        Self::new(value.x, value.y, value.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_blocks::TestBlocks;
+
+    fn cache_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("pico_world_cache_{}_{name}", std::process::id()))
+    }
+
+    fn sample_world(blocks: &TestBlocks) -> World {
+        let schematic = blocks.load("sponge_v3.schem").unwrap();
+        let generator = FlatGenerator::platform("minecraft:red_wool".to_string(), 40, -1);
+        World::generate(&generator, Some(&schematic), &blocks.lookup()).unwrap()
+    }
+
+    #[test]
+    fn test_world_round_trip() {
+        // Given
+        let blocks = TestBlocks::new();
+        let world = sample_world(&blocks);
+        let cache = WorldCache::new(cache_path("round_trip"), WorldCacheKey::new(1));
+
+        // When
+        cache.save(&world).unwrap();
+        let loaded = cache.load().unwrap().unwrap();
+        fs::remove_file(cache.path()).unwrap();
+
+        // Then
+        assert_eq!(loaded.sections.len(), world.sections.len());
+        for (index, section) in &world.sections {
+            let loaded_section = &loaded.sections[index];
+            assert!(loaded_section.palette == section.palette);
+            assert!(loaded_section.light == section.light);
+        }
+        let [sign] = loaded.get_block_entities(0, 0) else {
+            panic!("expected a single block entity");
+        };
+        assert_eq!(sign.id(), "minecraft:sign");
+        assert_eq!(sign.data().len(), 2);
+    }
+
+    #[test]
+    fn test_stale_cache_is_ignored() {
+        // Given
+        let blocks = TestBlocks::new();
+        let path = cache_path("stale");
+        WorldCache::new(&path, WorldCacheKey::new(1))
+            .save(&sample_world(&blocks))
+            .unwrap();
+        let mut key = WorldCacheKey::new(1);
+        key.generator(&FlatGenerator::platform(
+            "minecraft:stone".to_string(),
+            3,
+            0,
+        ));
+
+        // When
+        let loaded = WorldCache::new(&path, key).load().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // Then
+        assert!(loaded.is_none());
+        assert!(
+            WorldCache::new(cache_path("missing"), WorldCacheKey::new(1))
+                .load()
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_implausible_size_is_a_cache_miss() {
+        // Given
+        let cache = WorldCache::new(cache_path("implausible_size"), WorldCacheKey::new(1));
+        let mut bytes = cache.header();
+        bytes.extend(u32::MAX.to_le_bytes());
+        bytes.extend([0; 8]);
+        fs::write(cache.path(), bytes).unwrap();
+
+        // When
+        let loaded = cache.load().unwrap();
+        fs::remove_file(cache.path()).unwrap();
+
+        // Then
+        assert!(loaded.is_none());
+        let highly_compressible = vec![0; 1 << 20];
+        let compressed = lz4_flex::block::compress_prepend_size(&highly_compressible);
+        assert_eq!(
+            WorldCache::decompress(&compressed).unwrap(),
+            Some(highly_compressible)
+        );
+    }
+
+    #[test]
+    fn test_palette_is_part_of_the_key() {
+        // Given
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("test_data")
+            .join("sponge_v3.schem");
+        let keys = [0, 1].map(|palette| {
+            let mut key = WorldCacheKey::new(1);
+            key.schematic(&SchematicPlacement {
+                palette,
+                ..SchematicPlacement::at_world_origin(&path)
+            })
+            .unwrap();
+            key
+        });
+
+        // When
+        let [first, second] = keys.map(WorldCacheKey::finish);
+
+        // Then
+        assert_ne!(first, second);
+    }
+}
@@ -44,6 +44,20 @@ impl Component {
         }
     }
 
+    /// Returns a copy of this component with every occurrence of `placeholder` replaced by `value`.
+    pub fn replace(&self, placeholder: &str, value: &str) -> Self {
+        Self {
+            text: self.text.replace(placeholder, value),
+            color: self.color.clone(),
+            extra: self
+                .extra
+                .iter()
+                .map(|extra| extra.replace(placeholder, value))
+                .collect(),
+            ..*self
+        }
+    }
+
     pub fn to_json(&self) -> String {
         serde_json::to_string(&self).unwrap_or_default()
     }
@@ -101,3 +115,25 @@ impl EncodePacket for Component {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_nested_placeholder() {
+        // Given
+        let component = Component {
+            text: "Hello {name}".to_string(),
+            extra: vec![Component::new("Bye {name}")],
+            ..Default::default()
+        };
+
+        // When
+        let replaced = component.replace("{name}", "Steve");
+
+        // Then
+        assert_eq!(replaced.text, "Hello Steve");
+        assert_eq!(replaced.extra[0].text, "Bye Steve");
+    }
+}
@@ -94,7 +94,50 @@ pub enum ProtocolVersion {
     Any = -1,
 }
 
+/// Releases sharing the protocol version of an earlier release.
+const PATCH_RELEASES: &[(&str, ProtocolVersion)] = &[
+    ("1.7.4", ProtocolVersion::V1_7_2),
+    ("1.7.5", ProtocolVersion::V1_7_2),
+    ("1.7.7", ProtocolVersion::V1_7_6),
+    ("1.7.8", ProtocolVersion::V1_7_6),
+    ("1.7.9", ProtocolVersion::V1_7_6),
+    ("1.7.10", ProtocolVersion::V1_7_6),
+    ("1.8.1", ProtocolVersion::V1_8),
+    ("1.8.2", ProtocolVersion::V1_8),
+    ("1.8.3", ProtocolVersion::V1_8),
+    ("1.8.4", ProtocolVersion::V1_8),
+    ("1.8.5", ProtocolVersion::V1_8),
+    ("1.8.6", ProtocolVersion::V1_8),
+    ("1.8.7", ProtocolVersion::V1_8),
+    ("1.8.8", ProtocolVersion::V1_8),
+    ("1.8.9", ProtocolVersion::V1_8),
+    ("1.9.4", ProtocolVersion::V1_9_3),
+    ("1.10.1", ProtocolVersion::V1_10),
+    ("1.10.2", ProtocolVersion::V1_10),
+    ("1.11.2", ProtocolVersion::V1_11_1),
+    ("1.16.5", ProtocolVersion::V1_16_4),
+    ("1.18.1", ProtocolVersion::V1_18),
+    ("1.19.2", ProtocolVersion::V1_19_1),
+    ("1.20.1", ProtocolVersion::V1_20),
+    ("1.20.4", ProtocolVersion::V1_20_3),
+    ("1.20.6", ProtocolVersion::V1_20_5),
+    ("1.21.1", ProtocolVersion::V1_21),
+    ("1.21.3", ProtocolVersion::V1_21_2),
+    ("1.21.8", ProtocolVersion::V1_21_7),
+];
+
 impl ProtocolVersion {
+    /// Parses any release (e.g., "1.8.9"), including the ones sharing the protocol version
+    /// of an earlier release.
+    pub fn from_release(release: &str) -> Option<Self> {
+        Self::from_humanized(release).or_else(|| {
+            PATCH_RELEASES
+                .iter()
+                .find(|(patch_release, _)| *patch_release == release)
+                .map(|(_, protocol_version)| *protocol_version)
+        })
+    }
+
     #[inline]
     pub fn between_inclusive(&self, min_version: Self, max_version: Self) -> bool {
         self >= &min_version && self <= &max_version
@@ -151,4 +194,26 @@ mod tests {
         assert_eq!(v1_7_6.reports(), v1_7_2);
         assert_eq!(v1_7_2.reports(), v1_7_2);
     }
+
+    #[test]
+    fn test_from_release() {
+        // Given
+        let releases = ["1.8", "1.8.9", "1.20.4", "1.21.1", "1.21.4", "1.22"];
+
+        // When
+        let protocol_versions = releases.map(ProtocolVersion::from_release);
+
+        // Then
+        assert_eq!(
+            protocol_versions,
+            [
+                Some(ProtocolVersion::V1_8),
+                Some(ProtocolVersion::V1_8),
+                Some(ProtocolVersion::V1_20_3),
+                Some(ProtocolVersion::V1_21),
+                Some(ProtocolVersion::V1_21_4),
+                None,
+            ]
+        );
+    }
 }
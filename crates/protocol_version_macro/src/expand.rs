@@ -73,6 +73,18 @@ pub fn expand_protocol_version_derive(input: TokenStream) -> TokenStream {
         quote! { #enum_ident::#variant_ident => #humanized_lit }
     });
 
+    let from_version_number_arms = parsed_variants.iter().map(|v| {
+        let variant_ident = v.ident;
+        let discriminant_expr = v.discriminant_expr;
+        quote! { #discriminant_expr => Some(#enum_ident::#variant_ident) }
+    });
+
+    let from_humanized_arms = parsed_variants.iter().map(|v| {
+        let variant_ident = v.ident;
+        let humanized_lit = &v.humanized_string;
+        quote! { #humanized_lit => Some(#enum_ident::#variant_ident) }
+    });
+
     let from_str_arms = parsed_variants.iter().map(|v| {
         let variant_ident = v.ident;
         let variant_string = v.ident.to_string();
@@ -133,6 +145,22 @@ pub fn expand_protocol_version_derive(input: TokenStream) -> TokenStream {
                 *self as i32
             }
 
+            /// Returns the variant matching exactly the given protocol version number, if any.
+            pub fn from_version_number(value: i32) -> Option<Self> {
+                match value {
+                    #(#from_version_number_arms),*,
+                    _ => None,
+                }
+            }
+
+            /// Parses a human-readable version string (e.g., "1.18.2") into its variant, if any.
+            pub fn from_humanized(s: &str) -> Option<Self> {
+                match s {
+                    #(#from_humanized_arms),*,
+                    _ => None,
+                }
+            }
+
             /// Returns the human-readable version string (e.g., "1.18.2").
             pub fn humanize(&self) -> &'static str {
                 match self { #(#humanize_arms),* }
@@ -35,4 +35,32 @@ mod tests {
         // Then
         assert_eq!(result, expected_version_number);
     }
+
+    #[test]
+    fn test_from_unknown_version_number() {
+        // Given
+        let given_number = 768;
+        let unknown_number = 770;
+
+        // When
+        let known = ProtocolVersion::from_version_number(given_number);
+        let unknown = ProtocolVersion::from_version_number(unknown_number);
+
+        // Then
+        assert_eq!(known, Some(ProtocolVersion::V1_21_2));
+        assert_eq!(unknown, None);
+    }
+
+    #[test]
+    fn test_from_humanized() {
+        // Given
+        let given_string = "1.21.2";
+
+        // When
+        let result = ProtocolVersion::from_humanized(given_string);
+
+        // Then
+        assert_eq!(result, Some(ProtocolVersion::V1_21_2));
+        assert_eq!(ProtocolVersion::from_humanized("1.21.3"), None);
+    }
 }
@@ -145,6 +145,17 @@ fn get_grouped_registries(
         })
 }
 
+/// Names of the biomes, in the order of their network IDs since 1.16.2
+pub fn get_biome_names(protocol_version: ProtocolVersion, data_location: &Path) -> Vec<String> {
+    let biome_registry = Identifier::minecraft("worldgen/biome");
+    get_grouped_registries(protocol_version, data_location)
+        .remove(&biome_registry)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(entry_id, _)| entry_id.thing)
+        .collect()
+}
+
 /// Way to get registries since 1.16.2 up until 1.20.3
 pub fn get_v1_16_2_registry_codec(protocol_version: ProtocolVersion, data_location: &Path) -> Nbt {
     let grouped = get_grouped_registries(protocol_version, data_location)
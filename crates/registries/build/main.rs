@@ -2,7 +2,8 @@ mod get_all_registries;
 mod registries_indexes;
 
 use crate::get_all_registries::{
-    encode, get_v1_16_2_registry_codec, get_v1_16_registry_codec, get_v1_20_5_registries,
+    encode, get_biome_names, get_v1_16_2_registry_codec, get_v1_16_registry_codec,
+    get_v1_20_5_registries,
 };
 use crate::registries_indexes::{get_dimension_type_index, get_the_void_index};
 use minecraft_protocol::prelude::{Dimension, Nbt, ProtocolVersion};
@@ -50,6 +51,7 @@ fn main() -> anyhow::Result<()> {
     let mut registries_arms = Vec::new();
     let mut dimensions_arms = Vec::new();
     let mut void_biome_arms = Vec::new();
+    let mut biome_arms = Vec::new();
 
     for &protocol_version in &canonical_versions {
         let registry_format = RegistryFormat::from_version(protocol_version);
@@ -226,12 +228,27 @@ fn main() -> anyhow::Result<()> {
             dimensions_arms.push(arm);
         }
 
-        if protocol_version.is_after_inclusive(ProtocolVersion::V1_19) {
-            let void_biome_index = get_the_void_index(protocol_version, &data_location);
+        // Prior to 1.16.2, biomes are not sent by the server and use their hardcoded IDs
+        let void_biome_index = get_the_void_index(protocol_version, &data_location);
+        let arm = quote! {
+            ProtocolVersion::#version_ident => { Some(#void_biome_index) },
+        };
+        void_biome_arms.push(arm);
+
+        if protocol_version.is_after_inclusive(ProtocolVersion::V1_16_2) {
+            let biome_match_arms = get_biome_names(protocol_version, &data_location)
+                .into_iter()
+                .enumerate()
+                .map(|(index, biome)| quote! { #biome => Some(#index), });
             let arm = quote! {
-                ProtocolVersion::#version_ident => { Some(#void_biome_index) },
+                ProtocolVersion::#version_ident => {
+                    match biome {
+                        #(#biome_match_arms)*
+                        _ => None,
+                    }
+                },
             };
-            void_biome_arms.push(arm);
+            biome_arms.push(arm);
         }
     }
 
@@ -259,6 +276,14 @@ fn main() -> anyhow::Result<()> {
                 _ => None,
             }
         }
+
+        #[allow(clippy::match_same_arms)]
+        pub fn get_pregenerated_biome_index(protocol_version: minecraft_protocol::prelude::ProtocolVersion, biome: &str) -> Option<usize> {
+               match protocol_version {
+                #(#biome_arms)*
+                _ => None,
+            }
+        }
     };
 
     let dest_path = out_dir.join("generated_registries.rs");
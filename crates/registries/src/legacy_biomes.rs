@@ -0,0 +1,65 @@
+/// Hardcoded numeric IDs of the biomes known by clients prior to 1.16.2, by their current name.
+/// Biomes added later are missing, and shown as their closest legacy biome by newer clients only.
+const LEGACY_BIOME_IDS: [(&str, i32); 54] = [
+    ("ocean", 0),
+    ("plains", 1),
+    ("desert", 2),
+    ("windswept_hills", 3),
+    ("forest", 4),
+    ("taiga", 5),
+    ("swamp", 6),
+    ("river", 7),
+    ("nether_wastes", 8),
+    ("the_end", 9),
+    ("frozen_ocean", 10),
+    ("frozen_river", 11),
+    ("snowy_plains", 12),
+    ("mushroom_fields", 14),
+    ("beach", 16),
+    ("jungle", 21),
+    ("sparse_jungle", 23),
+    ("deep_ocean", 24),
+    ("stony_shore", 25),
+    ("snowy_beach", 26),
+    ("birch_forest", 27),
+    ("dark_forest", 29),
+    ("snowy_taiga", 30),
+    ("old_growth_pine_taiga", 32),
+    ("windswept_forest", 34),
+    ("savanna", 35),
+    ("savanna_plateau", 36),
+    ("badlands", 37),
+    ("wooded_badlands", 38),
+    ("small_end_islands", 40),
+    ("end_midlands", 41),
+    ("end_highlands", 42),
+    ("end_barrens", 43),
+    ("warm_ocean", 44),
+    ("lukewarm_ocean", 45),
+    ("cold_ocean", 46),
+    ("deep_warm_ocean", 47),
+    ("deep_lukewarm_ocean", 48),
+    ("deep_cold_ocean", 49),
+    ("deep_frozen_ocean", 50),
+    ("the_void", 127),
+    ("sunflower_plains", 129),
+    ("windswept_gravelly_hills", 131),
+    ("flower_forest", 132),
+    ("ice_spikes", 140),
+    ("old_growth_birch_forest", 155),
+    ("old_growth_spruce_taiga", 160),
+    ("windswept_savanna", 163),
+    ("eroded_badlands", 165),
+    ("bamboo_jungle", 168),
+    ("soul_sand_valley", 170),
+    ("crimson_forest", 171),
+    ("warped_forest", 172),
+    ("basalt_deltas", 173),
+];
+
+pub fn get_legacy_biome_id(biome: &str) -> Option<i32> {
+    LEGACY_BIOME_IDS
+        .iter()
+        .find(|(name, _)| *name == biome)
+        .map(|(_, id)| *id)
+}
@@ -1,3 +1,6 @@
+mod legacy_biomes;
+
+use crate::legacy_biomes::get_legacy_biome_id;
 use minecraft_protocol::prelude::*;
 pub use registries_data::grouped_registries::{
     V1_20_5Registries, V1_20_5RegistryEntries, V1_20_5RegistryEntry,
@@ -51,3 +54,14 @@ pub fn get_void_biome_index(protocol_version: ProtocolVersion) -> Option<i32> {
     }
     None
 }
+
+/// Returns the network ID of a biome, such as `minecraft:plains`, or `None` if the biome is unknown to the version.
+pub fn get_biome_index(protocol_version: ProtocolVersion, biome: &str) -> Option<i32> {
+    let biome = biome.strip_prefix("minecraft:").unwrap_or(biome);
+    // Prior to 1.16.2, biomes are not sent by the server and use their hardcoded IDs
+    if protocol_version.is_before_inclusive(ProtocolVersion::V1_16_1) {
+        return get_legacy_biome_id(biome);
+    }
+    let data_version = protocol_version.data();
+    get_pregenerated_biome_index(data_version, biome).and_then(|value| i32::try_from(value).ok())
+}
@@ -96,6 +96,7 @@ const SUPPORTED_VERSIONS = [
         if (await fileExists(wolfVariant)) {
             await cleanWolfVariants(wolfVariant);
         }
+        await extractBlockEntityTypes(reportsDirectory);
         await cleanReportsDirectory(reportsDirectory);
         await rm(generatedDirectory, { recursive: true, force: true });
     }
@@ -142,11 +143,31 @@ async function cleanWolfVariants(path: string): Promise<void> {
     }
 }
 
+async function extractBlockEntityTypes(path: string): Promise<void> {
+    // Block entity types are sent by ID in chunks, only their IDs are kept from the registries
+    const registries = JSON.parse(
+        await readFile(join(path, "registries.json"), "utf8"),
+    );
+    const entries: Record<string, { protocol_id: number }> =
+        registries["minecraft:block_entity_type"].entries;
+    const ids = Object.fromEntries(
+        Object.entries(entries).map(([name, entry]) => [
+            name,
+            entry.protocol_id,
+        ]),
+    );
+    await writeFile(
+        join(path, "block_entity_types.json"),
+        JSON.stringify(ids, null, 2),
+    );
+}
+
 async function cleanReportsDirectory(path: string): Promise<void> {
-    // Only keep the packets.json file
+    // Only keep the packets.json, blocks.json and block_entity_types.json files
+    const keptFiles = ["packets.json", "blocks.json", "block_entity_types.json"];
     const dir = await opendir(path);
     for await (const dirent of dir) {
-        if (dirent.name !== "packets.json" && dirent.name !== "blocks.json") {
+        if (!keptFiles.includes(dirent.name)) {
             const direntPath = join(path, dirent.name);
             await rm(direntPath, { recursive: true, force: true });
         }
@@ -1,6 +1,8 @@
 use crate::configuration::boss_bar::BossBarConfig;
 use crate::configuration::forwarding::ForwardingConfig;
 use crate::configuration::game_mode_config::GameModeConfig;
+use crate::configuration::messages::MessagesConfig;
+use crate::configuration::minecraft_version::MinecraftVersionConfig;
 use crate::configuration::server_list::ServerListConfig;
 use crate::configuration::tab_list::TabListConfig;
 use crate::configuration::world_config::WorldConfig;
@@ -53,6 +55,20 @@ pub struct Config {
     pub fetch_player_skins: bool,
 
     pub boss_bar: BossBarConfig,
+
+    /// Oldest Minecraft version allowed to join the server, e.g. "1.8"
+    pub min_version: MinecraftVersionConfig,
+
+    /// Latest Minecraft version allowed to join the server, e.g. "1.21.4"
+    pub max_version: MinecraftVersionConfig,
+
+    /// Maximum number of players connected at the same time, 0 means unlimited
+    pub player_limit: u32,
+
+    /// Players whose client brand matches one of these regular expressions are disconnected
+    pub blocked_brands: Vec<String>,
+
+    pub messages: MessagesConfig,
 }
 
 impl Default for Config {
@@ -68,6 +84,11 @@ impl Default for Config {
             tab_list: TabListConfig::default(),
             fetch_player_skins: false,
             boss_bar: BossBarConfig::default(),
+            min_version: MinecraftVersionConfig::oldest(),
+            max_version: MinecraftVersionConfig::latest(),
+            player_limit: 0,
+            blocked_brands: Vec::new(),
+            messages: MessagesConfig::default(),
         }
     }
 }
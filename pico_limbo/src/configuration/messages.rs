@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Messages generated by the server, all of them support `MiniMessage` formatting.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct MessagesConfig {
+    /// Sent when a player tries to join without going through the configured proxy.
+    pub proxy_required: String,
+
+    /// Sent when the proxy forwarded invalid player information.
+    pub forwarding_failed: String,
+
+    /// Sent to players older than 1.13 when modern forwarding is enabled.
+    pub modern_forwarding_not_supported: String,
+
+    /// Sent to players whose version is outside the allowed range.
+    /// `{min}` and `{max}` are replaced with the allowed versions.
+    pub unsupported_version: String,
+
+    /// Sent to players using a protocol version unknown to the server.
+    /// `{protocol}`, `{min}` and `{max}` are replaced with their values.
+    pub unknown_protocol: String,
+
+    /// Sent when the server reached its player limit.
+    pub server_full: String,
+
+    /// Sent to players whose client brand is blocked.
+    /// `{brand}` is replaced with the brand of the client.
+    pub brand_not_allowed: String,
+
+    /// Translated messages, keyed by client locale (e.g. `fr_fr` or `fr`).
+    /// Missing messages fall back to the default ones.
+    pub locales: HashMap<String, LocalizedMessagesConfig>,
+}
+
+impl Default for MessagesConfig {
+    fn default() -> Self {
+        Self {
+            proxy_required: "<red>You must connect through a proxy.</red>".into(),
+            forwarding_failed: "<red>Unable to verify your connection through the proxy.</red>"
+                .into(),
+            modern_forwarding_not_supported:
+                "<red>This server is only compatible with Minecraft 1.13 and above.</red>".into(),
+            unsupported_version: "<red>Your Minecraft version is not supported.</red><newline><gray>Please join using Minecraft {min} to {max}.</gray>".into(),
+            unknown_protocol: "<red>Unknown protocol version {protocol}.</red><newline><gray>Please join using Minecraft {min} to {max}.</gray>".into(),
+            server_full: "<red>The server is full.</red>".into(),
+            brand_not_allowed: "<red>Your client {brand} is not allowed on this server.</red>"
+                .into(),
+            locales: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct LocalizedMessagesConfig {
+    pub proxy_required: Option<String>,
+    pub forwarding_failed: Option<String>,
+    pub modern_forwarding_not_supported: Option<String>,
+    pub unsupported_version: Option<String>,
+    pub unknown_protocol: Option<String>,
+    pub server_full: Option<String>,
+    pub brand_not_allowed: Option<String>,
+    /// Overrides `welcome_message`
+    pub welcome: Option<String>,
+    /// Overrides `world.boundaries.teleport_message`
+    pub boundary_teleport: Option<String>,
+    /// Overrides `world.boundaries.ceiling_message`
+    pub boundary_ceiling_teleport: Option<String>,
+    /// Overrides `world.boundaries.horizontal_message`
+    pub boundary_horizontal_teleport: Option<String>,
+    /// Overrides `world.boundaries.kick_message`
+    pub boundary_kick: Option<String>,
+    /// Overrides `world.world_border.teleport_message`
+    pub world_border_teleport: Option<String>,
+}
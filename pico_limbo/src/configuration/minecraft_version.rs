@@ -0,0 +1,50 @@
+use minecraft_protocol::prelude::ProtocolVersion;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A Minecraft version written as a human-readable string, e.g. "1.8", "1.8.9" or "1.21.4".
+#[derive(Clone, Copy)]
+pub struct MinecraftVersionConfig(ProtocolVersion);
+
+impl MinecraftVersionConfig {
+    pub fn oldest() -> Self {
+        Self(ProtocolVersion::oldest())
+    }
+
+    pub fn latest() -> Self {
+        Self(ProtocolVersion::latest())
+    }
+}
+
+impl Serialize for MinecraftVersionConfig {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.0.humanize())
+    }
+}
+
+impl<'de> Deserialize<'de> for MinecraftVersionConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        ProtocolVersion::from_release(&value)
+            .filter(|protocol_version| !protocol_version.is_any())
+            .map(Self)
+            .ok_or_else(|| {
+                serde::de::Error::custom(format!(
+                    "Unknown Minecraft version: {value}, supported versions are {} to {}",
+                    ProtocolVersion::oldest().humanize(),
+                    ProtocolVersion::latest().humanize()
+                ))
+            })
+    }
+}
+
+impl From<MinecraftVersionConfig> for ProtocolVersion {
+    fn from(value: MinecraftVersionConfig) -> Self {
+        value.0
+    }
+}
@@ -2,6 +2,8 @@ pub mod boss_bar;
 pub mod config;
 mod forwarding;
 mod game_mode_config;
+pub mod messages;
+mod minecraft_version;
 mod require_boolean;
 mod server_list;
 pub mod tab_list;
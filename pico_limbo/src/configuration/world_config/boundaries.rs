@@ -1,4 +1,5 @@
 use crate::configuration::require_boolean::{require_false, require_true};
+use crate::server_state::{BoundaryAction, HorizontalBoundary};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -14,7 +15,24 @@ pub struct EnabledBoundariesConfig {
     #[serde(deserialize_with = "require_true")]
     enabled: bool,
     pub min_y: i32,
+    /// Highest Y position players can reach, no ceiling when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_y: Option<i32>,
+    /// Action taken when a player goes below `min_y` or above `max_y`
+    #[serde(default)]
+    pub vertical_action: BoundaryActionConfig,
+    /// Message sent when going below `min_y`
     pub teleport_message: String,
+    /// Message sent when going above `max_y`
+    #[serde(default = "default_ceiling_message")]
+    pub ceiling_message: String,
+    /// Message sent when leaving the horizontal boundary
+    #[serde(default = "default_horizontal_message")]
+    pub horizontal_message: String,
+    #[serde(default = "default_kick_message")]
+    pub kick_message: String,
+    #[serde(default)]
+    pub horizontal: HorizontalBoundaryConfig,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -23,12 +41,99 @@ pub struct DisabledBoundariesConfig {
     enabled: bool,
 }
 
+#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum BoundaryActionConfig {
+    /// Teleports the player back to the spawn position
+    #[default]
+    Teleport,
+    /// Teleports the player back to the last position inside the boundaries
+    PushBack,
+    /// Disconnects the player
+    Kick,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum HorizontalBoundaryConfig {
+    #[default]
+    None,
+    /// Axis-aligned box, edges included
+    Box {
+        min_x: f64,
+        max_x: f64,
+        min_z: f64,
+        max_z: f64,
+        action: BoundaryActionConfig,
+    },
+    /// Circle around a center given as [x, z] coordinates
+    Radius {
+        center: (f64, f64),
+        radius: f64,
+        action: BoundaryActionConfig,
+    },
+}
+
+impl From<BoundaryActionConfig> for BoundaryAction {
+    fn from(action: BoundaryActionConfig) -> Self {
+        match action {
+            BoundaryActionConfig::Teleport => Self::TeleportToSpawn,
+            BoundaryActionConfig::PushBack => Self::PushBack,
+            BoundaryActionConfig::Kick => Self::Kick,
+        }
+    }
+}
+
+impl From<HorizontalBoundaryConfig> for Option<(HorizontalBoundary, BoundaryAction)> {
+    fn from(config: HorizontalBoundaryConfig) -> Self {
+        match config {
+            HorizontalBoundaryConfig::None => None,
+            HorizontalBoundaryConfig::Box {
+                min_x,
+                max_x,
+                min_z,
+                max_z,
+                action,
+            } => Some((
+                HorizontalBoundary::Box {
+                    min: (min_x, min_z),
+                    max: (max_x, max_z),
+                },
+                action.into(),
+            )),
+            HorizontalBoundaryConfig::Radius {
+                center,
+                radius,
+                action,
+            } => Some((HorizontalBoundary::Radius { center, radius }, action.into())),
+        }
+    }
+}
+
+fn default_ceiling_message() -> String {
+    "<red>You have reached the top of the world.</red>".into()
+}
+
+fn default_horizontal_message() -> String {
+    "<red>You have reached the edge of the world.</red>".into()
+}
+
+fn default_kick_message() -> String {
+    "<red>You have left the world.</red>".into()
+}
+
 impl Default for BoundariesConfig {
     fn default() -> Self {
         Self::Enabled(EnabledBoundariesConfig {
             enabled: true,
             min_y: -64,
+            max_y: None,
+            vertical_action: BoundaryActionConfig::default(),
             teleport_message: "<red>You have reached the bottom of the world.</red>".into(),
+            ceiling_message: default_ceiling_message(),
+            horizontal_message: default_horizontal_message(),
+            kick_message: default_kick_message(),
+            horizontal: HorizontalBoundaryConfig::default(),
         })
     }
 }
@@ -1,4 +1,6 @@
-use serde::{Deserialize, Serialize};
+use minecraft_protocol::prelude::Coordinates;
+use pico_structures::prelude::{Mirror, Rotation, SchematicPlacement};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -6,8 +8,24 @@ pub struct ExperimentalWorldConfig {
     pub view_distance: i32,
     pub schematic_file: String,
 
+    /// Schematics placed in the world after `schematic_file`, later entries override earlier ones
+    #[serde(default)]
+    pub schematics: Vec<SchematicPlacementConfig>,
+
     /// Lock the world time to the value of `world.time`
     pub lock_time: bool,
+
+    /// Area of a vanilla world to load instead of a schematic
+    #[serde(default)]
+    pub anvil_world: AnvilWorldConfig,
+
+    /// Save the loaded world next to the schematic, to start faster while the schematic is unchanged
+    #[serde(default = "default_world_cache")]
+    pub world_cache: bool,
+}
+
+const fn default_world_cache() -> bool {
+    true
 }
 
 impl Default for ExperimentalWorldConfig {
@@ -15,7 +33,152 @@ impl Default for ExperimentalWorldConfig {
         Self {
             view_distance: 2,
             schematic_file: String::new(),
+            schematics: Vec::new(),
             lock_time: false,
+            anvil_world: AnvilWorldConfig::default(),
+            world_cache: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct AnvilWorldConfig {
+    /// Path to the folder of a vanilla world, empty to disable
+    pub world_folder: String,
+
+    /// Folder of the dimension inside the world folder.
+    /// Empty for the overworld, "DIM-1" for the nether or "DIM1" for the end
+    pub dimension_folder: String,
+
+    /// Chunk coordinates (x, z) of the first corner of the area to load
+    pub min_chunk: (i32, i32),
+
+    /// Chunk coordinates (x, z) of the opposite corner, included in the area
+    pub max_chunk: (i32, i32),
+}
+
+impl Default for AnvilWorldConfig {
+    fn default() -> Self {
+        Self {
+            world_folder: String::new(),
+            dimension_folder: String::new(),
+            min_chunk: (-2, -2),
+            max_chunk: (1, 1),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SchematicPlacementConfig {
+    /// Path to the schematic file
+    pub file: String,
+
+    /// World position (x, y, z) of the minimum corner of the placed schematic
+    #[serde(default)]
+    pub origin: (i32, i32, i32),
+
+    /// Clockwise rotation in degrees: 0, 90, 180 or 270
+    #[serde(default)]
+    pub rotation: RotationConfig,
+
+    /// Mirroring applied before the rotation.
+    /// Supported: "none", "x" to swap east and west or "z" to swap north and south
+    #[serde(default)]
+    pub mirror: MirrorConfig,
+
+    /// Index of the palette used by structure files with several palettes
+    #[serde(default)]
+    pub palette: usize,
+}
+
+impl From<SchematicPlacementConfig> for SchematicPlacement {
+    fn from(value: SchematicPlacementConfig) -> Self {
+        let (x, y, z) = value.origin;
+        Self {
+            path: value.file.into(),
+            origin: Coordinates::new(x, y, z),
+            rotation: value.rotation.into(),
+            mirror: value.mirror.into(),
+            palette: value.palette,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+pub enum RotationConfig {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    Clockwise270,
+}
+
+impl RotationConfig {
+    const fn degrees(self) -> u16 {
+        match self {
+            Self::None => 0,
+            Self::Clockwise90 => 90,
+            Self::Clockwise180 => 180,
+            Self::Clockwise270 => 270,
+        }
+    }
+}
+
+impl Serialize for RotationConfig {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u16(self.degrees())
+    }
+}
+
+impl<'de> Deserialize<'de> for RotationConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u16::deserialize(deserializer)? {
+            0 => Ok(Self::None),
+            90 => Ok(Self::Clockwise90),
+            180 => Ok(Self::Clockwise180),
+            270 => Ok(Self::Clockwise270),
+            degrees => Err(serde::de::Error::custom(format!(
+                "Invalid rotation: {degrees}, expected 0, 90, 180 or 270"
+            ))),
+        }
+    }
+}
+
+impl From<RotationConfig> for Rotation {
+    fn from(value: RotationConfig) -> Self {
+        match value {
+            RotationConfig::None => Self::None,
+            RotationConfig::Clockwise90 => Self::Clockwise90,
+            RotationConfig::Clockwise180 => Self::Clockwise180,
+            RotationConfig::Clockwise270 => Self::Counterclockwise90,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum MirrorConfig {
+    #[default]
+    None,
+    X,
+    Z,
+}
+
+impl From<MirrorConfig> for Mirror {
+    fn from(value: MirrorConfig) -> Self {
+        match value {
+            MirrorConfig::None => Self::None,
+            MirrorConfig::X => Self::X,
+            MirrorConfig::Z => Self::Z,
         }
     }
 }
@@ -0,0 +1,61 @@
+use pico_structures::prelude::FlatGenerator;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum GeneratorConfig {
+    /// The world is empty, except for the schematics
+    #[default]
+    None,
+
+    /// Superflat layers covering every chunk within `radius` chunks of the spawn chunk
+    Flat {
+        /// Layers from the bottom up
+        layers: Vec<LayerConfig>,
+        /// Y coordinate of the bottom of the lowest layer
+        #[serde(default)]
+        min_y: i32,
+        /// Radius in chunks around the chunk at 0, 0
+        radius: u32,
+    },
+
+    /// A square platform of one block thick, centered on 0, 0
+    Platform {
+        block: String,
+        /// Width of the platform in blocks
+        size: u32,
+        /// Y coordinate of the platform
+        y: i32,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayerConfig {
+    /// Block state, for example `minecraft:grass_block[snowy=false]`
+    pub block: String,
+    pub thickness: u32,
+}
+
+impl From<GeneratorConfig> for Option<FlatGenerator> {
+    fn from(value: GeneratorConfig) -> Self {
+        match value {
+            GeneratorConfig::None => None,
+            GeneratorConfig::Flat {
+                layers,
+                min_y,
+                radius,
+            } => Some(FlatGenerator::superflat(
+                layers
+                    .into_iter()
+                    .map(|layer| (layer.block, layer.thickness))
+                    .collect(),
+                min_y,
+                radius,
+            )),
+            GeneratorConfig::Platform { block, size, y } => {
+                Some(FlatGenerator::platform(block, size, y))
+            }
+        }
+    }
+}
@@ -1,13 +1,19 @@
 use crate::configuration::world_config::boundaries::BoundariesConfig;
 use crate::configuration::world_config::experimental::ExperimentalWorldConfig;
+use crate::configuration::world_config::generator::GeneratorConfig;
 use crate::configuration::world_config::spawn_dimension::SpawnDimensionConfig;
 use crate::configuration::world_config::time::TimeConfig;
+use crate::configuration::world_config::weather::{WeatherConfig, WeatherCycleEntryConfig};
+use crate::configuration::world_config::world_border::WorldBorderConfig;
 use serde::{Deserialize, Serialize};
 
 pub mod boundaries;
 mod experimental;
+mod generator;
 mod spawn_dimension;
 mod time;
+mod weather;
+pub mod world_border;
 
 #[derive(Serialize, Deserialize)]
 #[serde(default)]
@@ -20,15 +26,32 @@ pub struct WorldConfig {
     /// Supported: "overworld", "nether" or "end"
     pub dimension: SpawnDimensionConfig,
 
+    /// Biome of the world, such as `minecraft:plains` or `minecraft:cherry_grove`.
+    /// Biomes stored in the schematic or Anvil world take precedence
+    pub biome: String,
+
     /// Time of the world
     /// Supported: "sunrise", "noon", "sunset", "midnight" or ticks (0 - 24000)
     pub time: TimeConfig,
 
+    /// Weather of the world
+    /// Supported: "clear", "rain" or "thunder"
+    pub weather: WeatherConfig,
+
+    /// Weathers the world goes through in a loop, replacing `weather` when not empty
+    pub weather_cycle: Vec<WeatherCycleEntryConfig>,
+
     /// Experimental settings
     pub experimental: ExperimentalWorldConfig,
 
     /// World Boundaries settings
     pub boundaries: BoundariesConfig,
+
+    /// Horizontal limit of the world, shown to the players
+    pub world_border: WorldBorderConfig,
+
+    /// Blocks generated below the schematics, such as a superflat floor or a spawn platform
+    pub generator: GeneratorConfig,
 }
 
 impl Default for WorldConfig {
@@ -36,9 +59,14 @@ impl Default for WorldConfig {
         Self {
             spawn_position: (0.0, 320.0, 0.0),
             dimension: SpawnDimensionConfig::default(),
+            biome: "minecraft:plains".to_string(),
             time: TimeConfig::default(),
+            weather: WeatherConfig::default(),
+            weather_cycle: Vec::new(),
             experimental: ExperimentalWorldConfig::default(),
             boundaries: BoundariesConfig::default(),
+            world_border: WorldBorderConfig::default(),
+            generator: GeneratorConfig::default(),
         }
     }
 }
@@ -0,0 +1,36 @@
+use crate::server_state::Weather;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum WeatherConfig {
+    #[default]
+    Clear,
+    Rain,
+    Thunder,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeatherCycleEntryConfig {
+    pub weather: WeatherConfig,
+    /// Duration of the weather, in seconds
+    pub duration: u64,
+}
+
+impl From<WeatherConfig> for Weather {
+    fn from(weather: WeatherConfig) -> Self {
+        match weather {
+            WeatherConfig::Clear => Self::Clear,
+            WeatherConfig::Rain => Self::Rain,
+            WeatherConfig::Thunder => Self::Thunder,
+        }
+    }
+}
+
+impl From<WeatherCycleEntryConfig> for (Weather, Duration) {
+    fn from(entry: WeatherCycleEntryConfig) -> Self {
+        (entry.weather.into(), Duration::from_secs(entry.duration))
+    }
+}
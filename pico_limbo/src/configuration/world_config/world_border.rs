@@ -0,0 +1,37 @@
+use crate::configuration::require_boolean::{require_false, require_true};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WorldBorderConfig {
+    Enabled(EnabledWorldBorderConfig),
+    Disabled(DisabledWorldBorderConfig),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnabledWorldBorderConfig {
+    #[serde(deserialize_with = "require_true")]
+    enabled: bool,
+    /// Center of the border as [x, z] coordinates
+    pub center: (f64, f64),
+    /// Length of a side of the border, in blocks
+    pub diameter: f64,
+    /// Distance to the border, in blocks, at which the screen turns red
+    pub warning_distance: i32,
+    /// Time, in seconds, before a moving border reaches the player at which the screen turns red
+    pub warning_time: i32,
+    pub teleport_message: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DisabledWorldBorderConfig {
+    #[serde(deserialize_with = "require_false")]
+    enabled: bool,
+}
+
+impl Default for WorldBorderConfig {
+    fn default() -> Self {
+        Self::Disabled(DisabledWorldBorderConfig { enabled: false })
+    }
+}
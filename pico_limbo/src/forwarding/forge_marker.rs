@@ -0,0 +1,118 @@
+/// Modded clients append a marker to the hostname of their handshake.
+/// The marker is separated from the hostname by a null character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeMarker {
+    /// Forge 1.7 to 1.12.2
+    Fml,
+    /// Forge 1.13 to 1.17.1
+    Fml2,
+    /// Forge 1.18 to 1.20.1
+    Fml3,
+    /// Forge 1.20.2 and above
+    Forge,
+}
+
+impl ForgeMarker {
+    fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "FML" => Some(Self::Fml),
+            "FML2" => Some(Self::Fml2),
+            "FML3" => Some(Self::Fml3),
+            "FORGE" => Some(Self::Forge),
+            _ => None,
+        }
+    }
+
+    /// Whether the client expects the server to negotiate mods using login queries.
+    pub const fn uses_login_wrapper(self) -> bool {
+        matches!(self, Self::Fml2 | Self::Fml3)
+    }
+}
+
+/// Removes the Forge marker from a handshake hostname.
+/// The marker can either be right after the hostname or after the forwarded data of a proxy.
+/// FML markers end with a null character, which is removed along with the marker.
+pub fn strip_forge_marker(hostname: &str) -> (String, Option<ForgeMarker>) {
+    let mut parts: Vec<&str> = hostname.split('\0').collect();
+    let Some((index, marker)) = parts
+        .iter()
+        .enumerate()
+        .skip(1)
+        .find_map(|(index, part)| Some((index, ForgeMarker::from_token(part)?)))
+    else {
+        return (hostname.to_owned(), None);
+    };
+
+    parts.remove(index);
+    if marker != ForgeMarker::Forge && parts.get(index).is_some_and(|part| part.is_empty()) {
+        parts.remove(index);
+    }
+    (parts.join("\0"), Some(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vanilla_hostname() {
+        // Given
+        let hostname = "localhost";
+
+        // When
+        let (stripped, marker) = strip_forge_marker(hostname);
+
+        // Then
+        assert_eq!(stripped, "localhost");
+        assert_eq!(marker, None);
+    }
+
+    #[test]
+    fn test_strip_fml_markers() {
+        for (hostname, expected) in [
+            ("localhost\x00FML\x00", ForgeMarker::Fml),
+            ("localhost\x00FML2\x00", ForgeMarker::Fml2),
+            ("localhost\x00FML3\x00", ForgeMarker::Fml3),
+            ("localhost\x00FORGE", ForgeMarker::Forge),
+        ] {
+            // When
+            let (stripped, marker) = strip_forge_marker(hostname);
+
+            // Then
+            assert_eq!(stripped, "localhost");
+            assert_eq!(marker, Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_strip_marker_before_forwarded_data() {
+        // Given
+        let hostname = "localhost\x00FML2\x00\x00127.0.0.1\x006856201a9c1f49978608371019daf15e";
+
+        // When
+        let (stripped, marker) = strip_forge_marker(hostname);
+
+        // Then
+        assert_eq!(
+            stripped,
+            "localhost\x00127.0.0.1\x006856201a9c1f49978608371019daf15e"
+        );
+        assert_eq!(marker, Some(ForgeMarker::Fml2));
+    }
+
+    #[test]
+    fn test_keep_empty_forwarded_data() {
+        // Given
+        let hostname = "localhost\x00127.0.0.1\x006856201a9c1f49978608371019daf15e\x00\x00FML\x00";
+
+        // When
+        let (stripped, marker) = strip_forge_marker(hostname);
+
+        // Then
+        assert_eq!(
+            stripped,
+            "localhost\x00127.0.0.1\x006856201a9c1f49978608371019daf15e\x00"
+        );
+        assert_eq!(marker, Some(ForgeMarker::Fml));
+    }
+}
@@ -1,3 +1,4 @@
 pub mod check_bungee_cord;
 pub mod check_velocity_key_integrity;
+pub mod forge_marker;
 pub mod forwarding_result;
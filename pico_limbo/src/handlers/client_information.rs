@@ -0,0 +1,157 @@
+use crate::handlers::configuration::{send_skin_layers, send_welcome_message};
+use crate::handlers::play::send_chunks_circularly::send_chunks;
+use crate::server::batch::Batch;
+use crate::server::client_state::ClientState;
+use crate::server::packet_handler::{PacketHandler, PacketHandlerError};
+use crate::server::packet_registry::PacketRegistry;
+use crate::server_state::ServerState;
+use minecraft_packets::play::client_information_packet::ClientInformationPacket;
+use minecraft_protocol::prelude::State;
+
+impl PacketHandler for ClientInformationPacket {
+    fn handle(
+        &self,
+        client_state: &mut ClientState,
+        server_state: &ServerState,
+    ) -> Result<Batch<PacketRegistry>, PacketHandlerError> {
+        let mut batch = Batch::new();
+        let is_first_information = !client_state.has_client_information();
+        client_state.set_client_information(
+            self.locale(),
+            self.view_distance(),
+            self.displayed_skin_parts(),
+        );
+
+        if client_state.state() == State::Play {
+            send_skin_layers(&mut batch, client_state);
+
+            // Clients without a configuration state send their settings after joining the game
+            if is_first_information {
+                send_welcome_message(&mut batch, client_state, server_state);
+            }
+
+            // Loads or unloads the chunks affected by a new view distance
+            if client_state.chunk_sender().view().is_some() {
+                let position = client_state.position();
+                send_chunks(
+                    &mut batch,
+                    client_state,
+                    server_state,
+                    (position.x, position.z),
+                )?;
+            }
+        }
+
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::chunk_view::ChunkView;
+    use futures::StreamExt;
+    use minecraft_protocol::prelude::ProtocolVersion;
+
+    fn server_state() -> ServerState {
+        let mut builder = ServerState::builder();
+        builder.welcome_message("Hello, World!");
+        builder.build().unwrap()
+    }
+
+    fn client(protocol: ProtocolVersion, state: State) -> ClientState {
+        let mut cs = ClientState::default();
+        cs.set_protocol_version(protocol);
+        cs.set_state(state);
+        cs
+    }
+
+    #[tokio::test]
+    async fn test_should_store_information_during_configuration() {
+        // Given
+        let mut client_state = client(ProtocolVersion::V1_21_4, State::Configuration);
+        let packet = ClientInformationPacket::new("fr_fr", 4, 0x01);
+
+        // When
+        let batch = packet.handle(&mut client_state, &server_state()).unwrap();
+        let mut batch = batch.into_stream();
+
+        // Then
+        assert!(batch.next().await.is_none());
+        assert_eq!(client_state.locale(), Some("fr_fr"));
+        assert_eq!(client_state.displayed_skin_parts(), 0x01);
+        assert_eq!(client_state.effective_view_distance(8), 4);
+        assert_eq!(client_state.effective_view_distance(2), 2);
+    }
+
+    #[tokio::test]
+    async fn test_should_send_skin_layers_and_welcome_message_once() {
+        // Given
+        let mut client_state = client(ProtocolVersion::V1_8, State::Play);
+        let server_state = server_state();
+        let packet = ClientInformationPacket::new("en_us", 8, 0x7F);
+
+        // When
+        let first = packet.handle(&mut client_state, &server_state).unwrap();
+        let second = packet.handle(&mut client_state, &server_state).unwrap();
+        let mut first = first.into_stream();
+        let mut second = second.into_stream();
+
+        // Then
+        assert!(matches!(
+            first.next().await.unwrap(),
+            PacketRegistry::SetEntityMetadata(_)
+        ));
+        assert!(matches!(
+            first.next().await.unwrap(),
+            PacketRegistry::LegacyChatMessage(_)
+        ));
+        assert!(first.next().await.is_none());
+        assert!(matches!(
+            second.next().await.unwrap(),
+            PacketRegistry::SetEntityMetadata(_)
+        ));
+        assert!(second.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_should_apply_new_view_distance_during_play() {
+        // Given
+        let mut builder = ServerState::builder();
+        builder.view_distance(2);
+        let server_state = builder.build().unwrap();
+        let mut client_state = client(ProtocolVersion::V1_12_2, State::Play);
+        ClientInformationPacket::new("en_us", 2, 0x7F)
+            .handle(&mut client_state, &server_state)
+            .unwrap();
+        send_chunks(
+            &mut Batch::new(),
+            &mut client_state,
+            &server_state,
+            (0.0, 0.0),
+        )
+        .unwrap();
+        while !client_state
+            .chunk_sender_mut()
+            .next_batch(ProtocolVersion::V1_12_2)
+            .is_empty()
+        {}
+        let packet = ClientInformationPacket::new("en_us", 1, 0x7F);
+
+        // When
+        let batch = packet.handle(&mut client_state, &server_state).unwrap();
+        let packets: Vec<PacketRegistry> = batch.into_stream().collect().await;
+
+        // Then
+        let unloaded_chunks = packets
+            .iter()
+            .filter(|packet| matches!(packet, PacketRegistry::UnloadChunk(_)))
+            .count();
+        assert_eq!(unloaded_chunks, 25 - 9);
+        assert_eq!(client_state.chunk_sender().loaded_chunks().len(), 9);
+        assert_eq!(
+            client_state.chunk_sender().view(),
+            Some(ChunkView::new((0, 0), 1))
+        );
+    }
+}
@@ -1,21 +1,23 @@
 use crate::handlers::play::fetch_minecraft_profile::fetch_minecraft_profile;
-use crate::handlers::play::send_chunks_circularly::CircularChunkPacketIterator;
+use crate::handlers::play::send_chunks_circularly::send_chunks;
+use crate::handlers::play::weather::send_weather_packets;
 use crate::server::batch::Batch;
 use crate::server::client_state::ClientState;
 use crate::server::game_mode::GameMode;
 use crate::server::packet_handler::{PacketHandler, PacketHandlerError};
 use crate::server::packet_registry::PacketRegistry;
+use crate::server::player_position::PlayerPosition;
 use crate::server_state::{ServerState, TabList};
 use minecraft_packets::configuration::acknowledge_finish_configuration_packet::AcknowledgeConfigurationPacket;
 use minecraft_packets::login::Property;
 use minecraft_packets::play::boss_bar_packet::BossBarPacket;
 use minecraft_packets::play::commands_packet::CommandsPacket;
 use minecraft_packets::play::game_event_packet::GameEventPacket;
+use minecraft_packets::play::initialize_world_border_packet::InitializeWorldBorderPacket;
 use minecraft_packets::play::legacy_chat_message_packet::LegacyChatMessagePacket;
 use minecraft_packets::play::login_packet::LoginPacket;
 use minecraft_packets::play::play_client_bound_plugin_message_packet::PlayClientBoundPluginMessagePacket;
 use minecraft_packets::play::player_info_update_packet::PlayerInfoUpdatePacket;
-use minecraft_packets::play::set_chunk_cache_center_packet::SetCenterChunkPacket;
 use minecraft_packets::play::set_default_spawn_position_packet::SetDefaultSpawnPositionPacket;
 use minecraft_packets::play::set_entity_data_packet::SetEntityMetadataPacket;
 use minecraft_packets::play::synchronize_player_position_packet::SynchronizePlayerPositionPacket;
@@ -25,7 +27,7 @@ use minecraft_packets::play::update_time_packet::UpdateTimePacket;
 use minecraft_protocol::prelude::{Dimension, ProtocolVersion, State};
 use pico_structures::prelude::SchematicError;
 use pico_text_component::prelude::Component;
-use registries::{Registries, get_dimension_index, get_registries, get_void_biome_index};
+use registries::{Registries, get_dimension_index, get_registries};
 use std::num::TryFromIntError;
 
 impl PacketHandler for AcknowledgeConfigurationPacket {
@@ -86,27 +88,6 @@ fn build_login_packet(
     }
 }
 
-const F64_CONVERSION_FAILED: &str = "Conversion failed: Invalid or out-of-range float";
-
-fn safe_f64_to_i32(f: f64) -> Option<i32> {
-    if f.is_finite() && f >= f64::from(i32::MIN) && f <= f64::from(i32::MAX) {
-        #[allow(clippy::cast_possible_truncation)]
-        Some(f as i32)
-    } else {
-        None
-    }
-}
-
-fn world_position_to_chunk_position(
-    position: (f64, f64),
-) -> Result<(i32, i32), PacketHandlerError> {
-    let chunk_x = safe_f64_to_i32((position.0 / 16.0).floor())
-        .ok_or_else(|| PacketHandlerError::invalid_state(F64_CONVERSION_FAILED))?;
-    let chunk_z = safe_f64_to_i32((position.1 / 16.0).floor())
-        .ok_or_else(|| PacketHandlerError::invalid_state(F64_CONVERSION_FAILED))?;
-    Ok((chunk_x, chunk_z))
-}
-
 impl From<SchematicError> for PacketHandlerError {
     fn from(value: SchematicError) -> Self {
         Self::Custom(value.to_string())
@@ -149,7 +130,9 @@ pub fn send_play_packets(
     // Send Synchronize Player Position
     let packet = SynchronizePlayerPositionPacket::new(x, y, z);
     batch.queue(|| PacketRegistry::SynchronizePlayerPosition(packet));
-    client_state.set_feet_position(y);
+    let spawn = PlayerPosition::new(x, y, z);
+    client_state.set_position(spawn);
+    client_state.set_last_valid_position(spawn);
 
     if protocol_version.is_after_inclusive(ProtocolVersion::V1_13) {
         let packet = CommandsPacket::empty();
@@ -163,8 +146,10 @@ pub fn send_play_packets(
         batch.queue(|| PacketRegistry::PlayClientBoundPluginMessage(packet));
     }
 
-    if let Some(component) = server_state.welcome_message() {
-        send_message(batch, component, protocol_version);
+    // Prior to 1.20.2, the client sends its settings after joining the game,
+    // the welcome message will be sent once the locale is known
+    if client_state.has_client_information() {
+        send_welcome_message(batch, client_state, server_state);
     }
 
     let ticks = server_state.time_world_ticks();
@@ -175,36 +160,24 @@ pub fn send_play_packets(
     send_tab_list_packets(batch, server_state);
     send_skin_packets(batch, client_state, server_state);
     send_boss_bar_packets(batch, server_state);
+    send_world_border_packets(batch, protocol_version, server_state);
 
-    if protocol_version.is_after_inclusive(ProtocolVersion::V1_19) {
-        if protocol_version.is_after_inclusive(ProtocolVersion::V1_20_3) {
-            // Send Game Event
-            let packet = GameEventPacket::start_waiting_for_chunks(0.0);
-            batch.queue(|| PacketRegistry::GameEvent(packet));
-        }
+    // Clients join with a clear sky
+    let weather = server_state.weather();
+    if weather != client_state.weather() {
+        send_weather_packets(batch, weather);
+        client_state.set_weather(weather);
+    }
 
-        // Send Chunk Data and Update Light
-        let biome_id = get_void_biome_index(protocol_version).ok_or_else(|| {
-            PacketHandlerError::InvalidState(format!(
-                "Cannot find void biome index for version {protocol_version}"
-            ))
-        })?;
-
-        let center_chunk = world_position_to_chunk_position((x, z))?;
-        let packet = SetCenterChunkPacket::new(center_chunk.0, center_chunk.1);
-        batch.queue(|| PacketRegistry::SetCenterChunk(packet));
-
-        let iter = CircularChunkPacketIterator::new(
-            center_chunk,
-            view_distance,
-            server_state.world(),
-            biome_id,
-            dimension,
-            protocol_version,
-        );
-        batch.chain_iter(iter);
+    if protocol_version.is_after_inclusive(ProtocolVersion::V1_20_3) {
+        // Send Game Event
+        let packet = GameEventPacket::start_waiting_for_chunks(0.0);
+        batch.queue(|| PacketRegistry::GameEvent(packet));
     }
 
+    // Send Chunk Data and Update Light
+    send_chunks(batch, client_state, server_state, (x, z))?;
+
     client_state.set_state(State::Play);
     client_state.set_keep_alive_should_enable();
 
@@ -230,6 +203,25 @@ fn send_boss_bar_packets(batch: &mut Batch<PacketRegistry>, server_state: &Serve
     }
 }
 
+fn send_world_border_packets(
+    batch: &mut Batch<PacketRegistry>,
+    protocol_version: ProtocolVersion,
+    server_state: &ServerState,
+) {
+    // The world border was added in 1.8
+    if let Some(world_border) = server_state.world_border()
+        && protocol_version.is_after_inclusive(ProtocolVersion::V1_8)
+    {
+        let packet = InitializeWorldBorderPacket::new(
+            world_border.center,
+            world_border.diameter,
+            world_border.warning_distance,
+            world_border.warning_time,
+        );
+        batch.queue(|| PacketRegistry::InitializeWorldBorder(packet));
+    }
+}
+
 fn send_skin_packets(
     batch: &mut Batch<PacketRegistry>,
     client_state: &ClientState,
@@ -271,13 +263,31 @@ fn send_skin_packets(
         });
     }
 
+    send_skin_layers(batch, client_state);
+}
+
+pub fn send_skin_layers(batch: &mut Batch<PacketRegistry>, client_state: &ClientState) {
     // There are no skin layers before 1.8 so no need to send this packet
-    if protocol_version.is_after_inclusive(ProtocolVersion::V1_8) {
-        let packet = SetEntityMetadataPacket::skin_layers(0);
+    if client_state
+        .protocol_version()
+        .is_after_inclusive(ProtocolVersion::V1_8)
+    {
+        let packet = SetEntityMetadataPacket::skin_layers(0, client_state.displayed_skin_parts());
         batch.queue(|| PacketRegistry::SetEntityMetadata(packet));
     }
 }
 
+pub fn send_welcome_message(
+    batch: &mut Batch<PacketRegistry>,
+    client_state: &ClientState,
+    server_state: &ServerState,
+) {
+    let messages = server_state.messages().get(client_state.locale());
+    if let Some(component) = &messages.welcome {
+        send_message(batch, component, client_state.protocol_version());
+    }
+}
+
 impl From<TryFromIntError> for PacketHandlerError {
     fn from(_: TryFromIntError) -> Self {
         Self::custom("failed to cast int")
@@ -301,6 +311,7 @@ pub fn send_message(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::server_state::Weather;
     use futures::StreamExt;
 
     fn server_state() -> ServerState {
@@ -325,6 +336,7 @@ mod tests {
     async fn test_v1_20_3_play_packets() {
         // Given
         let mut client_state = client(ProtocolVersion::V1_20_3);
+        client_state.set_client_information("en_us", 2, 0x7F);
         let server_state = server_state();
         let mut batch = Batch::new();
 
@@ -369,10 +381,18 @@ mod tests {
             batch.next().await.unwrap(),
             PacketRegistry::SetCenterChunk(_)
         ));
+        assert!(matches!(
+            batch.next().await.unwrap(),
+            PacketRegistry::ChunkBatchStart(_)
+        ));
         assert!(matches!(
             batch.next().await.unwrap(),
             PacketRegistry::ChunkDataAndUpdateLight(_)
         ));
+        assert!(matches!(
+            batch.next().await.unwrap(),
+            PacketRegistry::ChunkBatchFinished(_)
+        ));
         assert!(batch.next().await.is_none());
     }
 
@@ -408,10 +428,6 @@ mod tests {
             batch.next().await.unwrap(),
             PacketRegistry::PlayClientBoundPluginMessage(_)
         ));
-        assert!(matches!(
-            batch.next().await.unwrap(),
-            PacketRegistry::SystemChatMessage(_)
-        ));
         assert!(matches!(
             batch.next().await.unwrap(),
             PacketRegistry::UpdateTime(_)
@@ -461,15 +477,15 @@ mod tests {
         ));
         assert!(matches!(
             batch.next().await.unwrap(),
-            PacketRegistry::LegacyChatMessage(_)
+            PacketRegistry::UpdateTime(_)
         ));
         assert!(matches!(
             batch.next().await.unwrap(),
-            PacketRegistry::UpdateTime(_)
+            PacketRegistry::SetEntityMetadata(_)
         ));
         assert!(matches!(
             batch.next().await.unwrap(),
-            PacketRegistry::SetEntityMetadata(_)
+            PacketRegistry::ChunkDataAndUpdateLight(_)
         ));
         assert!(batch.next().await.is_none());
     }
@@ -496,16 +512,79 @@ mod tests {
         ));
         assert!(matches!(
             batch.next().await.unwrap(),
-            PacketRegistry::LegacyChatMessage(_)
+            PacketRegistry::UpdateTime(_)
         ));
         assert!(matches!(
             batch.next().await.unwrap(),
-            PacketRegistry::UpdateTime(_)
+            PacketRegistry::SetEntityMetadata(_)
         ));
         assert!(matches!(
             batch.next().await.unwrap(),
-            PacketRegistry::SetEntityMetadata(_)
+            PacketRegistry::ChunkDataAndUpdateLight(_)
         ));
         assert!(batch.next().await.is_none());
     }
+
+    #[tokio::test]
+    async fn test_v1_14_play_packets_set_center_chunk() {
+        // Given
+        let mut client_state = client(ProtocolVersion::V1_14);
+        let server_state = server_state();
+        let mut batch = Batch::new();
+
+        // When
+        send_play_packets(&mut batch, &mut client_state, &server_state).unwrap();
+        let batch = batch.into_stream().collect::<Vec<_>>().await;
+
+        // Then
+        assert!(matches!(
+            batch[batch.len() - 2],
+            PacketRegistry::SetCenterChunk(_)
+        ));
+        assert!(matches!(
+            batch[batch.len() - 1],
+            PacketRegistry::ChunkDataAndUpdateLight(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_play_packets_do_not_contain_void_chunks() {
+        // Given
+        let mut client_state = client(ProtocolVersion::V1_8);
+        let server_state = server_state();
+        let mut batch = Batch::new();
+
+        // When
+        send_play_packets(&mut batch, &mut client_state, &server_state).unwrap();
+        let batch = batch.into_stream().collect::<Vec<_>>().await;
+
+        // Then
+        assert!(
+            !batch
+                .iter()
+                .any(|packet| matches!(packet, PacketRegistry::ChunkDataAndUpdateLight(_)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_send_the_weather_when_joining() {
+        // Given
+        let mut client_state = client(ProtocolVersion::V1_7_2);
+        let mut builder = ServerState::builder();
+        builder.view_distance(0).weather(Weather::Rain);
+        let server_state = builder.build().unwrap();
+        let mut batch = Batch::new();
+
+        // When
+        send_play_packets(&mut batch, &mut client_state, &server_state).unwrap();
+        let packets: Vec<_> = batch.into_stream().collect().await;
+
+        // Then
+        let game_events = packets
+            .iter()
+            .filter(|packet| matches!(packet, PacketRegistry::GameEvent(_)))
+            .count();
+        assert_eq!(game_events, 3);
+        assert_eq!(client_state.weather(), Weather::Rain);
+    }
 }
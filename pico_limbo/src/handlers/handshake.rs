@@ -1,6 +1,6 @@
 use crate::forwarding::check_bungee_cord::check_bungee_cord;
+use crate::forwarding::forge_marker::strip_forge_marker;
 use crate::forwarding::forwarding_result::LegacyForwardingResult;
-use crate::kick_messages::PROXY_REQUIRED_KICK_MESSAGE;
 use crate::server::batch::Batch;
 use crate::server::client_state::ClientState;
 use crate::server::game_profile::GameProfile;
@@ -9,7 +9,9 @@ use crate::server::packet_registry::PacketRegistry;
 use crate::server_state::ServerState;
 use minecraft_packets::handshaking::handshake_packet::HandshakePacket;
 use minecraft_protocol::prelude::{ProtocolVersion, State};
+use pico_text_component::prelude::Component;
 use thiserror::Error;
+use tracing::debug;
 
 impl PacketHandler for HandshakePacket {
     fn handle(
@@ -25,12 +27,27 @@ impl PacketHandler for HandshakePacket {
             |next_state| {
                 client_state.set_state(next_state);
 
-                let forwarding_result = check_bungee_cord(server_state, &self.hostname);
+                if next_state == State::Login
+                    && let Some(kick_message) =
+                        self.version_kick_message(client_state, server_state)
+                {
+                    client_state.kick(kick_message);
+                    return Ok(batch);
+                }
+
+                let (hostname, forge_marker) = strip_forge_marker(&self.hostname);
+                if let Some(marker) = forge_marker {
+                    debug!("Client is using Forge with marker {marker:?}");
+                }
+                client_state.set_forge_marker(forge_marker);
+
+                let forwarding_result = check_bungee_cord(server_state, &hostname);
                 match forwarding_result {
                     LegacyForwardingResult::Invalid => {
-                        client_state.kick(PROXY_REQUIRED_KICK_MESSAGE);
+                        let messages = server_state.messages().get(client_state.locale());
+                        client_state.kick(messages.proxy_required.clone());
                         Err(PacketHandlerError::invalid_state(
-                            PROXY_REQUIRED_KICK_MESSAGE,
+                            "Player did not connect through a proxy",
                         ))
                     }
                     LegacyForwardingResult::Anonymous {
@@ -55,6 +72,12 @@ struct UnknownStateError(i32);
 trait GetStateProtocol {
     fn get_next_state(&self) -> Result<State, UnknownStateError>;
     fn get_protocol(&self) -> ProtocolVersion;
+    fn is_unknown_protocol(&self) -> bool;
+    fn version_kick_message(
+        &self,
+        client_state: &ClientState,
+        server_state: &ServerState,
+    ) -> Option<Component>;
 }
 
 impl GetStateProtocol for HandshakePacket {
@@ -75,11 +98,42 @@ impl GetStateProtocol for HandshakePacket {
             ProtocolVersion::from(self.protocol.inner())
         }
     }
+
+    fn is_unknown_protocol(&self) -> bool {
+        let protocol = self.protocol.inner();
+        protocol != -1 && ProtocolVersion::from_version_number(protocol).is_none()
+    }
+
+    /// Returns the kick message to send when the client's version is not allowed to join.
+    fn version_kick_message(
+        &self,
+        client_state: &ClientState,
+        server_state: &ServerState,
+    ) -> Option<Component> {
+        let version_range = server_state.version_range();
+        let protocol_version = self.get_protocol();
+        let messages = server_state.messages().get(client_state.locale());
+
+        let template = if self.is_unknown_protocol() {
+            &messages.unknown_protocol
+        } else if protocol_version.is_any() || version_range.contains(protocol_version) {
+            return None;
+        } else {
+            &messages.unsupported_version
+        };
+
+        let message = template
+            .replace("{protocol}", &self.protocol.inner().to_string())
+            .replace("{min}", version_range.min.humanize())
+            .replace("{max}", version_range.max.humanize());
+        Some(message)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::forwarding::forge_marker::ForgeMarker;
     use minecraft_protocol::prelude::VarInt;
 
     fn server_state() -> ServerState {
@@ -176,7 +230,7 @@ mod tests {
         let mut client_state = ClientState::default();
         let handshake_packet = HandshakePacket {
             protocol: VarInt::new(578),
-            hostname: "localhost\0127.0.0.1\06856201a9c1f49978608371019daf15e".to_string(),
+            hostname: "localhost\x00127.0.0.1\x006856201a9c1f49978608371019daf15e".to_string(),
             next_state: VarInt::new(2),
             port: 25565,
         };
@@ -190,6 +244,29 @@ mod tests {
         assert_eq!(client_state.state(), State::Login);
     }
 
+    #[test]
+    fn test_handshake_handler_should_strip_forge_marker() {
+        // Given
+        let mut client_state = ClientState::default();
+        let handshake_packet = HandshakePacket {
+            protocol: VarInt::new(758),
+            hostname: "localhost\x00FML3\x00\x00127.0.0.1\x006856201a9c1f49978608371019daf15e"
+                .to_string(),
+            next_state: VarInt::new(2),
+            port: 25565,
+        };
+
+        // When
+        handshake_packet
+            .handle(&mut client_state, &bungee_cord())
+            .unwrap();
+
+        // Then
+        assert_eq!(client_state.state(), State::Login);
+        assert_eq!(client_state.forge_marker(), Some(ForgeMarker::Fml3));
+        assert!(client_state.should_kick().is_none());
+    }
+
     #[test]
     fn test_handshake_handler_should_kick_when_bungee_cord_handshake_is_invalid() {
         // Given
@@ -207,8 +284,64 @@ mod tests {
         // Then
         assert_eq!(
             client_state.should_kick(),
-            Some(PROXY_REQUIRED_KICK_MESSAGE.to_string())
+            Some(bungee_cord().messages().get(None).proxy_required.clone())
         );
         assert!(matches!(result, Err(PacketHandlerError::InvalidState(_))));
     }
+
+    #[test]
+    fn test_handshake_handler_should_kick_when_version_is_out_of_range() {
+        // Given
+        let mut client_state = ClientState::default();
+        let mut server_state_builder = ServerState::builder();
+        server_state_builder
+            .version_range(ProtocolVersion::V1_8, ProtocolVersion::V1_12_2)
+            .unwrap();
+        let server_state = server_state_builder.build().unwrap();
+        let handshake_packet = HandshakePacket::localhost(578, 2);
+
+        // When
+        let result = handshake_packet.handle(&mut client_state, &server_state);
+
+        // Then
+        assert!(result.is_ok());
+        assert_eq!(client_state.state(), State::Login);
+        assert!(client_state.should_kick().is_some());
+    }
+
+    #[test]
+    fn test_handshake_handler_should_not_kick_when_pinging_out_of_range() {
+        // Given
+        let mut client_state = ClientState::default();
+        let mut server_state_builder = ServerState::builder();
+        server_state_builder
+            .version_range(ProtocolVersion::V1_8, ProtocolVersion::V1_12_2)
+            .unwrap();
+        let server_state = server_state_builder.build().unwrap();
+        let handshake_packet = HandshakePacket::localhost(578, 1);
+
+        // When
+        handshake_packet
+            .handle(&mut client_state, &server_state)
+            .unwrap();
+
+        // Then
+        assert!(client_state.should_kick().is_none());
+    }
+
+    #[test]
+    fn test_handshake_handler_should_kick_unknown_protocol_version() {
+        // Given
+        let mut client_state = ClientState::default();
+        let handshake_packet = HandshakePacket::localhost(1_073_741_900, 2);
+
+        // When
+        handshake_packet
+            .handle(&mut client_state, &server_state())
+            .unwrap();
+
+        // Then
+        let kick_message = client_state.should_kick().unwrap();
+        assert!(kick_message.to_json().contains("1073741900"));
+    }
 }
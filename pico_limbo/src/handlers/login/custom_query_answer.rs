@@ -1,7 +1,6 @@
 use crate::forwarding::check_velocity_key_integrity::read_velocity_key;
 use crate::forwarding::forwarding_result::ModernForwardingResult;
-use crate::handlers::login::login_start::fire_login_success;
-use crate::kick_messages::PROXY_REQUIRED_KICK_MESSAGE;
+use crate::handlers::login::login_start::{fire_login_success, start_login};
 use crate::server::batch::Batch;
 use crate::server::client_state::ClientState;
 use crate::server::game_profile::GameProfile;
@@ -20,7 +19,12 @@ impl PacketHandler for CustomQueryAnswerPacket {
         let mut batch = Batch::new();
         let client_message_id = client_state.get_velocity_login_message_id();
 
-        if server_state.is_modern_forwarding() && self.message_id.inner() == client_message_id {
+        if let Some(game_profile) = client_state.take_pending_forge_login(self.message_id.inner()) {
+            // The mod list sent by the client is ignored, the client disconnects by itself if incompatible
+            fire_login_success(&mut batch, client_state, server_state, game_profile)?;
+        } else if server_state.is_modern_forwarding()
+            && self.message_id.inner() == client_message_id
+        {
             let secret_key = server_state
                 .secret_key()
                 .map_err(|_| PacketHandlerError::custom("No secret key"))?;
@@ -34,10 +38,11 @@ impl PacketHandler for CustomQueryAnswerPacket {
                     textures,
                 } => {
                     let game_profile = GameProfile::new(&player_name, player_uuid, textures);
-                    fire_login_success(&mut batch, client_state, server_state, game_profile)?;
+                    start_login(&mut batch, client_state, server_state, game_profile)?;
                 }
                 ModernForwardingResult::Invalid => {
-                    client_state.kick(PROXY_REQUIRED_KICK_MESSAGE);
+                    let messages = server_state.messages().get(client_state.locale());
+                    client_state.kick(messages.forwarding_failed.clone());
                 }
             }
         }
@@ -49,7 +54,7 @@ impl PacketHandler for CustomQueryAnswerPacket {
 mod tests {
     use super::*;
     use futures::StreamExt;
-    use minecraft_protocol::prelude::{ProtocolVersion, VarInt};
+    use minecraft_protocol::prelude::{ProtocolVersion, Uuid, VarInt};
 
     fn velocity() -> ServerState {
         let mut builder = ServerState::builder();
@@ -88,7 +93,7 @@ mod tests {
         // Then
         assert_eq!(
             client_state.should_kick(),
-            Some(PROXY_REQUIRED_KICK_MESSAGE.to_string())
+            Some(server_state.messages().get(None).forwarding_failed.clone())
         );
         assert!(batch.into_stream().next().await.is_none());
     }
@@ -109,4 +114,26 @@ mod tests {
         assert!(client_state.should_kick().is_none());
         assert!(batch.into_stream().next().await.is_none());
     }
+
+    #[tokio::test]
+    async fn test_custom_query_answer_completes_forge_login() {
+        // Given
+        let server_state = ServerState::builder().build().unwrap();
+        let mut client_state = client();
+        let game_profile = GameProfile::new("Player", Uuid::nil(), None);
+        client_state.set_pending_forge_login(7, game_profile);
+
+        let pkt = packet(7, vec![]);
+
+        // When
+        let batch = pkt.handle(&mut client_state, &server_state).unwrap();
+        let mut batch = batch.into_stream();
+
+        // Then
+        assert!(matches!(
+            batch.next().await.unwrap(),
+            PacketRegistry::GameProfile(_)
+        ));
+        assert_eq!(client_state.get_username(), "Player");
+    }
 }
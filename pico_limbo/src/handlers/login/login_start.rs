@@ -1,5 +1,5 @@
+use crate::forwarding::forge_marker::ForgeMarker;
 use crate::handlers::configuration::send_play_packets;
-use crate::kick_messages::CLIENT_MODERN_FORWARDING_NOT_SUPPORTED_KICK_MESSAGE;
 use crate::server::batch::Batch;
 use crate::server::client_state::ClientState;
 use crate::server::game_profile::GameProfile;
@@ -20,30 +20,66 @@ impl PacketHandler for LoginStartPacket {
         server_state: &ServerState,
     ) -> Result<Batch<PacketRegistry>, PacketHandlerError> {
         let mut batch = Batch::new();
+        let messages = server_state.messages().get(client_state.locale());
+        if !client_state.has_reserved_slot() {
+            if !server_state.try_reserve_slot() {
+                client_state.kick(messages.server_full.clone());
+                return Ok(batch);
+            }
+            client_state.set_has_reserved_slot(true);
+        }
+
         if server_state.is_modern_forwarding() {
             if client_state.protocol_version().is_modern() {
                 login_start_velocity(&mut batch, client_state);
             } else {
-                client_state.kick(CLIENT_MODERN_FORWARDING_NOT_SUPPORTED_KICK_MESSAGE);
+                client_state.kick(messages.modern_forwarding_not_supported.clone());
             }
         } else {
             let game_profile: GameProfile = self.into();
-            fire_login_success(&mut batch, client_state, server_state, game_profile)?;
+            start_login(&mut batch, client_state, server_state, game_profile)?;
         }
         Ok(batch)
     }
 }
 
+fn random_message_id() -> i32 {
+    let mut rng = rand::rng();
+    rng.random()
+}
+
 fn login_start_velocity(batch: &mut Batch<PacketRegistry>, client_state: &mut ClientState) {
-    let message_id = {
-        let mut rng = rand::rng();
-        rng.random()
-    };
+    let message_id = random_message_id();
     client_state.set_velocity_login_message_id(message_id);
     let packet = CustomQueryPacket::velocity_info_channel(message_id);
     batch.queue(|| PacketRegistry::CustomQuery(packet));
 }
 
+/// FML2 and FML3 clients expect the server to send its mod list before the login succeeds.
+/// An empty mod list lets clients with vanilla compatible mods join.
+pub fn start_login(
+    batch: &mut Batch<PacketRegistry>,
+    client_state: &mut ClientState,
+    server_state: &ServerState,
+    game_profile: GameProfile,
+) -> Result<(), PacketHandlerError> {
+    match client_state.forge_marker() {
+        Some(marker) if marker.uses_login_wrapper() => {
+            let message_id = random_message_id();
+            client_state.set_pending_forge_login(message_id, game_profile);
+            let with_data_pack_registries = marker == ForgeMarker::Fml3;
+            let packet =
+                CustomQueryPacket::forge_empty_mod_list(message_id, with_data_pack_registries)
+                    .map_err(|_| {
+                        PacketHandlerError::custom("Failed to write the Forge mod list")
+                    })?;
+            batch.queue(|| PacketRegistry::CustomQuery(packet));
+            Ok(())
+        }
+        _ => fire_login_success(batch, client_state, server_state, game_profile),
+    }
+}
+
 pub fn fire_login_success(
     batch: &mut Batch<PacketRegistry>,
     client_state: &mut ClientState,
@@ -132,7 +168,13 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(
             client_state.should_kick(),
-            Some(CLIENT_MODERN_FORWARDING_NOT_SUPPORTED_KICK_MESSAGE.to_string())
+            Some(
+                server_state
+                    .messages()
+                    .get(None)
+                    .modern_forwarding_not_supported
+                    .clone()
+            )
         );
     }
 
@@ -204,4 +246,70 @@ mod tests {
         let _ = batch.next().await.unwrap();
         assert!(batch.next().await.is_some());
     }
+
+    #[tokio::test]
+    async fn test_should_send_mod_list_to_forge_clients() {
+        // Given
+        let server_state = vanilla();
+        let mut client_state = client(ProtocolVersion::V1_18_2);
+        client_state.set_forge_marker(Some(ForgeMarker::Fml3));
+        let pkt = packet();
+
+        // When
+        let batch = pkt.handle(&mut client_state, &server_state).unwrap();
+        let mut batch = batch.into_stream();
+
+        // Then
+        assert!(matches!(
+            batch.next().await.unwrap(),
+            PacketRegistry::CustomQuery(_)
+        ));
+        assert!(batch.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_should_not_send_mod_list_to_legacy_forge_clients() {
+        // Given
+        let server_state = vanilla();
+        let mut client_state = client(ProtocolVersion::V1_12_2);
+        client_state.set_forge_marker(Some(ForgeMarker::Fml));
+        let pkt = packet();
+
+        // When
+        let batch = pkt.handle(&mut client_state, &server_state).unwrap();
+        let mut batch = batch.into_stream();
+
+        // Then
+        assert!(matches!(
+            batch.next().await.unwrap(),
+            PacketRegistry::GameProfile(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_should_reserve_a_slot_while_logging_in() {
+        // Given
+        let mut builder = ServerState::builder();
+        builder.player_limit(1);
+        let server_state = builder.build().unwrap();
+        let mut first_client = client(ProtocolVersion::V1_21_4);
+        let mut second_client = client(ProtocolVersion::V1_21_4);
+
+        // When
+        packet().handle(&mut first_client, &server_state).unwrap();
+        packet().handle(&mut second_client, &server_state).unwrap();
+        server_state.release_slot();
+        let mut third_client = client(ProtocolVersion::V1_21_4);
+        packet().handle(&mut third_client, &server_state).unwrap();
+
+        // Then
+        assert!(first_client.has_reserved_slot());
+        assert!(first_client.should_kick().is_none());
+        assert!(!second_client.has_reserved_slot());
+        assert_eq!(
+            second_client.should_kick(),
+            Some(server_state.messages().get(None).server_full.clone())
+        );
+        assert!(third_client.should_kick().is_none());
+    }
 }
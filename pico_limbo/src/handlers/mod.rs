@@ -1,5 +1,7 @@
+mod client_information;
 mod configuration;
 mod handshake;
 mod login;
-mod play;
+pub mod play;
+mod plugin_message;
 mod status;
@@ -0,0 +1,19 @@
+use crate::server::batch::Batch;
+use crate::server::client_state::ClientState;
+use crate::server::packet_handler::{PacketHandler, PacketHandlerError};
+use crate::server::packet_registry::PacketRegistry;
+use crate::server_state::ServerState;
+use minecraft_packets::play::chunk_batch_received_packet::ChunkBatchReceivedPacket;
+
+impl PacketHandler for ChunkBatchReceivedPacket {
+    fn handle(
+        &self,
+        client_state: &mut ClientState,
+        _server_state: &ServerState,
+    ) -> Result<Batch<PacketRegistry>, PacketHandlerError> {
+        client_state
+            .chunk_sender_mut()
+            .on_batch_received(self.chunks_per_tick);
+        Ok(Batch::new())
+    }
+}
@@ -1,4 +1,7 @@
+mod chunk_batch_received;
 pub mod fetch_minecraft_profile;
 pub mod send_chunks_circularly;
 mod set_player_pos;
 mod set_player_position_and_rotation;
+mod set_player_rotation;
+pub mod weather;
@@ -1,9 +1,21 @@
+use crate::server::batch::Batch;
+use crate::server::chunk_view::ChunkView;
+use crate::server::client_state::ClientState;
+use crate::server::packet_handler::PacketHandlerError;
 use crate::server::packet_registry::PacketRegistry;
-use blocks_report::get_block_report_id_mapping;
+use crate::server_state::ServerState;
+use blocks_report::{
+    BlockReportIdMappingError, get_block_entity_types, get_block_report_id_mapping,
+};
 use minecraft_packets::play::chunk_data_and_update_light_packet::ChunkDataAndUpdateLightPacket;
+use minecraft_packets::play::set_chunk_cache_center_packet::SetCenterChunkPacket;
+use minecraft_packets::play::unload_chunk_packet::UnloadChunkPacket;
+use minecraft_packets::play::update_light_packet::UpdateLightPacket;
 use minecraft_packets::play::{VoidChunkContext, WorldContext};
 use minecraft_protocol::prelude::{Coordinates, Dimension, ProtocolVersion};
 use pico_structures::prelude::World;
+use registries::get_biome_index;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 #[derive(Copy, Clone)]
@@ -96,49 +108,85 @@ impl Iterator for SpiralIterator {
     }
 }
 
+/// Packets sent to the client to load a single chunk.
+pub struct ChunkPackets {
+    pub position: (i32, i32),
+    pub packets: Vec<PacketRegistry>,
+}
+
 pub struct CircularChunkPacketIterator {
     biome_index: i32,
     dimension: Dimension,
     schematic_context: Option<WorldContext>,
     spiral_iterator: SpiralIterator,
+    /// Chunks already loaded by the client are skipped
+    loaded_chunks: HashSet<(i32, i32)>,
+    /// Since 1.14 included, up until 1.17.1 included, the light is sent in its own packet
+    sends_light_separately: bool,
+    /// Prior to 1.9, a chunk without any section unloads the chunk instead
+    skips_void_chunks: bool,
 }
 
 impl CircularChunkPacketIterator {
     pub fn new(
-        center_chunk: (i32, i32),
-        view_distance: i32,
+        view: ChunkView,
+        loaded_chunks: HashSet<(i32, i32)>,
         world: Option<Arc<World>>,
         biome_index: i32,
         dimension: Dimension,
         protocol_version: ProtocolVersion,
-    ) -> Self {
-        let (center_x, center_z) = center_chunk;
+    ) -> Result<Self, BlockReportIdMappingError> {
+        let (center_x, center_z) = view.center();
         let paste_origin = Coordinates::new_uniform(0);
 
+        // Since 1.18 included, block entities are sent with the ID of their type
+        let block_entity_types =
+            if world.is_some() && protocol_version.is_after_inclusive(ProtocolVersion::V1_18) {
+                Some(Arc::new(get_block_entity_types(protocol_version)?))
+            } else {
+                None
+            };
+        // Since 1.18 included, the biomes of the world are sent per 4×4×4 cell
+        let biome_ids = world.as_ref().and_then(|world| {
+            protocol_version
+                .is_after_inclusive(ProtocolVersion::V1_18)
+                .then(|| Arc::new(world_biome_ids(world, protocol_version)))
+        });
         let schematic_context: Option<WorldContext> = get_block_report_id_mapping(protocol_version)
             .map_or(None, |report_id_mapping| {
                 world.map(|world_arc| WorldContext {
                     paste_origin,
                     world: world_arc,
                     report_id_mapping: Arc::new(report_id_mapping),
+                    block_entity_types,
+                    biome_ids,
                 })
             });
 
-        Self {
+        Ok(Self {
             biome_index,
             dimension,
             schematic_context,
-            spiral_iterator: SpiralIterator::new(center_x, center_z, view_distance),
-        }
+            spiral_iterator: SpiralIterator::new(center_x, center_z, view.radius()),
+            loaded_chunks,
+            sends_light_separately: protocol_version
+                .between_inclusive(ProtocolVersion::V1_14, ProtocolVersion::V1_17_1),
+            skips_void_chunks: protocol_version.is_before_inclusive(ProtocolVersion::V1_8),
+        })
     }
 }
 
-impl Iterator for CircularChunkPacketIterator {
-    type Item = PacketRegistry;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let (chunk_x, chunk_z) = self.spiral_iterator.next()?;
+/// Biomes unknown to the version are left out, their cells use the biome of the world.
+fn world_biome_ids(world: &World, protocol_version: ProtocolVersion) -> HashMap<String, i32> {
+    world
+        .biome_names()
+        .iter()
+        .filter_map(|name| Some((name.clone(), get_biome_index(protocol_version, name)?)))
+        .collect()
+}
 
+impl CircularChunkPacketIterator {
+    fn chunk_packet(&self, chunk_x: i32, chunk_z: i32) -> ChunkDataAndUpdateLightPacket {
         let chunk_context = VoidChunkContext {
             chunk_x,
             chunk_z,
@@ -146,11 +194,280 @@ impl Iterator for CircularChunkPacketIterator {
             dimension: self.dimension,
         };
 
-        let packet = match &self.schematic_context {
+        match &self.schematic_context {
             Some(context) => ChunkDataAndUpdateLightPacket::from_structure(chunk_context, context),
             None => ChunkDataAndUpdateLightPacket::void(chunk_context),
+        }
+    }
+}
+
+impl Iterator for CircularChunkPacketIterator {
+    type Item = ChunkPackets;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (chunk_x, chunk_z, packet) = loop {
+            let (chunk_x, chunk_z) = self.spiral_iterator.next()?;
+            if self.loaded_chunks.contains(&(chunk_x, chunk_z)) {
+                continue;
+            }
+            let packet = self.chunk_packet(chunk_x, chunk_z);
+            if !self.skips_void_chunks || packet.has_blocks() {
+                break (chunk_x, chunk_z, packet);
+            }
         };
+        let has_blocks = packet.has_blocks();
+
+        let mut packets = Vec::with_capacity(2);
+        if self.sends_light_separately && has_blocks {
+            let packet = UpdateLightPacket::new(chunk_x, chunk_z, packet.light_data().clone());
+            packets.push(PacketRegistry::UpdateLight(Box::new(packet)));
+        }
+        packets.push(PacketRegistry::ChunkDataAndUpdateLight(Box::new(packet)));
+
+        Some(ChunkPackets {
+            position: (chunk_x, chunk_z),
+            packets,
+        })
+    }
+}
+
+const F64_CONVERSION_FAILED: &str = "Conversion failed: Invalid or out-of-range float";
+
+/// Network ID of the biome, versions that do not know the biome get plains instead.
+fn biome_index(protocol_version: ProtocolVersion, biome: &str) -> Option<i32> {
+    const FALLBACK_BIOME: &str = "minecraft:plains";
+    get_biome_index(protocol_version, biome)
+        .or_else(|| get_biome_index(protocol_version, FALLBACK_BIOME))
+}
+
+fn safe_f64_to_i32(f: f64) -> Option<i32> {
+    if f.is_finite() && f >= f64::from(i32::MIN) && f <= f64::from(i32::MAX) {
+        #[allow(clippy::cast_possible_truncation)]
+        Some(f as i32)
+    } else {
+        None
+    }
+}
+
+fn world_position_to_chunk_position(
+    position: (f64, f64),
+) -> Result<(i32, i32), PacketHandlerError> {
+    let chunk_x = safe_f64_to_i32((position.0 / 16.0).floor())
+        .ok_or_else(|| PacketHandlerError::invalid_state(F64_CONVERSION_FAILED))?;
+    let chunk_z = safe_f64_to_i32((position.1 / 16.0).floor())
+        .ok_or_else(|| PacketHandlerError::invalid_state(F64_CONVERSION_FAILED))?;
+    Ok((chunk_x, chunk_z))
+}
+
+/// Queues the chunks around the given position that the client did not load yet, nearest first,
+/// and unloads the chunks that went out of view.
+/// The first chunks are added to the batch, the others are sent over time by the chunk sender.
+/// Nothing is sent while the player stays within the same chunk.
+pub fn send_chunks(
+    batch: &mut Batch<PacketRegistry>,
+    client_state: &mut ClientState,
+    server_state: &ServerState,
+    position: (f64, f64),
+) -> Result<(), PacketHandlerError> {
+    let protocol_version = client_state.protocol_version();
+    let center_chunk = world_position_to_chunk_position(position)?;
+    let view_distance = client_state.effective_view_distance(server_state.view_distance());
+    let view = ChunkView::new(center_chunk, view_distance);
+
+    if client_state.chunk_sender().view() == Some(view) {
+        return Ok(());
+    }
+
+    let biome_id = biome_index(protocol_version, server_state.biome()).ok_or_else(|| {
+        PacketHandlerError::InvalidState(format!(
+            "Cannot find biome index for version {protocol_version}"
+        ))
+    })?;
+    let dimension = server_state.spawn_dimension();
+
+    let unloaded_chunks = client_state.chunk_sender_mut().unload_outside(view);
+    batch.chain_iter(unloaded_chunks.into_iter().map(move |(chunk_x, chunk_z)| {
+        if protocol_version.is_after_inclusive(ProtocolVersion::V1_9) {
+            PacketRegistry::UnloadChunk(UnloadChunkPacket::new(chunk_x, chunk_z))
+        } else {
+            let chunk_context = VoidChunkContext {
+                chunk_x,
+                chunk_z,
+                biome_index: biome_id,
+                dimension,
+            };
+            let packet = ChunkDataAndUpdateLightPacket::void(chunk_context);
+            PacketRegistry::ChunkDataAndUpdateLight(Box::new(packet))
+        }
+    }));
+
+    if protocol_version.is_after_inclusive(ProtocolVersion::V1_14) {
+        let packet = SetCenterChunkPacket::new(center_chunk.0, center_chunk.1);
+        batch.queue(|| PacketRegistry::SetCenterChunk(packet));
+    }
+
+    let chunk_sender = client_state.chunk_sender_mut();
+    let pending_chunks = CircularChunkPacketIterator::new(
+        view,
+        chunk_sender.loaded_chunks().clone(),
+        server_state.world(),
+        biome_id,
+        dimension,
+        protocol_version,
+    )
+    .map_err(|error| PacketHandlerError::InvalidState(error.to_string()))?;
+    chunk_sender.load(view, pending_chunks);
+    batch.chain_iter(chunk_sender.next_batch(protocol_version));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn server_state() -> ServerState {
+        let mut builder = ServerState::builder();
+        builder.view_distance(1);
+        builder.build().unwrap()
+    }
+
+    fn client(protocol_version: ProtocolVersion) -> ClientState {
+        let mut cs = ClientState::default();
+        cs.set_protocol_version(protocol_version);
+        cs
+    }
+
+    async fn send(
+        client_state: &mut ClientState,
+        server_state: &ServerState,
+        position: (f64, f64),
+    ) -> Vec<PacketRegistry> {
+        let mut batch = Batch::new();
+        send_chunks(&mut batch, client_state, server_state, position).unwrap();
+        batch.into_stream().collect::<Vec<_>>().await
+    }
+
+    fn count_chunks(packets: &[PacketRegistry]) -> usize {
+        packets
+            .iter()
+            .filter(|packet| matches!(packet, PacketRegistry::ChunkDataAndUpdateLight(_)))
+            .count()
+    }
+
+    fn count_unloads(packets: &[PacketRegistry]) -> usize {
+        packets
+            .iter()
+            .filter(|packet| matches!(packet, PacketRegistry::UnloadChunk(_)))
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_should_send_every_chunk_in_view_on_join() {
+        // Given
+        let mut client_state = client(ProtocolVersion::V1_21_4);
+        let server_state = server_state();
+
+        // When
+        let packets = send(&mut client_state, &server_state, (8.0, 8.0)).await;
+
+        // Then
+        assert!(matches!(packets[0], PacketRegistry::SetCenterChunk(_)));
+        assert!(matches!(packets[1], PacketRegistry::ChunkBatchStart(_)));
+        assert_eq!(count_chunks(&packets), 9);
+        assert_eq!(count_unloads(&packets), 0);
+        assert_eq!(
+            client_state.chunk_sender().view(),
+            Some(ChunkView::new((0, 0), 1))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_do_nothing_within_the_same_chunk() {
+        // Given
+        let mut client_state = client(ProtocolVersion::V1_21_4);
+        let server_state = server_state();
+        send(&mut client_state, &server_state, (8.0, 8.0)).await;
+
+        // When
+        let packets = send(&mut client_state, &server_state, (15.5, 0.5)).await;
+
+        // Then
+        assert!(packets.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_should_stream_chunks_when_crossing_a_chunk_boundary() {
+        // Given
+        let mut client_state = client(ProtocolVersion::V1_21_4);
+        let server_state = server_state();
+        send(&mut client_state, &server_state, (8.0, 8.0)).await;
+        client_state.chunk_sender_mut().on_batch_received(9.0);
+
+        // When
+        let packets = send(&mut client_state, &server_state, (16.5, 8.0)).await;
+
+        // Then
+        assert_eq!(count_unloads(&packets), 3);
+        assert!(matches!(packets[3], PacketRegistry::SetCenterChunk(_)));
+        assert_eq!(count_chunks(&packets), 3);
+        assert_eq!(
+            client_state.chunk_sender().view(),
+            Some(ChunkView::new((1, 0), 1))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_wait_for_the_previous_batch_before_streaming() {
+        // Given
+        let mut client_state = client(ProtocolVersion::V1_21_4);
+        let server_state = server_state();
+        send(&mut client_state, &server_state, (8.0, 8.0)).await;
+
+        // When
+        let packets = send(&mut client_state, &server_state, (16.5, 8.0)).await;
+        let has_pending_chunks = client_state.chunk_sender().has_pending_chunks();
+        client_state.chunk_sender_mut().on_batch_received(9.0);
+        let next_batch = client_state
+            .chunk_sender_mut()
+            .next_batch(ProtocolVersion::V1_21_4);
+
+        // Then
+        assert_eq!(count_unloads(&packets), 3);
+        assert_eq!(count_chunks(&packets), 0);
+        assert!(has_pending_chunks);
+        assert_eq!(count_chunks(&next_batch), 3);
+    }
+
+    #[test]
+    fn test_unknown_biome_falls_back_to_plains() {
+        // Given
+        let protocol_version = ProtocolVersion::V1_19;
+
+        // When
+        let biome_id = biome_index(protocol_version, "minecraft:cherry_grove");
+
+        // Then
+        assert!(biome_id.is_some());
+        assert_eq!(
+            biome_id,
+            get_biome_index(protocol_version, "minecraft:plains")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_legacy_clients_do_not_load_void_chunks() {
+        // Given
+        let mut client_state = client(ProtocolVersion::V1_8);
+        let server_state = server_state();
+        send(&mut client_state, &server_state, (8.0, 8.0)).await;
+
+        // When
+        let packets = send(&mut client_state, &server_state, (-8.0, 8.0)).await;
 
-        Some(PacketRegistry::ChunkDataAndUpdateLight(Box::new(packet)))
+        // Then
+        assert!(packets.is_empty());
+        assert!(client_state.chunk_sender().loaded_chunks().is_empty());
     }
 }
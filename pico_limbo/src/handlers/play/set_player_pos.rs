@@ -1,8 +1,9 @@
-use crate::handlers::play::set_player_position_and_rotation::teleport_player_to_spawn;
+use crate::handlers::play::set_player_position_and_rotation::move_player;
 use crate::server::batch::Batch;
 use crate::server::client_state::ClientState;
 use crate::server::packet_handler::{PacketHandler, PacketHandlerError};
 use crate::server::packet_registry::PacketRegistry;
+use crate::server::player_position::PlayerPosition;
 use crate::server_state::ServerState;
 use minecraft_packets::play::set_player_position_packet::SetPlayerPositionPacket;
 
@@ -12,10 +13,9 @@ impl PacketHandler for SetPlayerPositionPacket {
         client_state: &mut ClientState,
         server_state: &ServerState,
     ) -> Result<Batch<PacketRegistry>, PacketHandlerError> {
-        Ok(teleport_player_to_spawn(
-            client_state,
-            server_state,
-            self.feet_y,
-        ))
+        let (x, y, z) = self.position();
+        let PlayerPosition { yaw, pitch, .. } = client_state.position();
+        let position = PlayerPosition::new(x, y, z).with_rotation(yaw, pitch);
+        move_player(client_state, server_state, position)
     }
 }
@@ -1,13 +1,14 @@
 use crate::handlers::configuration::send_message;
+use crate::handlers::play::send_chunks_circularly::send_chunks;
 use crate::server::batch::Batch;
 use crate::server::client_state::ClientState;
 use crate::server::packet_handler::{PacketHandler, PacketHandlerError};
 use crate::server::packet_registry::PacketRegistry;
-use crate::server_state::{Boundaries, ServerState};
+use crate::server::player_position::PlayerPosition;
+use crate::server_state::{BoundaryAction, ServerState};
 use minecraft_packets::play::set_player_position_and_rotation_packet::SetPlayerPositionAndRotationPacket;
 use minecraft_packets::play::synchronize_player_position_packet::SynchronizePlayerPositionPacket;
-
-const FALL_SPEED: f64 = 3.8855;
+use pico_text_component::prelude::Component;
 
 impl PacketHandler for SetPlayerPositionAndRotationPacket {
     fn handle(
@@ -15,65 +16,133 @@ impl PacketHandler for SetPlayerPositionAndRotationPacket {
         client_state: &mut ClientState,
         server_state: &ServerState,
     ) -> Result<Batch<PacketRegistry>, PacketHandlerError> {
-        Ok(teleport_player_to_spawn(
+        let position =
+            PlayerPosition::new(self.x, self.feet_y, self.z).with_rotation(self.yaw, self.pitch);
+        move_player(client_state, server_state, position)
+    }
+}
+
+/// Streams the chunks around the player, or sends the player back when leaving the boundaries
+/// or the world border.
+pub fn move_player(
+    client_state: &mut ClientState,
+    server_state: &ServerState,
+    position: PlayerPosition,
+) -> Result<Batch<PacketRegistry>, PacketHandlerError> {
+    let mut batch = enforce_boundaries(client_state, server_state, position);
+    if client_state.chunk_sender().view().is_some() {
+        send_chunks(
+            &mut batch,
             client_state,
             server_state,
-            self.feet_y,
-        ))
+            (position.x, position.z),
+        )?;
     }
+    Ok(batch)
 }
 
-pub fn teleport_player_to_spawn(
+/// Teleports the player back or kicks the player when leaving the boundaries or the world border.
+/// Positions sent before the client received the teleport are ignored.
+pub fn enforce_boundaries(
     client_state: &mut ClientState,
     server_state: &ServerState,
-    feet_y: f64,
+    position: PlayerPosition,
 ) -> Batch<PacketRegistry> {
     let mut batch = Batch::new();
-    if let Some(Boundaries {
-        teleport_message,
-        min_y,
-    }) = server_state.boundaries()
-    {
-        let previous_position = client_state.get_y_position();
-        client_state.set_feet_position(feet_y);
+    client_state.set_position(position);
 
-        if feet_y < f64::from(*min_y) {
-            let difference = (previous_position - feet_y).abs();
-
-            if previous_position >= f64::from(*min_y) && difference <= FALL_SPEED {
-                let (x, y, z) = server_state.spawn_position();
-                let packet = SynchronizePlayerPositionPacket::new(x, y, z);
-                batch.queue(|| PacketRegistry::SynchronizePlayerPosition(packet));
+    let PlayerPosition { x, y, z, .. } = position;
+    let messages = server_state.messages().get(client_state.locale());
+    let outside_world_border = server_state
+        .world_border()
+        .is_some_and(|world_border| !world_border.contains(x, z));
+    let (action, message) = if outside_world_border {
+        (
+            BoundaryAction::TeleportToSpawn,
+            messages.world_border_teleport.as_ref(),
+        )
+    } else if let Some((boundary, action)) = server_state
+        .boundaries()
+        .and_then(|boundaries| boundaries.violation(x, y, z))
+    {
+        (action, messages.boundary_teleport_for(boundary))
+    } else {
+        client_state.set_last_valid_position(position);
+        client_state.set_outside_boundaries(false);
+        return batch;
+    };
 
-                if let Some(content) = teleport_message {
-                    send_message(&mut batch, content, client_state.protocol_version());
-                }
+    if client_state.is_outside_boundaries() {
+        return batch;
+    }
+    client_state.set_outside_boundaries(true);
 
-                client_state.set_feet_position(y);
-            }
+    let (spawn_x, spawn_y, spawn_z) = server_state.spawn_position();
+    let spawn = PlayerPosition::new(spawn_x, spawn_y, spawn_z);
+    match action {
+        BoundaryAction::TeleportToSpawn => {
+            queue_teleport(&mut batch, client_state, spawn, message);
         }
+        BoundaryAction::PushBack => {
+            let last_valid_position = client_state
+                .last_valid_position()
+                .unwrap_or(spawn)
+                .with_rotation(position.yaw, position.pitch);
+            queue_teleport(&mut batch, client_state, last_valid_position, message);
+        }
+        BoundaryAction::Kick => client_state.kick(messages.boundary_kick.clone()),
     }
     batch
 }
 
+fn queue_teleport(
+    batch: &mut Batch<PacketRegistry>,
+    client_state: &mut ClientState,
+    target: PlayerPosition,
+    message: Option<&Component>,
+) {
+    let packet = SynchronizePlayerPositionPacket::new(target.x, target.y, target.z)
+        .set_rotation(target.yaw, target.pitch);
+    batch.queue(|| PacketRegistry::SynchronizePlayerPosition(packet));
+
+    if let Some(content) = message {
+        send_message(batch, content, client_state.protocol_version());
+    }
+
+    client_state.set_position(target);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::server_state::{Boundaries, BoundaryMessages, HorizontalBoundary, WorldBorder};
     use futures::StreamExt;
     use minecraft_protocol::prelude::{ProtocolVersion, State};
 
-    fn server_state_with_min_y(min_y: i32, message: Option<String>) -> ServerState {
+    fn server_state_with_boundaries(boundaries: Boundaries, message: &str) -> ServerState {
         let mut builder = ServerState::builder();
         builder.spawn_position((0.0, 100.0, 0.0));
-        if let Some(content) = message {
-            builder.boundaries(min_y, content).unwrap();
-        } else {
-            builder.boundaries(min_y, "").unwrap();
-        }
-
+        builder.boundaries(
+            boundaries,
+            BoundaryMessages {
+                floor_teleport: message.to_owned(),
+                kick: "Kick test".to_owned(),
+                ..Default::default()
+            },
+        );
         builder.build().unwrap()
     }
 
+    fn server_state_with_min_y(min_y: i32, message: &str) -> ServerState {
+        server_state_with_boundaries(
+            Boundaries {
+                min_y,
+                ..Default::default()
+            },
+            message,
+        )
+    }
+
     fn client_state() -> ClientState {
         let mut cs = ClientState::default();
         cs.set_protocol_version(ProtocolVersion::V1_20_5);
@@ -81,14 +150,18 @@ mod tests {
         cs
     }
 
+    fn at(x: f64, y: f64, z: f64) -> PlayerPosition {
+        PlayerPosition::new(x, y, z)
+    }
+
     #[tokio::test]
     async fn test_should_teleport_and_message_player() {
         // Given
         let mut client_state = client_state();
-        let server_state = server_state_with_min_y(0, Some("Direct teleport test".to_string()));
+        let server_state = server_state_with_min_y(0, "Direct teleport test");
 
         // When
-        let batch = teleport_player_to_spawn(&mut client_state, &server_state, -1.0);
+        let batch = enforce_boundaries(&mut client_state, &server_state, at(0.0, -1.0, 0.0));
         let mut batch = batch.into_stream();
 
         // Then
@@ -101,16 +174,17 @@ mod tests {
             PacketRegistry::SystemChatMessage(_) | PacketRegistry::LegacyChatMessage(_)
         ));
         assert!(batch.next().await.is_none());
+        assert_eq!(client_state.position(), at(0.0, 100.0, 0.0));
     }
 
     #[tokio::test]
     async fn test_should_teleport_player() {
         // Given
         let mut client_state = client_state();
-        let server_state = server_state_with_min_y(0, None);
+        let server_state = server_state_with_min_y(0, "");
 
         // When
-        let batch = teleport_player_to_spawn(&mut client_state, &server_state, -1.0);
+        let batch = enforce_boundaries(&mut client_state, &server_state, at(0.0, -1.0, 0.0));
         let mut batch = batch.into_stream();
 
         // Then
@@ -125,66 +199,230 @@ mod tests {
     async fn test_should_do_nothing() {
         // Given
         let mut client_state = client_state();
-        let server_state = server_state_with_min_y(0, None);
+        let server_state = server_state_with_min_y(0, "");
 
         // When
-        let batch = teleport_player_to_spawn(&mut client_state, &server_state, 10.0);
+        let batch = enforce_boundaries(&mut client_state, &server_state, at(0.0, 10.0, 0.0));
         let mut batch = batch.into_stream();
 
         // Then
         assert!(batch.next().await.is_none());
+        assert_eq!(client_state.last_valid_position(), Some(at(0.0, 10.0, 0.0)));
     }
 
     #[tokio::test]
-    async fn test_should_teleport_once_two_packets() {
+    async fn test_should_teleport_once_until_back_inside() {
         // Given
-        const STARTING_POSITION: f64 = 1.0;
         let mut client_state = client_state();
-        client_state.set_feet_position(STARTING_POSITION);
-        let server_state = server_state_with_min_y(0, None);
+        let server_state = server_state_with_min_y(0, "");
 
         // When
-        let mut stream1 =
-            teleport_player_to_spawn(&mut client_state, &server_state, STARTING_POSITION)
-                .into_stream();
-
-        let mut stream2 = teleport_player_to_spawn(
-            &mut client_state,
-            &server_state,
-            STARTING_POSITION - FALL_SPEED,
-        )
-        .into_stream();
-
-        let subsequent_streams = (2..=10).map(|i| {
-            teleport_player_to_spawn(
-                &mut client_state,
-                &server_state,
-                FALL_SPEED.mul_add(-f64::from(i), STARTING_POSITION),
-            )
-            .into_stream()
-        });
+        let inside = enforce_boundaries(&mut client_state, &server_state, at(0.0, 1.0, 0.0));
+        let below = enforce_boundaries(&mut client_state, &server_state, at(0.0, -3.0, 0.0));
+        let subsequent_streams: Vec<_> = (2..=10)
+            .map(|i| {
+                let position = at(0.0, -3.0 * f64::from(i), 0.0);
+                enforce_boundaries(&mut client_state, &server_state, position).into_stream()
+            })
+            .collect();
+        let back_inside = enforce_boundaries(&mut client_state, &server_state, at(0.0, 100.0, 0.0));
+        let below_again = enforce_boundaries(&mut client_state, &server_state, at(0.0, -1.0, 0.0));
 
         // Then
         assert!(
-            stream1.next().await.is_none(),
+            inside.into_stream().next().await.is_none(),
             "First packet should do nothing"
         );
-
+        let mut below = below.into_stream();
         assert!(
             matches!(
-                stream2.next().await.unwrap(),
+                below.next().await.unwrap(),
                 PacketRegistry::SynchronizePlayerPosition(_)
             ),
             "Second packet should trigger a teleport"
         );
-        assert!(stream2.next().await.is_none());
-
-        for (i, mut stream) in subsequent_streams.enumerate() {
+        assert!(below.next().await.is_none());
+        for (i, mut stream) in subsequent_streams.into_iter().enumerate() {
             assert!(
                 stream.next().await.is_none(),
                 "Subsequent packet #{} should not trigger another teleport",
                 i + 3
             );
         }
+        assert!(back_inside.into_stream().next().await.is_none());
+        assert!(below_again.into_stream().next().await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_should_push_back_below_the_ceiling() {
+        // Given
+        let mut client_state = client_state();
+        let server_state = server_state_with_boundaries(
+            Boundaries {
+                min_y: 0,
+                max_y: Some(200),
+                vertical_action: BoundaryAction::PushBack,
+                horizontal: None,
+            },
+            "",
+        );
+        let flying = at(10.0, 150.0, -5.0).with_rotation(90.0, 45.0);
+
+        // When
+        let _ = enforce_boundaries(&mut client_state, &server_state, flying);
+        let batch = enforce_boundaries(
+            &mut client_state,
+            &server_state,
+            at(12.0, 201.0, -5.0).with_rotation(180.0, -30.0),
+        );
+        let mut batch = batch.into_stream();
+
+        // Then
+        let Some(PacketRegistry::SynchronizePlayerPosition(packet)) = batch.next().await else {
+            panic!("expected a teleport");
+        };
+        assert_eq!((packet.x, packet.y, packet.z), (10.0, 150.0, -5.0));
+        assert_eq!((packet.yaw, packet.pitch), (180.0, -30.0));
+        assert!(batch.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_should_push_back_to_spawn_without_valid_position() {
+        // Given
+        let mut client_state = client_state();
+        let server_state = server_state_with_boundaries(
+            Boundaries {
+                min_y: 0,
+                vertical_action: BoundaryAction::PushBack,
+                ..Default::default()
+            },
+            "",
+        );
+
+        // When
+        let batch = enforce_boundaries(&mut client_state, &server_state, at(5.0, -10.0, 5.0));
+        let mut batch = batch.into_stream();
+
+        // Then
+        let Some(PacketRegistry::SynchronizePlayerPosition(packet)) = batch.next().await else {
+            panic!("expected a teleport");
+        };
+        assert_eq!((packet.x, packet.y, packet.z), (0.0, 100.0, 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_should_send_the_message_of_the_boundary_left() {
+        // Given
+        let mut client_state = client_state();
+        let server_state = server_state_with_boundaries(
+            Boundaries {
+                min_y: 0,
+                max_y: Some(200),
+                ..Default::default()
+            },
+            "Bottom of the world",
+        );
+
+        // When
+        let above = enforce_boundaries(&mut client_state, &server_state, at(0.0, 201.0, 0.0));
+        let _ = enforce_boundaries(&mut client_state, &server_state, at(0.0, 100.0, 0.0));
+        let below = enforce_boundaries(&mut client_state, &server_state, at(0.0, -1.0, 0.0));
+
+        // Then
+        let above: Vec<_> = above.into_stream().collect().await;
+        let below: Vec<_> = below.into_stream().collect().await;
+        assert_eq!(above.len(), 1, "the ceiling message is empty");
+        assert_eq!(below.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_should_kick_outside_the_horizontal_boundary() {
+        // Given
+        let mut client_state = client_state();
+        let server_state = server_state_with_boundaries(
+            Boundaries {
+                min_y: 0,
+                horizontal: Some((
+                    HorizontalBoundary::Radius {
+                        center: (0.0, 0.0),
+                        radius: 50.0,
+                    },
+                    BoundaryAction::Kick,
+                )),
+                ..Default::default()
+            },
+            "",
+        );
+
+        // When
+        let inside = enforce_boundaries(&mut client_state, &server_state, at(30.0, 64.0, 40.0));
+        let kicked_before = client_state.should_kick().is_some();
+        let outside = enforce_boundaries(&mut client_state, &server_state, at(30.0, 64.0, 41.0));
+
+        // Then
+        assert!(inside.into_stream().next().await.is_none());
+        assert!(!kicked_before);
+        assert!(outside.into_stream().next().await.is_none());
+        assert!(client_state.should_kick().is_some());
+    }
+
+    #[test]
+    fn test_box_boundary_should_include_its_edges() {
+        // Given
+        let boundary = HorizontalBoundary::Box {
+            min: (-10.0, 0.0),
+            max: (10.0, 20.0),
+        };
+
+        // When
+        let inside = [(-10.0, 0.0), (10.0, 20.0), (0.0, 5.0)].map(|(x, z)| boundary.contains(x, z));
+        let outside = [(-10.5, 0.0), (0.0, 20.5)].map(|(x, z)| boundary.contains(x, z));
+
+        // Then
+        assert_eq!(inside, [true; 3]);
+        assert_eq!(outside, [false; 2]);
+    }
+
+    #[tokio::test]
+    async fn test_should_teleport_once_outside_world_border() {
+        // Given
+        let mut client_state = client_state();
+        let mut builder = ServerState::builder();
+        builder.spawn_position((0.0, 100.0, 0.0));
+        builder.world_border(
+            WorldBorder {
+                center: (0.0, 0.0),
+                diameter: 20.0,
+                warning_distance: 5,
+                warning_time: 15,
+            },
+            "Border teleport test",
+        );
+        let server_state = builder.build().unwrap();
+
+        // When
+        let inside = enforce_boundaries(&mut client_state, &server_state, at(10.0, 64.0, -10.0));
+        let outside = enforce_boundaries(&mut client_state, &server_state, at(10.5, 64.0, 0.0));
+        let still_outside =
+            enforce_boundaries(&mut client_state, &server_state, at(11.0, 64.0, 0.0));
+        let back_inside = enforce_boundaries(&mut client_state, &server_state, at(0.0, 64.0, 0.0));
+        let outside_again =
+            enforce_boundaries(&mut client_state, &server_state, at(0.0, 64.0, -12.0));
+
+        // Then
+        assert!(inside.into_stream().next().await.is_none());
+        let mut batch = outside.into_stream();
+        assert!(matches!(
+            batch.next().await.unwrap(),
+            PacketRegistry::SynchronizePlayerPosition(_)
+        ));
+        assert!(matches!(
+            batch.next().await.unwrap(),
+            PacketRegistry::SystemChatMessage(_) | PacketRegistry::LegacyChatMessage(_)
+        ));
+        assert!(batch.next().await.is_none());
+        assert!(still_outside.into_stream().next().await.is_none());
+        assert!(back_inside.into_stream().next().await.is_none());
+        assert!(outside_again.into_stream().next().await.is_some());
     }
 }
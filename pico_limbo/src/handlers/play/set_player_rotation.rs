@@ -0,0 +1,47 @@
+use crate::handlers::play::set_player_position_and_rotation::move_player;
+use crate::server::batch::Batch;
+use crate::server::client_state::ClientState;
+use crate::server::packet_handler::{PacketHandler, PacketHandlerError};
+use crate::server::packet_registry::PacketRegistry;
+use crate::server_state::ServerState;
+use minecraft_packets::play::set_player_rotation_packet::SetPlayerRotationPacket;
+
+impl PacketHandler for SetPlayerRotationPacket {
+    fn handle(
+        &self,
+        client_state: &mut ClientState,
+        server_state: &ServerState,
+    ) -> Result<Batch<PacketRegistry>, PacketHandlerError> {
+        let position = client_state.position().with_rotation(self.yaw, self.pitch);
+        move_player(client_state, server_state, position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::player_position::PlayerPosition;
+
+    #[test]
+    fn test_should_keep_position_and_update_rotation() {
+        // Given
+        let mut client_state = ClientState::default();
+        let server_state = ServerState::builder().build().unwrap();
+        client_state.set_position(PlayerPosition::new(1.0, 64.0, -2.0));
+        let packet = SetPlayerRotationPacket {
+            yaw: 90.0,
+            pitch: -15.0,
+            v1_21_4_flags: 0,
+            on_ground: true,
+        };
+
+        // When
+        packet.handle(&mut client_state, &server_state).unwrap();
+
+        // Then
+        assert_eq!(
+            client_state.position(),
+            PlayerPosition::new(1.0, 64.0, -2.0).with_rotation(90.0, -15.0)
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use crate::server::batch::Batch;
+use crate::server::packet_registry::PacketRegistry;
+use crate::server_state::Weather;
+use minecraft_packets::play::game_event_packet::GameEventPacket;
+
+/// Starts or stops the rain, the levels are set right away instead of fading in.
+pub fn send_weather_packets(batch: &mut Batch<PacketRegistry>, weather: Weather) {
+    let (rain_level, thunder_level) = match weather {
+        Weather::Clear => (0.0, 0.0),
+        Weather::Rain => (1.0, 0.0),
+        Weather::Thunder => (1.0, 1.0),
+    };
+    let packet = if weather == Weather::Clear {
+        GameEventPacket::end_raining()
+    } else {
+        GameEventPacket::begin_raining()
+    };
+    batch.queue(|| PacketRegistry::GameEvent(packet));
+    batch.queue(move || PacketRegistry::GameEvent(GameEventPacket::rain_level(rain_level)));
+    batch.queue(move || PacketRegistry::GameEvent(GameEventPacket::thunder_level(thunder_level)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_should_start_the_thunder() {
+        // Given
+        let mut batch = Batch::new();
+
+        // When
+        send_weather_packets(&mut batch, Weather::Thunder);
+        let packets: Vec<_> = batch.into_stream().collect().await;
+
+        // Then
+        assert_eq!(packets.len(), 3);
+        assert!(
+            packets
+                .iter()
+                .all(|packet| matches!(packet, PacketRegistry::GameEvent(_)))
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use crate::server::batch::Batch;
+use crate::server::client_state::ClientState;
+use crate::server::packet_handler::{PacketHandler, PacketHandlerError};
+use crate::server::packet_registry::PacketRegistry;
+use crate::server_state::ServerState;
+use minecraft_packets::play::server_bound_plugin_message_packet::ServerBoundPluginMessagePacket;
+use tracing::{info, trace};
+
+impl PacketHandler for ServerBoundPluginMessagePacket {
+    fn handle(
+        &self,
+        client_state: &mut ClientState,
+        server_state: &ServerState,
+    ) -> Result<Batch<PacketRegistry>, PacketHandlerError> {
+        let protocol_version = client_state.protocol_version();
+        if let Some(client_brand) = self.brand(protocol_version) {
+            info!(
+                "{} is using brand {}",
+                client_state.get_username(),
+                client_brand
+            );
+            if server_state.is_brand_blocked(&client_brand) {
+                let messages = server_state.messages().get(client_state.locale());
+                client_state.kick(messages.brand_not_allowed.replace("{brand}", &client_brand));
+            }
+            client_state.set_brand(client_brand);
+        } else if self.is_register() {
+            let channels = self.channels(protocol_version);
+            info!(
+                "{} registered channels: {}",
+                client_state.get_username(),
+                channels.join(", ")
+            );
+            client_state.register_channels(channels);
+        } else if self.is_unregister() {
+            client_state.unregister_channels(&self.channels(protocol_version));
+        } else {
+            trace!("Ignoring plugin message on channel {}", self.channel());
+        }
+
+        Ok(Batch::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minecraft_protocol::prelude::{BinaryWriter, EncodePacket, ProtocolVersion, State};
+
+    fn brand_packet(brand: &str) -> ServerBoundPluginMessagePacket {
+        let mut writer = BinaryWriter::new();
+        brand
+            .to_string()
+            .encode(&mut writer, ProtocolVersion::Any)
+            .unwrap();
+        ServerBoundPluginMessagePacket::new("minecraft:brand", writer.into_inner())
+    }
+
+    fn server_state() -> ServerState {
+        let mut builder = ServerState::builder();
+        builder.blocked_brands(&["(?i)^lunar".to_string()]).unwrap();
+        builder.build().unwrap()
+    }
+
+    fn client_state() -> ClientState {
+        let mut cs = ClientState::default();
+        cs.set_protocol_version(ProtocolVersion::V1_21_4);
+        cs.set_state(State::Configuration);
+        cs
+    }
+
+    #[test]
+    fn test_should_record_brand() {
+        // Given
+        let mut client_state = client_state();
+        let packet = brand_packet("fabric");
+
+        // When
+        packet.handle(&mut client_state, &server_state()).unwrap();
+
+        // Then
+        assert_eq!(client_state.brand(), Some("fabric"));
+        assert!(client_state.should_kick().is_none());
+    }
+
+    #[test]
+    fn test_should_kick_blocked_brand() {
+        // Given
+        let mut client_state = client_state();
+        let packet = brand_packet("Lunar Client");
+
+        // When
+        packet.handle(&mut client_state, &server_state()).unwrap();
+
+        // Then
+        assert!(client_state.should_kick().is_some());
+    }
+
+    #[test]
+    fn test_should_record_registered_channels() {
+        // Given
+        let mut client_state = client_state();
+        let register =
+            ServerBoundPluginMessagePacket::new("minecraft:register", b"a:b\0c:d".to_vec());
+        let unregister =
+            ServerBoundPluginMessagePacket::new("minecraft:unregister", b"a:b".to_vec());
+
+        // When
+        register.handle(&mut client_state, &server_state()).unwrap();
+        register.handle(&mut client_state, &server_state()).unwrap();
+        unregister
+            .handle(&mut client_state, &server_state())
+            .unwrap();
+
+        // Then
+        assert_eq!(client_state.channels(), ["c:d".to_string()]);
+    }
+}
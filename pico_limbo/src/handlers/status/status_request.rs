@@ -16,11 +16,22 @@ impl PacketHandler for StatusRequestPacket {
     ) -> Result<Batch<PacketRegistry>, PacketHandlerError> {
         let mut batch = Batch::new();
         let client_protocol_version = client_state.protocol_version();
+        let version_range = server_state.version_range();
         let (version_string, version_number) = if client_protocol_version.is_any() {
             let oldest = ProtocolVersion::oldest();
             let latest = ProtocolVersion::latest();
             let version_string = format!("{oldest}-{latest}");
             (version_string, -1)
+        } else if !version_range.contains(client_protocol_version) {
+            // A mismatching protocol number makes the client display the version in red
+            let version_string = format!(
+                "{}-{}",
+                version_range.min.humanize(),
+                version_range.max.humanize()
+            );
+            let closest_version =
+                client_protocol_version.clamp(version_range.min, version_range.max);
+            (version_string, closest_version.version_number())
         } else {
             (
                 client_protocol_version.humanize().to_string(),
@@ -156,4 +167,33 @@ mod tests {
             assert!(batch.next().await.is_none());
         }
     }
+
+    #[tokio::test]
+    async fn test_should_respond_with_incompatible_version_when_out_of_range() {
+        // Given
+        let mut builder = ServerState::builder();
+        builder
+            .version_range(ProtocolVersion::V1_8, ProtocolVersion::V1_12_2)
+            .unwrap();
+        let server_state = builder.build().unwrap();
+        let mut client_state = client(&server_state, ProtocolVersion::V1_20_5.version_number());
+        let status_request_packet = StatusRequestPacket::default();
+
+        // When
+        let batch = status_request_packet
+            .handle(&mut client_state, &server_state)
+            .unwrap();
+        let mut batch = batch.into_stream();
+
+        // Then
+        let packet = batch.next().await.unwrap();
+        assert!(matches!(
+            packet,
+            PacketRegistry::StatusResponse(ref status_packet)
+                if status_packet.status_response().unwrap().version.protocol ==
+                    ProtocolVersion::V1_12_2.version_number()
+                    && status_packet.status_response().unwrap().version.name == "1.8-1.12.2"
+        ));
+        assert!(batch.next().await.is_none());
+    }
 }
@@ -1,3 +0,0 @@
-pub const PROXY_REQUIRED_KICK_MESSAGE: &str = "You must connect through a proxy.";
-pub const CLIENT_MODERN_FORWARDING_NOT_SUPPORTED_KICK_MESSAGE: &str =
-    "This server is only compatible with Minecraft 1.13 and above.";
@@ -3,7 +3,7 @@ mod cli;
 mod configuration;
 mod forwarding;
 mod handlers;
-mod kick_messages;
+mod messages;
 mod server;
 mod server_state;
 
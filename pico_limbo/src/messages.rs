@@ -0,0 +1,208 @@
+use crate::configuration::messages::{LocalizedMessagesConfig, MessagesConfig};
+use crate::server_state::{Boundary, BoundaryMessages};
+use pico_text_component::prelude::{Component, MiniMessageError, parse_mini_message};
+use std::collections::HashMap;
+
+/// Messages sent by the server, for a single locale.
+#[derive(Default)]
+pub struct MessageBundle {
+    pub proxy_required: Component,
+    pub forwarding_failed: Component,
+    pub modern_forwarding_not_supported: Component,
+    /// `{min}` and `{max}` are placeholders
+    pub unsupported_version: Component,
+    /// `{protocol}`, `{min}` and `{max}` are placeholders
+    pub unknown_protocol: Component,
+    pub server_full: Component,
+    /// `{brand}` is a placeholder
+    pub brand_not_allowed: Component,
+    pub welcome: Option<Component>,
+    pub boundary_teleport: Option<Component>,
+    pub boundary_ceiling_teleport: Option<Component>,
+    pub boundary_horizontal_teleport: Option<Component>,
+    pub boundary_kick: Component,
+    pub world_border_teleport: Option<Component>,
+}
+
+impl MessageBundle {
+    /// Returns the message sent to players teleported back for going past the given boundary.
+    pub const fn boundary_teleport_for(&self, boundary: Boundary) -> Option<&Component> {
+        match boundary {
+            Boundary::Floor => self.boundary_teleport.as_ref(),
+            Boundary::Ceiling => self.boundary_ceiling_teleport.as_ref(),
+            Boundary::Horizontal => self.boundary_horizontal_teleport.as_ref(),
+        }
+    }
+}
+
+/// Message bundles for every configured locale.
+#[derive(Default)]
+pub struct Messages {
+    default: MessageBundle,
+    locales: HashMap<String, MessageBundle>,
+}
+
+impl Messages {
+    pub fn new(
+        config: MessagesConfig,
+        welcome: &str,
+        boundary_messages: &BoundaryMessages,
+        world_border_teleport: &str,
+    ) -> Result<Self, MiniMessageError> {
+        let default = MessageBundle {
+            proxy_required: parse_mini_message(&config.proxy_required)?,
+            forwarding_failed: parse_mini_message(&config.forwarding_failed)?,
+            modern_forwarding_not_supported: parse_mini_message(
+                &config.modern_forwarding_not_supported,
+            )?,
+            unsupported_version: parse_mini_message(&config.unsupported_version)?,
+            unknown_protocol: parse_mini_message(&config.unknown_protocol)?,
+            server_full: parse_mini_message(&config.server_full)?,
+            brand_not_allowed: parse_mini_message(&config.brand_not_allowed)?,
+            welcome: optional_mini_message(welcome)?,
+            boundary_teleport: optional_mini_message(&boundary_messages.floor_teleport)?,
+            boundary_ceiling_teleport: optional_mini_message(&boundary_messages.ceiling_teleport)?,
+            boundary_horizontal_teleport: optional_mini_message(
+                &boundary_messages.horizontal_teleport,
+            )?,
+            boundary_kick: parse_mini_message(&boundary_messages.kick)?,
+            world_border_teleport: optional_mini_message(world_border_teleport)?,
+        };
+
+        let mut locales = HashMap::with_capacity(config.locales.len());
+        for (locale, localized) in config.locales {
+            let bundle = Self::localize(&default, localized)?;
+            locales.insert(locale.to_lowercase(), bundle);
+        }
+
+        Ok(Self { default, locales })
+    }
+
+    fn localize(
+        default: &MessageBundle,
+        localized: LocalizedMessagesConfig,
+    ) -> Result<MessageBundle, MiniMessageError> {
+        let or_default = |message: Option<String>, default: &Component| {
+            message.map_or_else(|| Ok(default.clone()), |m| parse_mini_message(&m))
+        };
+        let optional_or_default = |message: Option<String>, default: &Option<Component>| {
+            message.map_or_else(|| Ok(default.clone()), |m| optional_mini_message(&m))
+        };
+
+        Ok(MessageBundle {
+            proxy_required: or_default(localized.proxy_required, &default.proxy_required)?,
+            forwarding_failed: or_default(localized.forwarding_failed, &default.forwarding_failed)?,
+            modern_forwarding_not_supported: or_default(
+                localized.modern_forwarding_not_supported,
+                &default.modern_forwarding_not_supported,
+            )?,
+            unsupported_version: or_default(
+                localized.unsupported_version,
+                &default.unsupported_version,
+            )?,
+            unknown_protocol: or_default(localized.unknown_protocol, &default.unknown_protocol)?,
+            server_full: or_default(localized.server_full, &default.server_full)?,
+            brand_not_allowed: or_default(localized.brand_not_allowed, &default.brand_not_allowed)?,
+            welcome: optional_or_default(localized.welcome, &default.welcome)?,
+            boundary_teleport: optional_or_default(
+                localized.boundary_teleport,
+                &default.boundary_teleport,
+            )?,
+            boundary_ceiling_teleport: optional_or_default(
+                localized.boundary_ceiling_teleport,
+                &default.boundary_ceiling_teleport,
+            )?,
+            boundary_horizontal_teleport: optional_or_default(
+                localized.boundary_horizontal_teleport,
+                &default.boundary_horizontal_teleport,
+            )?,
+            boundary_kick: or_default(localized.boundary_kick, &default.boundary_kick)?,
+            world_border_teleport: optional_or_default(
+                localized.world_border_teleport,
+                &default.world_border_teleport,
+            )?,
+        })
+    }
+
+    /// Returns the bundle for the given client locale (e.g. `fr_FR`).
+    /// Falls back to the language alone (`fr`), then to the default messages.
+    pub fn get(&self, locale: Option<&str>) -> &MessageBundle {
+        locale
+            .map(str::to_lowercase)
+            .and_then(|locale| {
+                self.locales.get(&locale).or_else(|| {
+                    locale
+                        .split_once('_')
+                        .and_then(|(language, _)| self.locales.get(language))
+                })
+            })
+            .unwrap_or(&self.default)
+    }
+}
+
+pub fn optional_mini_message(content: &str) -> Result<Option<Component>, MiniMessageError> {
+    let component = if content.is_empty() {
+        None
+    } else {
+        Some(parse_mini_message(content)?)
+    };
+    Ok(component)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages() -> Messages {
+        let mut config = MessagesConfig::default();
+        config.locales.insert(
+            "fr_FR".to_string(),
+            LocalizedMessagesConfig {
+                server_full: Some("Le serveur est plein.".to_string()),
+                ..Default::default()
+            },
+        );
+        Messages::new(config, "Welcome!", &BoundaryMessages::default(), "").unwrap()
+    }
+
+    #[test]
+    fn test_should_pick_localized_message() {
+        // Given
+        let messages = messages();
+
+        // When
+        let bundle = messages.get(Some("fr_fr"));
+
+        // Then
+        assert_eq!(
+            bundle.server_full,
+            parse_mini_message("Le serveur est plein.").unwrap()
+        );
+        assert_eq!(bundle.welcome, messages.get(None).welcome);
+    }
+
+    #[test]
+    fn test_should_fall_back_to_language_then_default() {
+        // Given
+        let mut config = MessagesConfig::default();
+        config.locales.insert(
+            "fr".to_string(),
+            LocalizedMessagesConfig {
+                server_full: Some("Le serveur est plein.".to_string()),
+                ..Default::default()
+            },
+        );
+        let messages = Messages::new(config, "", &BoundaryMessages::default(), "").unwrap();
+
+        // When
+        let canadian = messages.get(Some("fr_CA"));
+        let english = messages.get(Some("en_us"));
+
+        // Then
+        assert_eq!(
+            canadian.server_full,
+            parse_mini_message("Le serveur est plein.").unwrap()
+        );
+        assert_ne!(english.server_full, canadian.server_full);
+    }
+}
@@ -0,0 +1,260 @@
+use crate::handlers::play::send_chunks_circularly::CircularChunkPacketIterator;
+use crate::server::chunk_view::ChunkView;
+use crate::server::packet_registry::PacketRegistry;
+use minecraft_packets::play::chunk_batch_finished_packet::ChunkBatchFinishedPacket;
+use minecraft_packets::play::chunk_batch_start_packet::ChunkBatchStartPacket;
+use minecraft_protocol::prelude::ProtocolVersion;
+use std::collections::HashSet;
+
+/// Sends the chunks loaded by a client over time instead of all at once.
+///
+/// Since 1.20.2 included, chunks are sent in batches and the client answers each batch
+/// with the number of chunks per tick it is able to process, older versions receive
+/// a fixed number of chunks per tick.
+pub struct ChunkSender {
+    view: Option<ChunkView>,
+    loaded_chunks: HashSet<(i32, i32)>,
+    pending_chunks: Option<CircularChunkPacketIterator>,
+    desired_chunks_per_tick: f32,
+    batch_quota: f32,
+    unacknowledged_batches: u32,
+    max_unacknowledged_batches: u32,
+}
+
+impl Default for ChunkSender {
+    fn default() -> Self {
+        Self {
+            view: None,
+            loaded_chunks: HashSet::new(),
+            pending_chunks: None,
+            desired_chunks_per_tick: Self::START_CHUNKS_PER_TICK,
+            batch_quota: 0.0,
+            unacknowledged_batches: 0,
+            max_unacknowledged_batches: 1,
+        }
+    }
+}
+
+impl ChunkSender {
+    const START_CHUNKS_PER_TICK: f32 = 9.0;
+    const MIN_CHUNKS_PER_TICK: f32 = 0.01;
+    const MAX_CHUNKS_PER_TICK: f32 = 64.0;
+    const MAX_UNACKNOWLEDGED_BATCHES: u32 = 10;
+    /// Chunks sent every tick to clients that do not support chunk batches
+    const FIXED_CHUNKS_PER_TICK: usize = 16;
+
+    /// Returns the chunks in view of the client, if it already received any.
+    pub const fn view(&self) -> Option<ChunkView> {
+        self.view
+    }
+
+    pub const fn loaded_chunks(&self) -> &HashSet<(i32, i32)> {
+        &self.loaded_chunks
+    }
+
+    pub const fn has_pending_chunks(&self) -> bool {
+        self.pending_chunks.is_some()
+    }
+
+    /// Forgets the loaded chunks outside the given view and returns them.
+    pub fn unload_outside(&mut self, view: ChunkView) -> Vec<(i32, i32)> {
+        let unloaded_chunks: Vec<(i32, i32)> = self
+            .loaded_chunks
+            .iter()
+            .copied()
+            .filter(|&chunk| !view.contains(chunk))
+            .collect();
+        for chunk in &unloaded_chunks {
+            self.loaded_chunks.remove(chunk);
+        }
+        unloaded_chunks
+    }
+
+    /// Replaces the chunks waiting to be sent, the previous ones are no longer in view.
+    pub fn load(&mut self, view: ChunkView, pending_chunks: CircularChunkPacketIterator) {
+        self.view = Some(view);
+        self.pending_chunks = Some(pending_chunks);
+    }
+
+    /// Returns the packets to send during this tick.
+    pub fn next_batch(&mut self, protocol_version: ProtocolVersion) -> Vec<PacketRegistry> {
+        if protocol_version.is_before_inclusive(ProtocolVersion::V1_20) {
+            let (_, packets) = self.take_chunks(Self::FIXED_CHUNKS_PER_TICK);
+            return packets;
+        }
+
+        if self.unacknowledged_batches >= self.max_unacknowledged_batches {
+            return Vec::new();
+        }
+
+        let max_quota = self.desired_chunks_per_tick.max(1.0);
+        self.batch_quota = (self.batch_quota + self.desired_chunks_per_tick).min(max_quota);
+        if self.batch_quota < 1.0 {
+            return Vec::new();
+        }
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (chunk_count, chunk_packets) = self.take_chunks(self.batch_quota as usize);
+        if chunk_count == 0 {
+            return Vec::new();
+        }
+
+        self.unacknowledged_batches += 1;
+        #[allow(clippy::cast_precision_loss)]
+        {
+            self.batch_quota -= chunk_count as f32;
+        }
+
+        let mut packets = Vec::with_capacity(chunk_packets.len() + 2);
+        packets.push(PacketRegistry::ChunkBatchStart(ChunkBatchStartPacket {}));
+        packets.extend(chunk_packets);
+        let batch_size = i32::try_from(chunk_count).unwrap_or(i32::MAX);
+        packets.push(PacketRegistry::ChunkBatchFinished(
+            ChunkBatchFinishedPacket::new(batch_size),
+        ));
+        packets
+    }
+
+    /// Called when the client processed a batch, with the number of chunks per tick it wants.
+    pub const fn on_batch_received(&mut self, desired_chunks_per_tick: f32) {
+        self.unacknowledged_batches = self.unacknowledged_batches.saturating_sub(1);
+        self.desired_chunks_per_tick = if desired_chunks_per_tick.is_nan() {
+            Self::MIN_CHUNKS_PER_TICK
+        } else {
+            desired_chunks_per_tick.clamp(Self::MIN_CHUNKS_PER_TICK, Self::MAX_CHUNKS_PER_TICK)
+        };
+        if self.unacknowledged_batches == 0 {
+            self.batch_quota = 1.0;
+        }
+        self.max_unacknowledged_batches = Self::MAX_UNACKNOWLEDGED_BATCHES;
+    }
+
+    /// Takes up to `count` chunks from the pending ones, returns the number of chunks taken.
+    fn take_chunks(&mut self, count: usize) -> (usize, Vec<PacketRegistry>) {
+        let Some(pending_chunks) = self.pending_chunks.as_mut() else {
+            return (0, Vec::new());
+        };
+
+        let mut packets = Vec::new();
+        let mut chunk_count = 0;
+        while chunk_count < count {
+            let Some(chunk) = pending_chunks.next() else {
+                self.pending_chunks = None;
+                break;
+            };
+            self.loaded_chunks.insert(chunk.position);
+            packets.extend(chunk.packets);
+            chunk_count += 1;
+        }
+        (chunk_count, packets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minecraft_protocol::prelude::Dimension;
+
+    fn sender(protocol_version: ProtocolVersion, radius: i32) -> ChunkSender {
+        let mut sender = ChunkSender::default();
+        let view = ChunkView::new((0, 0), radius);
+        let pending_chunks = CircularChunkPacketIterator::new(
+            view,
+            HashSet::new(),
+            None,
+            0,
+            Dimension::Overworld,
+            protocol_version,
+        )
+        .unwrap();
+        sender.load(view, pending_chunks);
+        sender
+    }
+
+    fn count_chunks(packets: &[PacketRegistry]) -> usize {
+        packets
+            .iter()
+            .filter(|packet| matches!(packet, PacketRegistry::ChunkDataAndUpdateLight(_)))
+            .count()
+    }
+
+    #[test]
+    fn test_first_batch_is_wrapped_in_batch_packets() {
+        // Given
+        let mut sender = sender(ProtocolVersion::V1_21_4, 2);
+
+        // When
+        let packets = sender.next_batch(ProtocolVersion::V1_21_4);
+
+        // Then
+        assert!(matches!(packets[0], PacketRegistry::ChunkBatchStart(_)));
+        assert!(matches!(
+            packets[packets.len() - 1],
+            PacketRegistry::ChunkBatchFinished(_)
+        ));
+        assert_eq!(count_chunks(&packets), 9);
+        assert_eq!(sender.loaded_chunks().len(), 9);
+    }
+
+    #[test]
+    fn test_waits_for_the_client_before_sending_the_next_batch() {
+        // Given
+        let mut sender = sender(ProtocolVersion::V1_21_4, 2);
+        sender.next_batch(ProtocolVersion::V1_21_4);
+
+        // When
+        let packets = sender.next_batch(ProtocolVersion::V1_21_4);
+
+        // Then
+        assert!(packets.is_empty());
+        assert!(sender.has_pending_chunks());
+    }
+
+    #[test]
+    fn test_honours_the_rate_requested_by_the_client() {
+        // Given
+        let mut sender = sender(ProtocolVersion::V1_21_4, 2);
+        sender.next_batch(ProtocolVersion::V1_21_4);
+
+        // When
+        sender.on_batch_received(4.0);
+        let packets = sender.next_batch(ProtocolVersion::V1_21_4);
+
+        // Then
+        assert_eq!(count_chunks(&packets), 4);
+    }
+
+    #[test]
+    fn test_fixed_rate_for_clients_without_chunk_batches() {
+        // Given
+        let mut sender = sender(ProtocolVersion::V1_20, 3);
+
+        // When
+        let first_tick = sender.next_batch(ProtocolVersion::V1_20);
+        let second_tick = sender.next_batch(ProtocolVersion::V1_20);
+        let third_tick = sender.next_batch(ProtocolVersion::V1_20);
+        let fourth_tick = sender.next_batch(ProtocolVersion::V1_20);
+
+        // Then
+        assert_eq!(count_chunks(&first_tick), 16);
+        assert_eq!(count_chunks(&second_tick), 16);
+        assert_eq!(count_chunks(&third_tick), 16);
+        assert_eq!(count_chunks(&fourth_tick), 1);
+        assert!(!sender.has_pending_chunks());
+    }
+
+    #[test]
+    fn test_unload_outside_forgets_chunks() {
+        // Given
+        let mut sender = sender(ProtocolVersion::V1_20, 1);
+        sender.next_batch(ProtocolVersion::V1_20);
+
+        // When
+        let unloaded_chunks = sender.unload_outside(ChunkView::new((1, 0), 1));
+
+        // Then
+        assert_eq!(unloaded_chunks.len(), 3);
+        assert!(unloaded_chunks.iter().all(|&(chunk_x, _)| chunk_x == -1));
+        assert_eq!(sender.loaded_chunks().len(), 6);
+    }
+}
@@ -0,0 +1,42 @@
+/// Square of chunks loaded by a client, centered on the chunk the player is in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkView {
+    center: (i32, i32),
+    radius: i32,
+}
+
+impl ChunkView {
+    pub const fn new(center: (i32, i32), radius: i32) -> Self {
+        Self { center, radius }
+    }
+
+    pub const fn center(&self) -> (i32, i32) {
+        self.center
+    }
+
+    pub const fn radius(&self) -> i32 {
+        self.radius
+    }
+
+    pub const fn contains(&self, chunk: (i32, i32)) -> bool {
+        (chunk.0 - self.center.0).abs() <= self.radius
+            && (chunk.1 - self.center.1).abs() <= self.radius
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        // Given
+        let view = ChunkView::new((2, -3), 1);
+
+        // Then
+        assert!(view.contains((1, -4)));
+        assert!(view.contains((3, -2)));
+        assert!(!view.contains((4, -3)));
+        assert!(!view.contains((2, -5)));
+    }
+}
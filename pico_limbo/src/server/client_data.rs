@@ -15,9 +15,14 @@ pub struct ClientData {
     client_state: Arc<Mutex<ClientState>>,
     packet_stream: Arc<Mutex<PacketStream<TcpStream>>>,
     interval: Arc<Mutex<ControllableInterval>>,
+    chunk_interval: Arc<Mutex<ControllableInterval>>,
+    weather_interval: Arc<Mutex<ControllableInterval>>,
 }
 
 impl ClientData {
+    const TICK_DURATION: Duration = Duration::from_millis(50);
+    const WEATHER_CHECK_PERIOD: Duration = Duration::from_secs(1);
+
     pub fn new(socket: TcpStream) -> Self {
         let client_state = ClientState::default();
         let packet_stream = PacketStream::new(socket);
@@ -27,6 +32,8 @@ impl ClientData {
             client_state: Arc::new(Mutex::new(client_state)),
             packet_stream: Arc::new(Mutex::new(packet_stream)),
             interval: Arc::new(Mutex::new(interval)),
+            chunk_interval: Arc::new(Mutex::new(ControllableInterval::new())),
+            weather_interval: Arc::new(Mutex::new(ControllableInterval::new())),
         }
     }
 
@@ -37,6 +44,11 @@ impl ClientData {
         self.client_state.lock().await
     }
 
+    /// Returns the state of the client, shared with the player list.
+    pub fn shared_client_state(&self) -> Arc<Mutex<ClientState>> {
+        Arc::clone(&self.client_state)
+    }
+
     pub async fn protocol_version(&self) -> ProtocolVersion {
         self.client().await.protocol_version()
     }
@@ -59,6 +71,8 @@ impl ClientData {
     pub async fn shutdown(&self) -> Result<(), PacketStreamError> {
         self.stream().await.get_stream().shutdown().await?;
         self.interval().await.clear_interval().await;
+        self.chunk_interval().await.clear_interval().await;
+        self.weather_interval().await.clear_interval().await;
         Ok(())
     }
 
@@ -90,4 +104,50 @@ impl ClientData {
     async fn interval(&self) -> tokio::sync::MutexGuard<'_, ControllableInterval> {
         self.interval.lock().await
     }
+
+    // Chunks
+
+    /// Starts ticking while the client has chunks waiting to be sent.
+    pub async fn enable_chunk_ticks_if_needed(&self) {
+        let has_pending_chunks = self.client().await.chunk_sender().has_pending_chunks();
+        if has_pending_chunks {
+            let chunk_interval = self.chunk_interval().await;
+            if !chunk_interval.is_enabled().await {
+                chunk_interval.set_interval(Self::TICK_DURATION).await;
+            }
+            drop(chunk_interval);
+        }
+    }
+
+    pub async fn disable_chunk_ticks(&self) {
+        self.chunk_interval().await.clear_interval().await;
+    }
+
+    pub async fn chunk_tick(&self) {
+        self.chunk_interval().await.tick().await;
+    }
+
+    #[inline]
+    async fn chunk_interval(&self) -> tokio::sync::MutexGuard<'_, ControllableInterval> {
+        self.chunk_interval.lock().await
+    }
+
+    // Weather
+
+    /// Starts checking whether the weather cycle moved on to another weather.
+    pub async fn enable_weather_ticks(&self) {
+        self.weather_interval()
+            .await
+            .set_interval(Self::WEATHER_CHECK_PERIOD)
+            .await;
+    }
+
+    pub async fn weather_tick(&self) {
+        self.weather_interval().await.tick().await;
+    }
+
+    #[inline]
+    async fn weather_interval(&self) -> tokio::sync::MutexGuard<'_, ControllableInterval> {
+        self.weather_interval.lock().await
+    }
 }
@@ -1,6 +1,11 @@
+use crate::forwarding::forge_marker::ForgeMarker;
+use crate::server::chunk_sender::ChunkSender;
 use crate::server::game_profile::GameProfile;
+use crate::server::player_position::PlayerPosition;
+use crate::server_state::Weather;
 use minecraft_packets::login::Property;
 use minecraft_protocol::prelude::{ProtocolVersion, State, Uuid};
+use pico_text_component::prelude::Component;
 use tracing::info;
 
 #[derive(PartialEq, Eq)]
@@ -19,7 +24,20 @@ impl Default for ClientState {
             message_id: -1,
             game_profile: None,
             keep_alive_enabled: KeepAliveStatus::Disabled,
-            feet_y: 0.0,
+            position: PlayerPosition::default(),
+            last_valid_position: None,
+            outside_boundaries: false,
+            weather: Weather::default(),
+            locale: None,
+            view_distance: None,
+            displayed_skin_parts: Self::ALL_SKIN_PARTS,
+            brand: None,
+            channels: Vec::new(),
+            forge_marker: None,
+            forge_login_message_id: -1,
+            pending_game_profile: None,
+            chunk_sender: ChunkSender::default(),
+            has_reserved_slot: false,
         }
     }
 }
@@ -27,23 +45,41 @@ impl Default for ClientState {
 pub struct ClientState {
     state: State,
     protocol_version: ProtocolVersion,
-    kick_message: Option<String>,
+    kick_message: Option<Component>,
     message_id: i32,
     game_profile: Option<GameProfile>,
     keep_alive_enabled: KeepAliveStatus,
-    feet_y: f64,
+    position: PlayerPosition,
+    /// Last position inside the boundaries and the world border, none before joining the game
+    last_valid_position: Option<PlayerPosition>,
+    /// Set once the player was sent back, until the client is back inside the boundaries and the border
+    outside_boundaries: bool,
+    /// Last weather sent to the client
+    weather: Weather,
+    locale: Option<String>,
+    view_distance: Option<i32>,
+    displayed_skin_parts: u8,
+    brand: Option<String>,
+    channels: Vec<String>,
+    forge_marker: Option<ForgeMarker>,
+    forge_login_message_id: i32,
+    pending_game_profile: Option<GameProfile>,
+    chunk_sender: ChunkSender,
+    /// Whether the client took a player slot, released once disconnected
+    has_reserved_slot: bool,
 }
 
 impl ClientState {
     const ANONYMOUS: &'static str = "Anonymous";
+    const ALL_SKIN_PARTS: u8 = 0x7F;
 
     // Kick
 
-    pub fn kick(&mut self, kick_message: &str) {
-        self.kick_message = Some(kick_message.to_string());
+    pub fn kick(&mut self, kick_message: Component) {
+        self.kick_message = Some(kick_message);
     }
 
-    pub fn should_kick(&self) -> Option<String> {
+    pub fn should_kick(&self) -> Option<Component> {
         self.kick_message.clone()
     }
 
@@ -77,6 +113,41 @@ impl ClientState {
         self.message_id
     }
 
+    // Player slot
+
+    pub const fn has_reserved_slot(&self) -> bool {
+        self.has_reserved_slot
+    }
+
+    pub const fn set_has_reserved_slot(&mut self, has_reserved_slot: bool) {
+        self.has_reserved_slot = has_reserved_slot;
+    }
+
+    // Forge
+
+    pub const fn set_forge_marker(&mut self, forge_marker: Option<ForgeMarker>) {
+        self.forge_marker = forge_marker;
+    }
+
+    pub const fn forge_marker(&self) -> Option<ForgeMarker> {
+        self.forge_marker
+    }
+
+    /// Keeps the game profile until the Forge client answered the login query.
+    pub fn set_pending_forge_login(&mut self, message_id: i32, game_profile: GameProfile) {
+        self.forge_login_message_id = message_id;
+        self.pending_game_profile = Some(game_profile);
+    }
+
+    /// Returns the pending game profile if the message ID matches the Forge login query.
+    pub const fn take_pending_forge_login(&mut self, message_id: i32) -> Option<GameProfile> {
+        if self.forge_login_message_id == message_id {
+            self.pending_game_profile.take()
+        } else {
+            None
+        }
+    }
+
     // Game profile
 
     pub fn set_game_profile(&mut self, game_profile: GameProfile) {
@@ -102,10 +173,10 @@ impl ClientState {
     }
 
     pub fn get_username(&self) -> String {
-        self.game_profile()
-            .map_or(Self::ANONYMOUS.to_owned(), |profile| {
-                profile.username().to_owned()
-            })
+        self.game_profile().map_or_else(
+            || Self::ANONYMOUS.to_owned(),
+            |profile| profile.username().to_owned(),
+        )
     }
 
     pub fn get_unique_id(&self) -> Uuid {
@@ -118,6 +189,67 @@ impl ClientState {
             .and_then(|profile| profile.textures().cloned())
     }
 
+    // Client information
+
+    pub fn set_client_information(
+        &mut self,
+        locale: &str,
+        view_distance: i32,
+        displayed_skin_parts: u8,
+    ) {
+        self.locale = Some(locale.to_owned());
+        self.view_distance = Some(view_distance);
+        self.displayed_skin_parts = displayed_skin_parts;
+    }
+
+    /// Whether the client already sent its settings.
+    pub const fn has_client_information(&self) -> bool {
+        self.view_distance.is_some()
+    }
+
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    /// Returns the smallest view distance between the server and the client.
+    pub fn effective_view_distance(&self, server_view_distance: i32) -> i32 {
+        self.view_distance
+            .map_or(server_view_distance, |view_distance| {
+                view_distance.clamp(0, server_view_distance)
+            })
+    }
+
+    pub const fn displayed_skin_parts(&self) -> u8 {
+        self.displayed_skin_parts
+    }
+
+    // Plugin messages
+
+    pub fn set_brand(&mut self, brand: String) {
+        self.brand = Some(brand);
+    }
+
+    pub fn brand(&self) -> Option<&str> {
+        self.brand.as_deref()
+    }
+
+    pub fn register_channels(&mut self, channels: Vec<String>) {
+        for channel in channels {
+            if !self.channels.contains(&channel) {
+                self.channels.push(channel);
+            }
+        }
+    }
+
+    pub fn unregister_channels(&mut self, channels: &[String]) {
+        self.channels.retain(|channel| !channels.contains(channel));
+    }
+
+    /// Returns the plugin channels registered by the client.
+    pub fn channels(&self) -> &[String] {
+        &self.channels
+    }
+
     // Keep alive
 
     pub fn should_enable_keep_alive(&self) -> bool {
@@ -138,11 +270,47 @@ impl ClientState {
 
     // Position
 
-    pub const fn get_y_position(&self) -> f64 {
-        self.feet_y
+    pub const fn position(&self) -> PlayerPosition {
+        self.position
+    }
+
+    pub const fn set_position(&mut self, position: PlayerPosition) {
+        self.position = position;
+    }
+
+    pub const fn last_valid_position(&self) -> Option<PlayerPosition> {
+        self.last_valid_position
+    }
+
+    pub const fn set_last_valid_position(&mut self, position: PlayerPosition) {
+        self.last_valid_position = Some(position);
+    }
+
+    pub const fn is_outside_boundaries(&self) -> bool {
+        self.outside_boundaries
+    }
+
+    pub const fn set_outside_boundaries(&mut self, outside_boundaries: bool) {
+        self.outside_boundaries = outside_boundaries;
+    }
+
+    // Weather
+
+    pub const fn weather(&self) -> Weather {
+        self.weather
+    }
+
+    pub const fn set_weather(&mut self, weather: Weather) {
+        self.weather = weather;
+    }
+
+    // Chunks
+
+    pub const fn chunk_sender(&self) -> &ChunkSender {
+        &self.chunk_sender
     }
 
-    pub const fn set_feet_position(&mut self, feet_y: f64) {
-        self.feet_y = feet_y;
+    pub const fn chunk_sender_mut(&mut self) -> &mut ChunkSender {
+        &mut self.chunk_sender
     }
 }
@@ -0,0 +1,45 @@
+use crate::server::player_list::PlayerList;
+use std::io::BufRead;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Reads the commands typed in the console until the standard input is closed.
+pub async fn read_console_commands(player_list: Arc<PlayerList>) {
+    let mut lines = spawn_stdin_reader();
+    while let Some(line) = lines.recv().await {
+        match line.trim() {
+            "" => {}
+            "list" => list_players(&player_list).await,
+            command => warn!("Unknown command: {command}, available commands: list"),
+        }
+    }
+}
+
+/// Reads the standard input on a dedicated thread, a blocking read would otherwise
+/// keep the runtime from shutting down. The thread is detached and ends with the process.
+fn spawn_stdin_reader() -> mpsc::UnboundedReceiver<String> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let spawn_result = std::thread::Builder::new()
+        .name("console".to_string())
+        .spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    if let Err(error) = spawn_result {
+        warn!("Failed to read the console: {error}");
+    }
+    receiver
+}
+
+async fn list_players(player_list: &PlayerList) {
+    let players = player_list.describe().await;
+    info!("There are {} players online", players.len());
+    for player in players {
+        info!("- {player}");
+    }
+}
@@ -61,6 +61,11 @@ impl ControllableInterval {
         self.notify.notify_waiters();
     }
 
+    /// Returns whether an interval is currently set.
+    pub async fn is_enabled(&self) -> bool {
+        self.state.lock().await.interval.is_some()
+    }
+
     /// Waits for the next tick of the interval.
     ///
     /// - If the interval is enabled, this method will complete when the next
@@ -1,11 +1,16 @@
 pub mod batch;
+pub mod chunk_sender;
+pub mod chunk_view;
 mod client_data;
 pub mod client_state;
+mod console;
 mod controllable_interval;
 pub mod game_mode;
 pub mod game_profile;
 pub mod network;
 pub mod packet_handler;
 pub mod packet_registry;
+pub mod player_list;
+pub mod player_position;
 mod shutdown_signal;
 pub mod start_server;
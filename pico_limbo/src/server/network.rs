@@ -1,4 +1,7 @@
+use crate::handlers::play::weather::send_weather_packets;
+use crate::server::batch::Batch;
 use crate::server::client_data::ClientData;
+use crate::server::console::read_console_commands;
 use crate::server::packet_handler::{PacketHandler, PacketHandlerError};
 use crate::server::packet_registry::{
     PacketRegistry, PacketRegistryDecodeError, PacketRegistryEncodeError,
@@ -12,6 +15,7 @@ use minecraft_packets::play::disconnect_packet::DisconnectPacket;
 use minecraft_protocol::prelude::State;
 use net::packet_stream::PacketStreamError;
 use net::raw_packet::RawPacket;
+use pico_text_component::prelude::Component;
 use std::num::TryFromIntError;
 use std::sync::Arc;
 use thiserror::Error;
@@ -42,6 +46,8 @@ impl Server {
         };
 
         info!("Listening on: {}", self.listen_address);
+        let player_list = Arc::clone(self.state.read().await.player_list());
+        tokio::spawn(read_console_commands(player_list));
         self.accept(&listener).await;
     }
 
@@ -144,9 +150,21 @@ async fn process_packet(
     let state = client_state.state();
     let decoded_packet = PacketRegistry::decode_packet(protocol_version, state, raw_packet)?;
 
-    let batch = {
+    let result = {
         let server_state_guard = server_state.read().await;
-        decoded_packet.handle(&mut client_state, &server_state_guard)?
+        decoded_packet.handle(&mut client_state, &server_state_guard)
+    };
+
+    let batch = match result {
+        Ok(batch) => batch,
+        Err(error) => {
+            // Let the client know why it is disconnected before reporting the error
+            if let Some(reason) = client_state.should_kick() {
+                drop(client_state);
+                let _ = kick_client(client_data, reason).await;
+            }
+            return Err(error.into());
+        }
     };
 
     let protocol_version = client_state.protocol_version();
@@ -155,6 +173,15 @@ async fn process_packet(
     if !*was_in_play_state && state == State::Play {
         *was_in_play_state = true;
         server_state.write().await.increment();
+        server_state
+            .read()
+            .await
+            .player_list()
+            .add(client_data.shared_client_state())
+            .await;
+        if server_state.read().await.weather_cycle().is_some() {
+            client_data.enable_weather_ticks().await;
+        }
         let username = client_state.get_username();
         debug!(
             "{} joined using version {}",
@@ -172,7 +199,7 @@ async fn process_packet(
 
     if let Some(reason) = client_state.should_kick() {
         drop(client_state);
-        kick_client(client_data, reason.clone())
+        kick_client(client_data, reason)
             .await
             .map_err(|_| PacketProcessingError::Disconnected)?;
         return Err(PacketProcessingError::Disconnected);
@@ -180,6 +207,7 @@ async fn process_packet(
 
     drop(client_state);
     client_data.enable_keep_alive_if_needed().await;
+    client_data.enable_chunk_ticks_if_needed().await;
 
     Ok(())
 }
@@ -197,6 +225,12 @@ async fn read(
         () = client_data.keep_alive_tick() => {
             send_keep_alive(client_data).await?;
         }
+        () = client_data.chunk_tick() => {
+            send_pending_chunks(client_data).await?;
+        }
+        () = client_data.weather_tick() => {
+            send_weather_change(client_data, server_state).await?;
+        }
     }
     Ok(())
 }
@@ -225,16 +259,31 @@ async fn handle_client(socket: TcpStream, server_state: Arc<RwLock<ServerState>>
 
     let _ = client_data.shutdown().await;
 
+    if client_data.client().await.has_reserved_slot() {
+        server_state.read().await.release_slot();
+    }
+
     if was_in_play_state {
         server_state.write().await.decrement();
-        let username = client_data.client().await.get_username();
+        server_state
+            .read()
+            .await
+            .player_list()
+            .remove(&client_data.shared_client_state())
+            .await;
+        let client_state = client_data.client().await;
+        let username = client_state.get_username();
+        let brand = client_state.brand().unwrap_or("unknown").to_owned();
+        let channels = client_state.channels().join(", ");
+        drop(client_state);
         info!("{} left the game", username);
+        debug!("{username} was using brand {brand} with channels [{channels}]");
     }
 }
 
 async fn kick_client(
     client_data: &ClientData,
-    reason: String,
+    reason: Component,
 ) -> Result<(), PacketProcessingError> {
     let (protocol_version, state) = {
         let state = client_data.client().await;
@@ -243,15 +292,15 @@ async fn kick_client(
     let packet = match state {
         State::Login => {
             debug!("Login disconnect");
-            PacketRegistry::LoginDisconnect(LoginDisconnectPacket::text(reason))
+            PacketRegistry::LoginDisconnect(LoginDisconnectPacket::component(&reason))
         }
         State::Configuration => {
             debug!("Configuration disconnect");
-            PacketRegistry::ConfigurationDisconnect(DisconnectPacket::text(reason))
+            PacketRegistry::ConfigurationDisconnect(DisconnectPacket::component(&reason))
         }
         State::Play => {
             debug!("Play disconnect");
-            PacketRegistry::PlayDisconnect(DisconnectPacket::text(reason))
+            PacketRegistry::PlayDisconnect(DisconnectPacket::component(&reason))
         }
         _ => {
             debug!("A user was disconnected from a state where no packet can be sent");
@@ -280,3 +329,47 @@ async fn send_keep_alive(client_data: &ClientData) -> Result<(), PacketProcessin
 
     Ok(())
 }
+
+async fn send_pending_chunks(client_data: &ClientData) -> Result<(), PacketProcessingError> {
+    let mut client = client_data.client().await;
+    let protocol_version = client.protocol_version();
+    let packets = client.chunk_sender_mut().next_batch(protocol_version);
+    let has_pending_chunks = client.chunk_sender().has_pending_chunks();
+    drop(client);
+
+    for packet in packets {
+        let raw_packet = packet.encode_packet(protocol_version)?;
+        client_data.write_packet(raw_packet).await?;
+    }
+
+    if !has_pending_chunks {
+        client_data.disable_chunk_ticks().await;
+    }
+
+    Ok(())
+}
+
+/// Sends the new weather once the weather cycle moved on.
+async fn send_weather_change(
+    client_data: &ClientData,
+    server_state: &Arc<RwLock<ServerState>>,
+) -> Result<(), PacketProcessingError> {
+    let weather = server_state.read().await.weather();
+    let mut client = client_data.client().await;
+    if client.state() != State::Play || client.weather() == weather {
+        return Ok(());
+    }
+    client.set_weather(weather);
+    let protocol_version = client.protocol_version();
+    drop(client);
+
+    let mut batch = Batch::new();
+    send_weather_packets(&mut batch, weather);
+    let mut stream = batch.into_stream();
+    while let Some(packet) = stream.next().await {
+        let raw_packet = packet.encode_packet(protocol_version)?;
+        client_data.write_packet(raw_packet).await?;
+    }
+
+    Ok(())
+}
@@ -17,23 +17,32 @@ use minecraft_packets::login::login_disconnect_packet::LoginDisconnectPacket;
 use minecraft_packets::login::login_state_packet::LoginStartPacket;
 use minecraft_packets::login::login_success_packet::LoginSuccessPacket;
 use minecraft_packets::play::boss_bar_packet::BossBarPacket;
+use minecraft_packets::play::chunk_batch_finished_packet::ChunkBatchFinishedPacket;
+use minecraft_packets::play::chunk_batch_received_packet::ChunkBatchReceivedPacket;
+use minecraft_packets::play::chunk_batch_start_packet::ChunkBatchStartPacket;
 use minecraft_packets::play::chunk_data_and_update_light_packet::ChunkDataAndUpdateLightPacket;
 use minecraft_packets::play::client_bound_keep_alive_packet::ClientBoundKeepAlivePacket;
+use minecraft_packets::play::client_information_packet::ClientInformationPacket;
 use minecraft_packets::play::commands_packet::CommandsPacket;
 use minecraft_packets::play::disconnect_packet::DisconnectPacket;
 use minecraft_packets::play::game_event_packet::GameEventPacket;
+use minecraft_packets::play::initialize_world_border_packet::InitializeWorldBorderPacket;
 use minecraft_packets::play::legacy_chat_message_packet::LegacyChatMessagePacket;
 use minecraft_packets::play::login_packet::LoginPacket;
 use minecraft_packets::play::play_client_bound_plugin_message_packet::PlayClientBoundPluginMessagePacket;
 use minecraft_packets::play::player_info_update_packet::PlayerInfoUpdatePacket;
+use minecraft_packets::play::server_bound_plugin_message_packet::ServerBoundPluginMessagePacket;
 use minecraft_packets::play::set_chunk_cache_center_packet::SetCenterChunkPacket;
 use minecraft_packets::play::set_default_spawn_position_packet::SetDefaultSpawnPositionPacket;
 use minecraft_packets::play::set_entity_data_packet::SetEntityMetadataPacket;
 use minecraft_packets::play::set_player_position_and_rotation_packet::SetPlayerPositionAndRotationPacket;
 use minecraft_packets::play::set_player_position_packet::SetPlayerPositionPacket;
+use minecraft_packets::play::set_player_rotation_packet::SetPlayerRotationPacket;
 use minecraft_packets::play::synchronize_player_position_packet::SynchronizePlayerPositionPacket;
 use minecraft_packets::play::system_chat_message_packet::SystemChatMessagePacket;
 use minecraft_packets::play::tab_list_packet::TabListPacket;
+use minecraft_packets::play::unload_chunk_packet::UnloadChunkPacket;
+use minecraft_packets::play::update_light_packet::UpdateLightPacket;
 use minecraft_packets::play::update_time_packet::UpdateTimePacket;
 use minecraft_packets::status::ping_request_packet::PingRequestPacket;
 use minecraft_packets::status::ping_response_packet::PongResponsePacket;
@@ -138,6 +147,20 @@ pub enum PacketRegistry {
     )]
     AcknowledgeConfiguration(AcknowledgeConfigurationPacket),
 
+    #[protocol_id(
+        state = "configuration",
+        bound = "serverbound",
+        name = "minecraft:client_information"
+    )]
+    ConfigurationClientInformation(ClientInformationPacket),
+
+    #[protocol_id(
+        state = "configuration",
+        bound = "serverbound",
+        name = "minecraft:custom_payload"
+    )]
+    ConfigurationServerBoundPluginMessage(ServerBoundPluginMessagePacket),
+
     #[protocol_id(
         state = "configuration",
         bound = "clientbound",
@@ -198,6 +221,27 @@ pub enum PacketRegistry {
     )]
     SetPlayerPositionAndRotation(SetPlayerPositionAndRotationPacket),
 
+    #[protocol_id(
+        state = "play",
+        bound = "serverbound",
+        name = "minecraft:move_player_rot"
+    )]
+    SetPlayerRotation(SetPlayerRotationPacket),
+
+    #[protocol_id(
+        state = "play",
+        bound = "serverbound",
+        name = "minecraft:client_information"
+    )]
+    PlayClientInformation(ClientInformationPacket),
+
+    #[protocol_id(
+        state = "play",
+        bound = "serverbound",
+        name = "minecraft:custom_payload"
+    )]
+    PlayServerBoundPluginMessage(ServerBoundPluginMessagePacket),
+
     #[protocol_id(
         state = "play",
         bound = "clientbound",
@@ -225,6 +269,37 @@ pub enum PacketRegistry {
     )]
     ChunkDataAndUpdateLight(Box<ChunkDataAndUpdateLightPacket>),
 
+    #[protocol_id(state = "play", bound = "clientbound", name = "minecraft:light_update")]
+    UpdateLight(Box<UpdateLightPacket>),
+
+    #[protocol_id(
+        state = "play",
+        bound = "clientbound",
+        name = "minecraft:forget_level_chunk"
+    )]
+    UnloadChunk(UnloadChunkPacket),
+
+    #[protocol_id(
+        state = "play",
+        bound = "clientbound",
+        name = "minecraft:chunk_batch_start"
+    )]
+    ChunkBatchStart(ChunkBatchStartPacket),
+
+    #[protocol_id(
+        state = "play",
+        bound = "clientbound",
+        name = "minecraft:chunk_batch_finished"
+    )]
+    ChunkBatchFinished(ChunkBatchFinishedPacket),
+
+    #[protocol_id(
+        state = "play",
+        bound = "serverbound",
+        name = "minecraft:chunk_batch_received"
+    )]
+    ChunkBatchReceived(ChunkBatchReceivedPacket),
+
     #[protocol_id(
         state = "play",
         bound = "clientbound",
@@ -270,6 +345,13 @@ pub enum PacketRegistry {
 
     #[protocol_id(state = "play", bound = "clientbound", name = "minecraft:boss_event")]
     BossBar(BossBarPacket),
+
+    #[protocol_id(
+        state = "play",
+        bound = "clientbound",
+        name = "minecraft:initialize_border"
+    )]
+    InitializeWorldBorder(InitializeWorldBorderPacket),
 }
 
 impl PacketHandler for PacketRegistry {
@@ -288,6 +370,15 @@ impl PacketHandler for PacketRegistry {
             Self::AcknowledgeConfiguration(packet) => packet.handle(client_state, server_state),
             Self::SetPlayerPositionAndRotation(packet) => packet.handle(client_state, server_state),
             Self::SetPlayerPosition(packet) => packet.handle(client_state, server_state),
+            Self::SetPlayerRotation(packet) => packet.handle(client_state, server_state),
+            Self::ChunkBatchReceived(packet) => packet.handle(client_state, server_state),
+            Self::ConfigurationClientInformation(packet) | Self::PlayClientInformation(packet) => {
+                packet.handle(client_state, server_state)
+            }
+            Self::ConfigurationServerBoundPluginMessage(packet)
+            | Self::PlayServerBoundPluginMessage(packet) => {
+                packet.handle(client_state, server_state)
+            }
             _ => Err(PacketHandlerError::custom("Unhandled packet")),
         }
     }
@@ -0,0 +1,78 @@
+use crate::server::client_state::ClientState;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Players currently in game, listed by the `list` console command.
+#[derive(Default)]
+pub struct PlayerList {
+    players: Mutex<Vec<Arc<Mutex<ClientState>>>>,
+}
+
+impl PlayerList {
+    pub async fn add(&self, client_state: Arc<Mutex<ClientState>>) {
+        self.players.lock().await.push(client_state);
+    }
+
+    pub async fn remove(&self, client_state: &Arc<Mutex<ClientState>>) {
+        self.players
+            .lock()
+            .await
+            .retain(|player| !Arc::ptr_eq(player, client_state));
+    }
+
+    /// Returns a line per player with their version, client brand and registered channels.
+    pub async fn describe(&self) -> Vec<String> {
+        let players = self.players.lock().await.clone();
+        let mut lines = Vec::with_capacity(players.len());
+        for player in players {
+            let client_state = player.lock().await;
+            let line = format!(
+                "{} ({}), brand: {}, channels: [{}]",
+                client_state.get_username(),
+                client_state.protocol_version().humanize(),
+                client_state.brand().unwrap_or("unknown"),
+                client_state.channels().join(", ")
+            );
+            drop(client_state);
+            lines.push(line);
+        }
+        lines.sort();
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::game_profile::GameProfile;
+    use minecraft_protocol::prelude::{ProtocolVersion, Uuid};
+
+    fn player(username: &str, brand: &str) -> Arc<Mutex<ClientState>> {
+        let mut client_state = ClientState::default();
+        client_state.set_protocol_version(ProtocolVersion::V1_21_4);
+        client_state.set_game_profile(GameProfile::new(username, Uuid::nil(), None));
+        client_state.set_brand(brand.to_owned());
+        client_state.register_channels(vec!["a:b".to_owned(), "c:d".to_owned()]);
+        Arc::new(Mutex::new(client_state))
+    }
+
+    #[tokio::test]
+    async fn test_should_describe_players_in_game() {
+        // Given
+        let player_list = PlayerList::default();
+        let steve = player("Steve", "fabric");
+        let alex = player("Alex", "vanilla");
+        player_list.add(Arc::clone(&steve)).await;
+        player_list.add(Arc::clone(&alex)).await;
+
+        // When
+        player_list.remove(&alex).await;
+        let lines = player_list.describe().await;
+
+        // Then
+        assert_eq!(
+            lines,
+            ["Steve (1.21.4), brand: fabric, channels: [a:b, c:d]".to_owned()]
+        );
+    }
+}
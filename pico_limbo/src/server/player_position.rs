@@ -0,0 +1,27 @@
+/// Position and rotation of a player, as last sent by the client.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct PlayerPosition {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl PlayerPosition {
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            yaw: 0.0,
+            pitch: 0.0,
+        }
+    }
+
+    pub const fn with_rotation(mut self, yaw: f32, pitch: f32) -> Self {
+        self.yaw = yaw;
+        self.pitch = pitch;
+        self
+    }
+}
@@ -3,8 +3,13 @@ use crate::configuration::boss_bar::BossBarConfig;
 use crate::configuration::config::{Config, ConfigError, load_or_create};
 use crate::configuration::tab_list::TabListConfig;
 use crate::configuration::world_config::boundaries::BoundariesConfig;
+use crate::configuration::world_config::world_border::WorldBorderConfig;
 use crate::server::network::Server;
-use crate::server_state::{ServerState, ServerStateBuilderError};
+use crate::server_state::{
+    Boundaries, BoundaryMessages, ServerState, ServerStateBuilder, ServerStateBuilderError,
+    WorldBorder,
+};
+use pico_structures::prelude::{AnvilArea, FlatGenerator, SchematicPlacement};
 use std::path::PathBuf;
 use std::process::ExitCode;
 use tracing::{debug, error};
@@ -67,11 +72,42 @@ fn build_state(cfg: Config) -> Result<ServerState, ServerStateBuilderError> {
         }
     }
 
-    if let BoundariesConfig::Enabled(boundaries) = cfg.world.boundaries {
-        if cfg.world.spawn_position.1 < f64::from(boundaries.min_y) {
-            return Err(ServerStateBuilderError::InvalidSpawnPosition);
+    configure_world_limits(
+        &mut server_state_builder,
+        cfg.world.boundaries,
+        cfg.world.world_border,
+        cfg.world.spawn_position,
+    )?;
+
+    let schematic_file = cfg.world.experimental.schematic_file;
+    let has_schematics =
+        !schematic_file.is_empty() || !cfg.world.experimental.schematics.is_empty();
+    if !schematic_file.is_empty() {
+        server_state_builder.schematic(SchematicPlacement::at_world_origin(schematic_file));
+    }
+    for placement in cfg.world.experimental.schematics {
+        server_state_builder.schematic(placement.into());
+    }
+
+    let generator: Option<FlatGenerator> = cfg.world.generator.into();
+    let has_generator = generator.is_some();
+    if let Some(generator) = generator {
+        server_state_builder.generator(generator);
+    }
+
+    let anvil_world = cfg.world.experimental.anvil_world;
+    if !anvil_world.world_folder.is_empty() {
+        if has_schematics || has_generator {
+            return Err(ServerStateBuilderError::ConflictingWorldSources);
         }
-        server_state_builder.boundaries(boundaries.min_y, boundaries.teleport_message)?;
+        server_state_builder.anvil_world(
+            anvil_world.world_folder,
+            AnvilArea {
+                dimension_folder: anvil_world.dimension_folder,
+                min_chunk: anvil_world.min_chunk,
+                max_chunk: anvil_world.max_chunk,
+            },
+        );
     }
 
     if let TabListConfig::Enabled(tab_list) = cfg.tab_list {
@@ -82,6 +118,17 @@ fn build_state(cfg: Config) -> Result<ServerState, ServerStateBuilderError> {
         server_state_builder.boss_bar(boss_bar)?;
     }
 
+    server_state_builder.biome(cfg.world.biome)?;
+    server_state_builder.weather_cycle(
+        cfg.world
+            .weather_cycle
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+    )?;
+    server_state_builder.version_range(cfg.min_version.into(), cfg.max_version.into())?;
+    server_state_builder.blocked_brands(&cfg.blocked_brands)?;
+
     let server_icon = cfg.server_list.server_icon;
     if std::fs::exists(&server_icon)? {
         server_state_builder.fav_icon(server_icon)?;
@@ -91,16 +138,99 @@ fn build_state(cfg: Config) -> Result<ServerState, ServerStateBuilderError> {
         .dimension(cfg.world.dimension.into())
         .time_world(cfg.world.time.into())
         .lock_time(cfg.world.experimental.lock_time)
+        .weather(cfg.world.weather.into())
+        .world_cache(cfg.world.experimental.world_cache)
         .description_text(&cfg.server_list.message_of_the_day)
         .welcome_message(&cfg.welcome_message)
+        .messages(cfg.messages)
+        .player_limit(cfg.player_limit)
         .max_players(cfg.server_list.max_players)
         .show_online_player_count(cfg.server_list.show_online_player_count)
         .game_mode(cfg.default_game_mode.into())
         .hardcore(cfg.hardcore)
         .spawn_position(cfg.world.spawn_position)
         .view_distance(cfg.world.experimental.view_distance)
-        .schematic(cfg.world.experimental.schematic_file)
         .fetch_player_skins(cfg.fetch_player_skins);
 
     server_state_builder.build()
 }
+
+/// Validates the boundaries and the world border against the spawn position.
+fn configure_world_limits(
+    builder: &mut ServerStateBuilder,
+    boundaries: BoundariesConfig,
+    world_border: WorldBorderConfig,
+    spawn_position: (f64, f64, f64),
+) -> Result<(), ServerStateBuilderError> {
+    if let BoundariesConfig::Enabled(config) = boundaries {
+        let boundaries = Boundaries {
+            min_y: config.min_y,
+            max_y: config.max_y,
+            vertical_action: config.vertical_action.into(),
+            horizontal: config.horizontal.into(),
+        };
+        let (spawn_x, spawn_y, spawn_z) = spawn_position;
+        if boundaries.violation(spawn_x, spawn_y, spawn_z).is_some() {
+            return Err(ServerStateBuilderError::InvalidSpawnPosition);
+        }
+        let messages = BoundaryMessages {
+            floor_teleport: config.teleport_message,
+            ceiling_teleport: config.ceiling_message,
+            horizontal_teleport: config.horizontal_message,
+            kick: config.kick_message,
+        };
+        builder.boundaries(boundaries, messages);
+    }
+
+    if let WorldBorderConfig::Enabled(world_border) = world_border {
+        let border = WorldBorder {
+            center: world_border.center,
+            diameter: world_border.diameter,
+            warning_distance: world_border.warning_distance,
+            warning_time: world_border.warning_time,
+        };
+        if border.diameter <= 0.0 {
+            return Err(ServerStateBuilderError::InvalidWorldBorderDiameter);
+        }
+        let (spawn_x, _, spawn_z) = spawn_position;
+        if !border.contains(spawn_x, spawn_z) {
+            return Err(ServerStateBuilderError::SpawnOutsideWorldBorder);
+        }
+        builder.world_border(border, world_border.teleport_message);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_should_not_apply_a_world_border() {
+        // Given
+        let configs = [Config::default(), toml::from_str::<Config>("").unwrap()];
+
+        for cfg in configs {
+            // When
+            let server_state = build_state(cfg).unwrap();
+
+            // Then
+            assert!(server_state.world_border().is_none());
+        }
+    }
+
+    #[test]
+    fn test_enabled_world_border_should_be_applied() {
+        // Given
+        let cfg: Config = toml::from_str(
+            "[world.world_border]\nenabled = true\ncenter = [0.0, 0.0]\ndiameter = 64.0\nwarning_distance = 5\nwarning_time = 15\nteleport_message = \"\"",
+        )
+        .unwrap();
+
+        // When
+        let server_state = build_state(cfg).unwrap();
+
+        // Then
+        assert!(server_state.world_border().is_some());
+    }
+}
@@ -1,19 +1,28 @@
 use crate::configuration::boss_bar::EnabledBossBarConfig;
+use crate::configuration::messages::MessagesConfig;
+use crate::messages::Messages;
 use crate::server::game_mode::GameMode;
+use crate::server::player_list::PlayerList;
 use base64::engine::general_purpose;
 use base64::{Engine, alphabet, engine};
+use blocks_report::BlockStateLookup;
 use minecraft_packets::play::boss_bar_packet::{BossBarColor, BossBarDivision};
-use minecraft_protocol::prelude::{BinaryReaderError, Dimension};
-use pico_structures::prelude::{Schematic, SchematicError, World, WorldLoadingError};
+use minecraft_protocol::prelude::{BinaryReaderError, Dimension, ProtocolVersion};
+use pico_structures::prelude::{
+    AnvilArea, FlatGenerator, Schematic, SchematicError, SchematicPlacement, World, WorldCache,
+    WorldCacheKey, WorldLoadingError,
+};
 use pico_text_component::prelude::{Component, MiniMessageError, parse_mini_message};
+use regex::RegexSet;
+use registries::get_biome_index;
 use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU32, Ordering};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tracing::debug;
+use tracing::{debug, info, warn};
 
 #[derive(PartialEq, Eq, Default)]
 pub enum ForwardingMode {
@@ -32,10 +41,149 @@ pub enum ForwardingMode {
 #[error("secret key not set")]
 pub struct MisconfiguredForwardingError;
 
+/// Action taken when a player leaves the boundaries.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum BoundaryAction {
+    #[default]
+    TeleportToSpawn,
+    /// Teleports the player back to the last position inside the boundaries
+    PushBack,
+    Kick,
+}
+
+/// Horizontal area players are allowed in, edges included.
+#[derive(Clone, Copy)]
+pub enum HorizontalBoundary {
+    Box { min: (f64, f64), max: (f64, f64) },
+    Radius { center: (f64, f64), radius: f64 },
+}
+
+impl HorizontalBoundary {
+    pub fn contains(&self, x: f64, z: f64) -> bool {
+        match *self {
+            Self::Box { min, max } => (min.0..=max.0).contains(&x) && (min.1..=max.1).contains(&z),
+            Self::Radius { center, radius } => (x - center.0).hypot(z - center.1) <= radius,
+        }
+    }
+}
+
+/// Part of the boundaries a player went past.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Boundary {
+    Floor,
+    Ceiling,
+    Horizontal,
+}
+
 #[derive(Default)]
 pub struct Boundaries {
     pub min_y: i32,
-    pub teleport_message: Option<Component>,
+    pub max_y: Option<i32>,
+    pub vertical_action: BoundaryAction,
+    pub horizontal: Option<(HorizontalBoundary, BoundaryAction)>,
+}
+
+impl Boundaries {
+    /// Returns the boundary the position went past and the action to take.
+    /// The vertical boundaries are checked before the horizontal one.
+    pub fn violation(&self, x: f64, y: f64, z: f64) -> Option<(Boundary, BoundaryAction)> {
+        if y < f64::from(self.min_y) {
+            return Some((Boundary::Floor, self.vertical_action));
+        }
+        if self.max_y.is_some_and(|max_y| y > f64::from(max_y)) {
+            return Some((Boundary::Ceiling, self.vertical_action));
+        }
+        self.horizontal
+            .filter(|(boundary, _)| !boundary.contains(x, z))
+            .map(|(_, action)| (Boundary::Horizontal, action))
+    }
+}
+
+/// Messages sent to the players leaving the boundaries, empty teleport messages are not sent.
+#[derive(Default)]
+pub struct BoundaryMessages {
+    pub floor_teleport: String,
+    pub ceiling_teleport: String,
+    pub horizontal_teleport: String,
+    pub kick: String,
+}
+
+/// Square border around the playable area, sent to the players since 1.8.
+#[derive(Clone, Copy)]
+pub struct WorldBorder {
+    pub center: (f64, f64),
+    pub diameter: f64,
+    pub warning_distance: i32,
+    pub warning_time: i32,
+}
+
+impl WorldBorder {
+    /// Whether the horizontal position is within the border, edges included.
+    pub fn contains(&self, x: f64, z: f64) -> bool {
+        let radius = self.diameter / 2.0;
+        (x - self.center.0).abs() <= radius && (z - self.center.1).abs() <= radius
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Rain,
+    Thunder,
+}
+
+/// Weathers the world goes through in a loop, the same for every player.
+pub struct WeatherCycle {
+    started_at: Instant,
+    schedule: Vec<(Weather, Duration)>,
+}
+
+impl WeatherCycle {
+    pub fn current(&self) -> Weather {
+        self.weather_at(self.started_at.elapsed())
+    }
+
+    /// Returns the weather once the cycle ran for the given duration.
+    pub fn weather_at(&self, elapsed: Duration) -> Weather {
+        let total: f64 = self
+            .schedule
+            .iter()
+            .map(|(_, duration)| duration.as_secs_f64())
+            .sum();
+        let mut remaining = elapsed.as_secs_f64() % total;
+        for (weather, duration) in &self.schedule {
+            if remaining < duration.as_secs_f64() {
+                return *weather;
+            }
+            remaining -= duration.as_secs_f64();
+        }
+        self.schedule
+            .last()
+            .map_or_else(Weather::default, |(weather, _)| *weather)
+    }
+}
+
+/// Range of Minecraft versions allowed to join, both ends included.
+#[derive(Clone, Copy)]
+pub struct VersionRange {
+    pub min: ProtocolVersion,
+    pub max: ProtocolVersion,
+}
+
+impl Default for VersionRange {
+    fn default() -> Self {
+        Self {
+            min: ProtocolVersion::oldest(),
+            max: ProtocolVersion::latest(),
+        }
+    }
+}
+
+impl VersionRange {
+    pub fn contains(self, protocol_version: ProtocolVersion) -> bool {
+        protocol_version.between_inclusive(self.min, self.max)
+    }
 }
 
 #[derive(Default)]
@@ -56,12 +204,19 @@ pub struct BossBar {
 pub struct ServerState {
     forwarding_mode: ForwardingMode,
     spawn_dimension: Dimension,
+    biome: String,
     motd: Component,
     time_world: i64,
     lock_time: bool,
+    weather: Weather,
+    weather_cycle: Option<WeatherCycle>,
     max_players: u32,
-    welcome_message: Option<Component>,
+    player_limit: u32,
+    messages: Messages,
     connected_clients: Arc<AtomicU32>,
+    /// Slots taken by the players logging in or in game
+    reserved_slots: Arc<AtomicU32>,
+    player_list: Arc<PlayerList>,
     show_online_player_count: bool,
     game_mode: GameMode,
     hardcore: bool,
@@ -69,10 +224,13 @@ pub struct ServerState {
     view_distance: i32,
     world: Option<Arc<World>>,
     boundaries: Option<Boundaries>,
+    world_border: Option<WorldBorder>,
     tab_list: Option<TabList>,
     fetch_player_skins: bool,
     boss_bar: Option<BossBar>,
     fav_icon: Option<String>,
+    version_range: VersionRange,
+    blocked_brands: RegexSet,
 }
 
 impl ServerState {
@@ -115,8 +273,27 @@ impl ServerState {
         self.max_players
     }
 
-    pub const fn welcome_message(&self) -> Option<&Component> {
-        self.welcome_message.as_ref()
+    pub const fn messages(&self) -> &Messages {
+        &self.messages
+    }
+
+    /// Takes a player slot, returns false if the player limit is reached.
+    /// A limit of 0 means unlimited.
+    pub fn try_reserve_slot(&self) -> bool {
+        self.reserved_slots
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |reserved_slots| {
+                (self.player_limit == 0 || reserved_slots < self.player_limit)
+                    .then_some(reserved_slots + 1)
+            })
+            .is_ok()
+    }
+
+    pub fn release_slot(&self) {
+        self.reserved_slots.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    pub const fn player_list(&self) -> &Arc<PlayerList> {
+        &self.player_list
     }
 
     /// Returns the current number of connected clients.
@@ -132,6 +309,11 @@ impl ServerState {
         self.spawn_dimension
     }
 
+    /// Biome of the chunks, and of the cells of the world without a biome of their own
+    pub fn biome(&self) -> &str {
+        &self.biome
+    }
+
     pub const fn game_mode(&self) -> GameMode {
         self.game_mode
     }
@@ -160,10 +342,25 @@ impl ServerState {
         self.lock_time
     }
 
+    /// Returns the current weather, following the weather cycle when configured.
+    pub fn weather(&self) -> Weather {
+        self.weather_cycle
+            .as_ref()
+            .map_or(self.weather, WeatherCycle::current)
+    }
+
+    pub const fn weather_cycle(&self) -> Option<&WeatherCycle> {
+        self.weather_cycle.as_ref()
+    }
+
     pub const fn boundaries(&self) -> Option<&Boundaries> {
         self.boundaries.as_ref()
     }
 
+    pub const fn world_border(&self) -> Option<&WorldBorder> {
+        self.world_border.as_ref()
+    }
+
     pub const fn tab_list(&self) -> Option<&TabList> {
         self.tab_list.as_ref()
     }
@@ -180,6 +377,15 @@ impl ServerState {
         self.fav_icon.clone()
     }
 
+    /// Returns true if the client brand matches one of the blocked patterns.
+    pub fn is_brand_blocked(&self, brand: &str) -> bool {
+        self.blocked_brands.is_match(brand)
+    }
+
+    pub const fn version_range(&self) -> VersionRange {
+        self.version_range
+    }
+
     pub fn increment(&self) {
         self.connected_clients.fetch_add(1, Ordering::SeqCst);
     }
@@ -194,22 +400,35 @@ impl ServerState {
 pub struct ServerStateBuilder {
     forwarding_mode: ForwardingMode,
     dimension: Option<Dimension>,
+    biome: Option<String>,
     time_world: i64,
     lock_time: bool,
+    weather: Weather,
+    weather_cycle: Vec<(Weather, Duration)>,
     description_text: String,
     max_players: u32,
+    player_limit: u32,
     welcome_message: String,
+    messages: MessagesConfig,
     show_online_player_count: bool,
     game_mode: GameMode,
     hardcore: bool,
     spawn_position: (f64, f64, f64),
     view_distance: i32,
-    schematic_file_path: String,
+    schematics: Vec<SchematicPlacement>,
+    anvil_world: Option<(PathBuf, AnvilArea)>,
+    generator: Option<FlatGenerator>,
+    world_cache: bool,
     boundaries: Option<Boundaries>,
+    boundary_messages: BoundaryMessages,
+    world_border: Option<WorldBorder>,
+    world_border_teleport_message: String,
     tab_list: Option<TabList>,
     fetch_player_skins: bool,
     boss_bar: Option<BossBar>,
     fav_icon: Option<String>,
+    version_range: VersionRange,
+    blocked_brands: RegexSet,
 }
 
 #[derive(Debug, Error)]
@@ -222,13 +441,33 @@ pub enum ServerStateBuilderError {
     WorldLoading(#[from] WorldLoadingError),
     #[error(transparent)]
     MiniMessage(#[from] MiniMessageError),
-    #[error("the configured spawn position Y is below the configured minimum Y position")]
+    #[error(
+        "a schematic file or a world generator and an Anvil world cannot be loaded at the same time"
+    )]
+    ConflictingWorldSources,
+    #[error("the configured spawn position is outside of the configured boundaries")]
     InvalidSpawnPosition,
+    #[error("the configured world border diameter must be positive")]
+    InvalidWorldBorderDiameter,
+    #[error("the configured spawn position is outside of the configured world border")]
+    SpawnOutsideWorldBorder,
+    #[error("every weather of the configured weather cycle must last at least a second")]
+    InvalidWeatherCycle,
+    #[error("the configured minimum version {0} is newer than the configured maximum version {1}")]
+    InvalidVersionRange(&'static str, &'static str),
+    #[error("unknown biome {0}, it must be a biome of Minecraft {1}")]
+    UnknownBiome(String, &'static str),
+    #[error("the block entity types of Minecraft {0} are missing from the bundled reports")]
+    MissingBlockEntityTypes(&'static str),
+    #[error("invalid blocked brand pattern: {0}")]
+    InvalidBrandPattern(#[from] regex::Error),
     #[error(transparent)]
     Io(#[from] std::io::Error),
 }
 
 impl ServerStateBuilder {
+    const DEFAULT_BIOME: &'static str = "minecraft:plains";
+
     pub fn enable_legacy_forwarding(&mut self) -> &mut Self {
         self.forwarding_mode = ForwardingMode::Legacy;
         self
@@ -258,6 +497,22 @@ impl ServerStateBuilder {
         self
     }
 
+    /// Set the biome of the world, which must exist in the latest version of the game
+    pub fn biome<S>(&mut self, biome: S) -> Result<&mut Self, ServerStateBuilderError>
+    where
+        S: Into<String>,
+    {
+        let biome = biome.into();
+        if get_biome_index(ProtocolVersion::latest(), &biome).is_none() {
+            return Err(ServerStateBuilderError::UnknownBiome(
+                biome,
+                ProtocolVersion::latest().humanize(),
+            ));
+        }
+        self.biome = Some(biome);
+        Ok(self)
+    }
+
     /// Set the time of the world
     pub const fn time_world(&mut self, time_world: i64) -> &mut Self {
         self.time_world = time_world;
@@ -269,6 +524,23 @@ impl ServerStateBuilder {
         self
     }
 
+    pub const fn weather(&mut self, weather: Weather) -> &mut Self {
+        self.weather = weather;
+        self
+    }
+
+    /// Replaces the weather with a cycle, an empty schedule keeps the weather.
+    pub fn weather_cycle(
+        &mut self,
+        schedule: Vec<(Weather, Duration)>,
+    ) -> Result<&mut Self, ServerStateBuilderError> {
+        if schedule.iter().any(|(_, duration)| duration.is_zero()) {
+            return Err(ServerStateBuilderError::InvalidWeatherCycle);
+        }
+        self.weather_cycle = schedule;
+        Ok(self)
+    }
+
     pub fn description_text<S>(&mut self, text: S) -> &mut Self
     where
         S: Into<String>,
@@ -282,6 +554,12 @@ impl ServerStateBuilder {
         self
     }
 
+    /// Maximum number of players connected at the same time, 0 means unlimited
+    pub const fn player_limit(&mut self, player_limit: u32) -> &mut Self {
+        self.player_limit = player_limit;
+        self
+    }
+
     pub fn welcome_message<S>(&mut self, message: S) -> &mut Self
     where
         S: Into<String>,
@@ -290,6 +568,11 @@ impl ServerStateBuilder {
         self
     }
 
+    pub fn messages(&mut self, messages: MessagesConfig) -> &mut Self {
+        self.messages = messages;
+        self
+    }
+
     pub const fn show_online_player_count(&mut self, show: bool) -> &mut Self {
         self.show_online_player_count = show;
         self
@@ -315,8 +598,30 @@ impl ServerStateBuilder {
         self
     }
 
-    pub fn schematic(&mut self, schematic_file_path: String) -> &mut Self {
-        self.schematic_file_path = schematic_file_path;
+    /// Place a schematic in the world, over the schematics placed before
+    pub fn schematic(&mut self, placement: SchematicPlacement) -> &mut Self {
+        self.schematics.push(placement);
+        self
+    }
+
+    /// Load an area of a vanilla world instead of a schematic
+    pub fn anvil_world<P>(&mut self, world_folder: P, area: AnvilArea) -> &mut Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.anvil_world = Some((world_folder.into(), area));
+        self
+    }
+
+    /// Save the loaded world next to its sources, and load it from there while they are unchanged
+    pub const fn world_cache(&mut self, world_cache: bool) -> &mut Self {
+        self.world_cache = world_cache;
+        self
+    }
+
+    /// Generate layers of blocks below the schematics
+    pub fn generator(&mut self, generator: FlatGenerator) -> &mut Self {
+        self.generator = Some(generator);
         self
     }
 
@@ -336,20 +641,19 @@ impl ServerStateBuilder {
         Ok(self)
     }
 
-    pub fn boundaries<S>(
-        &mut self,
-        min_y: i32,
-        teleport_message: S,
-    ) -> Result<&mut Self, ServerStateBuilderError>
+    pub fn boundaries(&mut self, boundaries: Boundaries, messages: BoundaryMessages) -> &mut Self {
+        self.boundaries = Some(boundaries);
+        self.boundary_messages = messages;
+        self
+    }
+
+    pub fn world_border<S>(&mut self, world_border: WorldBorder, teleport_message: S) -> &mut Self
     where
-        S: AsRef<str>,
+        S: Into<String>,
     {
-        let teleport_message = optional_mini_message(teleport_message.as_ref())?;
-        self.boundaries = Some(Boundaries {
-            min_y,
-            teleport_message,
-        });
-        Ok(self)
+        self.world_border = Some(world_border);
+        self.world_border_teleport_message = teleport_message.into();
+        self
     }
 
     pub fn fav_icon<P>(&mut self, file_path: P) -> Result<&mut Self, ServerStateBuilderError>
@@ -387,29 +691,77 @@ impl ServerStateBuilder {
         Ok(self)
     }
 
+    pub fn version_range(
+        &mut self,
+        min_version: ProtocolVersion,
+        max_version: ProtocolVersion,
+    ) -> Result<&mut Self, ServerStateBuilderError> {
+        if min_version > max_version {
+            return Err(ServerStateBuilderError::InvalidVersionRange(
+                min_version.humanize(),
+                max_version.humanize(),
+            ));
+        }
+        self.version_range = VersionRange {
+            min: min_version,
+            max: max_version,
+        };
+        Ok(self)
+    }
+
+    pub fn blocked_brands(
+        &mut self,
+        patterns: &[String],
+    ) -> Result<&mut Self, ServerStateBuilderError> {
+        self.blocked_brands = RegexSet::new(patterns)?;
+        Ok(self)
+    }
+
     /// Finish building, returning an error if any required fields are missing.
     pub fn build(self) -> Result<ServerState, ServerStateBuilderError> {
-        let world = if self.schematic_file_path.is_empty() {
-            None
-        } else {
-            let schematic = time_operation("Loading schematic", || {
-                let internal_mapping = blocks_report::load_internal_mapping()?;
-                let schematic_file_path = PathBuf::from(self.schematic_file_path);
-                Schematic::load_schematic_file(&schematic_file_path, &internal_mapping)
-            })?;
-            let world = time_operation("Loading world", || World::from_schematic(&schematic))?;
+        let has_world =
+            !self.schematics.is_empty() || self.anvil_world.is_some() || self.generator.is_some();
+        let world = if has_world {
+            self.check_block_entity_types()?;
+            let world = self.load_cached_world()?;
+            info!("World loaded: {}", world.memory_stats());
+            for biome in world.biome_names() {
+                if get_biome_index(ProtocolVersion::latest(), biome).is_none() {
+                    warn!(
+                        "Unknown biome {biome} in the world, the biome of the world is used instead"
+                    );
+                }
+            }
             Some(Arc::new(world))
+        } else {
+            None
         };
 
         Ok(ServerState {
             forwarding_mode: self.forwarding_mode,
             spawn_dimension: self.dimension.unwrap_or_default(),
+            biome: self
+                .biome
+                .unwrap_or_else(|| Self::DEFAULT_BIOME.to_string()),
             motd: parse_mini_message(&self.description_text)?,
             time_world: self.time_world,
             lock_time: self.lock_time,
+            weather: self.weather,
+            weather_cycle: (!self.weather_cycle.is_empty()).then(|| WeatherCycle {
+                started_at: Instant::now(),
+                schedule: self.weather_cycle,
+            }),
             max_players: self.max_players,
-            welcome_message: optional_mini_message(&self.welcome_message)?,
+            player_limit: self.player_limit,
+            messages: Messages::new(
+                self.messages,
+                &self.welcome_message,
+                &self.boundary_messages,
+                &self.world_border_teleport_message,
+            )?,
             connected_clients: Arc::new(AtomicU32::new(0)),
+            reserved_slots: Arc::new(AtomicU32::new(0)),
+            player_list: Arc::new(PlayerList::default()),
             show_online_player_count: self.show_online_player_count,
             game_mode: self.game_mode,
             hardcore: self.hardcore,
@@ -417,21 +769,113 @@ impl ServerStateBuilder {
             view_distance: self.view_distance,
             world,
             boundaries: self.boundaries,
+            world_border: self.world_border,
             tab_list: self.tab_list,
             fetch_player_skins: self.fetch_player_skins,
             boss_bar: self.boss_bar,
             fav_icon: self.fav_icon,
+            version_range: self.version_range,
+            blocked_brands: self.blocked_brands,
         })
     }
-}
 
-fn optional_mini_message(content: &str) -> Result<Option<Component>, MiniMessageError> {
-    let component = if content.is_empty() {
-        None
-    } else {
-        Some(parse_mini_message(content)?)
-    };
-    Ok(component)
+    /// Loads the world from its cache while its sources are unchanged, and refreshes the cache otherwise.
+    fn load_cached_world(&self) -> Result<World, ServerStateBuilderError> {
+        let cache = if self.world_cache {
+            self.find_world_cache()
+        } else {
+            None
+        };
+        let cached_world = cache.as_ref().and_then(|cache| {
+            match time_operation("Reading world cache", || cache.load()) {
+                Ok(world) => world,
+                Err(error) => {
+                    warn!("Ignoring world cache {}: {error}", cache.path().display());
+                    None
+                }
+            }
+        });
+        if let Some(world) = cached_world {
+            return Ok(world);
+        }
+
+        let world = self.load_world()?;
+        if let Some(cache) = &cache
+            && let Err(error) = cache.save(&world)
+        {
+            warn!(
+                "Failed to write world cache {}: {error}",
+                cache.path().display()
+            );
+        }
+        Ok(world)
+    }
+
+    fn load_world(&self) -> Result<World, ServerStateBuilderError> {
+        let internal_mapping = blocks_report::load_internal_mapping()?;
+        let block_properties = blocks_report::load_block_properties()?;
+        let legacy_block_names = blocks_report::load_legacy_block_names()?;
+        let block_state_lookup =
+            BlockStateLookup::new(&internal_mapping, &block_properties, &legacy_block_names);
+        let schematic = if self.schematics.is_empty() && self.anvil_world.is_none() {
+            None
+        } else {
+            Some(time_operation("Loading schematic", || {
+                if let Some((world_folder, area)) = &self.anvil_world {
+                    return Schematic::load_anvil_world(world_folder, area, &block_state_lookup);
+                }
+                Schematic::load_placements(&self.schematics, &block_state_lookup)
+            })?)
+        };
+        let world = time_operation("Loading world", || match (&self.generator, &schematic) {
+            (Some(generator), schematic) => {
+                World::generate(generator, schematic.as_ref(), &block_state_lookup)
+            }
+            (None, Some(schematic)) => World::from_schematic(schematic, &block_state_lookup),
+            (None, None) => unreachable!("a world source is configured"),
+        })?;
+        Ok(world)
+    }
+
+    /// Since 1.18 included, block entities are sent with the ID of their type,
+    /// so every allowed version needs the registry of block entity types.
+    fn check_block_entity_types(&self) -> Result<(), ServerStateBuilderError> {
+        let missing_version = ProtocolVersion::ALL_VERSION
+            .iter()
+            .find(|protocol_version| {
+                !protocol_version.is_any()
+                    && protocol_version.is_after_inclusive(ProtocolVersion::V1_18)
+                    && self.version_range.contains(**protocol_version)
+                    && blocks_report::get_block_entity_types(**protocol_version).is_err()
+            });
+        missing_version.map_or(Ok(()), |protocol_version| {
+            Err(ServerStateBuilderError::MissingBlockEntityTypes(
+                protocol_version.humanize(),
+            ))
+        })
+    }
+
+    /// The cache is stored next to the first schematic, or inside the folder of the Anvil world.
+    /// Worlds made of generated layers only are fast to build and are not cached.
+    fn find_world_cache(&self) -> Option<WorldCache> {
+        let mut key = WorldCacheKey::new(blocks_report::block_data_version());
+        let path = if let Some((world_folder, area)) = &self.anvil_world {
+            key.anvil_world(world_folder, area).ok()?;
+            world_folder.join("picolimbo.cache")
+        } else {
+            let mut path = self.schematics.first()?.path.clone().into_os_string();
+            path.push(".cache");
+            for placement in &self.schematics {
+                // Missing files are reported when loading the schematics
+                key.schematic(placement).ok()?;
+            }
+            PathBuf::from(path)
+        };
+        if let Some(generator) = &self.generator {
+            key.generator(generator);
+        }
+        Some(WorldCache::new(path, key))
+    }
 }
 
 fn format_duration(duration: Duration) -> String {
@@ -455,3 +899,62 @@ where
     debug!("Time elapsed: {}", format_duration(elapsed));
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weather_cycle_should_loop() {
+        // Given
+        let mut builder = ServerState::builder();
+        builder
+            .weather_cycle(vec![
+                (Weather::Clear, Duration::from_mins(10)),
+                (Weather::Rain, Duration::from_mins(5)),
+            ])
+            .unwrap();
+        let server_state = builder.build().unwrap();
+        let cycle = server_state.weather_cycle().unwrap();
+
+        // When
+        let weathers =
+            [0, 599, 600, 899, 900].map(|secs| cycle.weather_at(Duration::from_secs(secs)));
+
+        // Then
+        assert_eq!(
+            weathers,
+            [
+                Weather::Clear,
+                Weather::Clear,
+                Weather::Rain,
+                Weather::Rain,
+                Weather::Clear
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weather_cycle_should_reject_empty_durations() {
+        // Given
+        let mut builder = ServerState::builder();
+
+        // When
+        let result = builder.weather_cycle(vec![(Weather::Rain, Duration::ZERO)]);
+
+        // Then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_block_entity_types_are_bundled_for_every_version() {
+        // Given
+        let builder = ServerState::builder();
+
+        // When
+        let result = builder.check_block_entity_types();
+
+        // Then
+        assert!(result.is_ok());
+    }
+}